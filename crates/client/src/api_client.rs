@@ -3,7 +3,8 @@ use reqwest::Client;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::client_keys::{sign_request, KeyPair};
+use crate::client_keys::{body_hash, sign_request_digest, KeyPair};
+use crate::multipart::Boundary;
 
 #[derive(Debug, Clone)]
 pub struct ApiClient {
@@ -61,11 +62,24 @@ impl ApiClient {
 
     fn apply_signing(
         &self,
-        mut rb: reqwest::RequestBuilder,
+        rb: reqwest::RequestBuilder,
         method: &str,
         url: &str,
         path: &str,
         body: &[u8],
+    ) -> reqwest::RequestBuilder {
+        self.apply_signing_digest(rb, method, url, path, &body_hash(body))
+    }
+
+    /// Sign a request whose body hash is already known, for bodies that are
+    /// streamed rather than held in memory.
+    fn apply_signing_digest(
+        &self,
+        mut rb: reqwest::RequestBuilder,
+        method: &str,
+        url: &str,
+        path: &str,
+        body_hash: &str,
     ) -> reqwest::RequestBuilder {
         if let (Some(keys), Some(handle), Some(domain)) = (&self.keys, &self.handle, &self.domain) {
             let path_only = if let Ok(u) = reqwest::Url::parse(url) {
@@ -74,7 +88,7 @@ impl ApiClient {
                 path.split('?').next().unwrap_or(path).to_string()
             };
 
-            if let Some(headers) = sign_request(method, &path_only, body, keys, handle, domain) {
+            if let Some(headers) = sign_request_digest(method, &path_only, body_hash, keys, handle, domain) {
                 rb = rb.header("X-OFSCP-Actor", headers.actor);
                 rb = rb.header("X-OFSCP-Timestamp", headers.timestamp);
                 rb = rb.header(
@@ -271,6 +285,16 @@ impl ApiClient {
         .await
     }
 
+    pub async fn update_channel(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+        req: &rorumall_shared::UpdateChannelRequest,
+    ) -> Result<rorumall_shared::Channel, ApiError> {
+        self.patch_json(&format!("/api/groups/{}/channels/{}", group_id, channel_id), req)
+            .await
+    }
+
     pub async fn create_group(
         &self,
         name: &str,
@@ -283,6 +307,71 @@ impl ApiClient {
         self.post_json("/api/groups", &body).await
     }
 
+    /// Groups the provider lists publicly, filtered by `query` when it isn't empty.
+    pub async fn list_public_groups(
+        &self,
+        query: &str,
+        cursor: Option<&str>,
+    ) -> Result<rorumall_shared::PagedResponse<rorumall_shared::Group>, ApiError> {
+        let mut path = "/api/groups?discoverability=public".to_string();
+        if !query.is_empty() {
+            path.push_str(&format!("&q={}", urlencoding::encode(query)));
+        }
+        if let Some(cursor) = cursor {
+            path.push_str(&format!("&cursor={}", urlencoding::encode(cursor)));
+        }
+        self.get_json(&path).await
+    }
+
+    pub async fn join_group(
+        &self,
+        group_id: &str,
+        req: &rorumall_shared::JoinGroupRequest,
+    ) -> Result<rorumall_shared::JoinGroupResponse, ApiError> {
+        self.post_json(&format!("/api/groups/{}/join", group_id), req).await
+    }
+
+    pub async fn list_invites(&self, group_id: &str) -> Result<Vec<rorumall_shared::GroupInvite>, ApiError> {
+        self.get_json(&format!("/api/groups/{}/invites", group_id)).await
+    }
+
+    pub async fn create_invite(
+        &self,
+        group_id: &str,
+        req: &rorumall_shared::CreateInviteRequest,
+    ) -> Result<rorumall_shared::GroupInvite, ApiError> {
+        self.post_json(&format!("/api/groups/{}/invites", group_id), req).await
+    }
+
+    pub async fn revoke_invite(&self, group_id: &str, code: &str) -> Result<(), ApiError> {
+        self.delete(&format!("/api/groups/{}/invites/{}", group_id, urlencoding::encode(code)))
+            .await
+    }
+
+    /// The group behind an invite code, for the user to look at before joining.
+    pub async fn get_invite(&self, code: &str) -> Result<rorumall_shared::InvitePreview, ApiError> {
+        self.get_json(&format!("/api/invites/{}", urlencoding::encode(code))).await
+    }
+
+    /// Where the user's request to join `group_id` stands.
+    pub async fn join_status(&self, group_id: &str) -> Result<rorumall_shared::JoinGroupResponse, ApiError> {
+        self.get_json(&format!("/api/groups/{}/join", group_id)).await
+    }
+
+    /// Record a group, possibly on another provider, in the user's group list.
+    pub async fn add_joined_group(
+        &self,
+        user_id: &str,
+        req: &rorumall_shared::AddJoinedGroupRequest,
+    ) -> Result<rorumall_shared::UserJoinedGroup, ApiError> {
+        self.post_json(&format!("/api/users/{}/groups", urlencoding::encode(user_id)), req)
+            .await
+    }
+
+    pub async fn get_group(&self, group_id: &str) -> Result<rorumall_shared::Group, ApiError> {
+        self.get_json(&format!("/api/groups/{}", group_id)).await
+    }
+
     pub async fn list_group_members(
         &self,
         group_id: &str,
@@ -290,6 +379,14 @@ impl ApiClient {
         self.get_json(&format!("/api/groups/{}/members", group_id)).await
     }
 
+    pub async fn add_group_member(
+        &self,
+        group_id: &str,
+        req: &rorumall_shared::AddMemberRequest,
+    ) -> Result<rorumall_shared::GroupMember, ApiError> {
+        self.post_json(&format!("/api/groups/{}/members", group_id), req).await
+    }
+
     pub async fn remove_group_member(&self, group_id: &str, user_id: &str) -> Result<(), ApiError> {
         self.delete(&format!(
             "/api/groups/{}/members/{}",
@@ -369,6 +466,198 @@ impl ApiClient {
         self.delete(&format!("/api/groups/{}/roles/{}", group_id, role_id)).await
     }
 
+    pub async fn get_thread(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+        message_id: &str,
+    ) -> Result<rorumall_shared::MessagesPage, ApiError> {
+        self.get_json(&format!(
+            "/api/groups/{}/channels/{}/messages/{}/thread",
+            group_id, channel_id, message_id
+        ))
+        .await
+    }
+
+    /// One page of a channel's history; pass the previous page's
+    /// `next_cursor` to continue.
+    pub async fn list_messages(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> Result<rorumall_shared::MessagesPage, ApiError> {
+        let mut path = format!("/api/groups/{}/channels/{}/messages?limit={}", group_id, channel_id, limit);
+        if let Some(cursor) = cursor {
+            path.push_str(&format!("&cursor={}", urlencoding::encode(cursor)));
+        }
+        self.get_json(&path).await
+    }
+
+    /// Specific messages of a channel by id, in one request.
+    pub async fn get_messages_by_id(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+        message_ids: &[String],
+    ) -> Result<rorumall_shared::MessagesPage, ApiError> {
+        let ids: Vec<String> = message_ids.iter().map(|id| urlencoding::encode(id).into_owned()).collect();
+        self.get_json(&format!(
+            "/api/groups/{}/channels/{}/messages?ids={}",
+            group_id,
+            channel_id,
+            ids.join(",")
+        ))
+        .await
+    }
+
+    /// The stretch of a channel's history around `message_id`, for jumping
+    /// to a message that isn't loaded.
+    pub async fn list_messages_around(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+        message_id: &str,
+        limit: u32,
+    ) -> Result<rorumall_shared::MessagesPage, ApiError> {
+        self.get_json(&format!(
+            "/api/groups/{}/channels/{}/messages?around={}&limit={}",
+            group_id,
+            channel_id,
+            urlencoding::encode(message_id),
+            limit
+        ))
+        .await
+    }
+
+    pub async fn update_message(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+        message_id: &str,
+        req: &rorumall_shared::UpdateMessageRequest,
+    ) -> Result<rorumall_shared::ChannelMessage, ApiError> {
+        self.patch_json(
+            &format!("/api/groups/{}/channels/{}/messages/{}", group_id, channel_id, message_id),
+            req,
+        )
+        .await
+    }
+
+    pub async fn list_pins(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+    ) -> Result<rorumall_shared::ListPinsResponse, ApiError> {
+        self.get_json(&format!("/api/groups/{}/channels/{}/pins", group_id, channel_id))
+            .await
+    }
+
+    pub async fn pin_message(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+        message_id: &str,
+    ) -> Result<rorumall_shared::ChannelPin, ApiError> {
+        self.put_json(
+            &format!("/api/groups/{}/channels/{}/pins/{}", group_id, channel_id, message_id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    pub async fn unpin_message(&self, group_id: &str, channel_id: &str, message_id: &str) -> Result<(), ApiError> {
+        self.delete(&format!("/api/groups/{}/channels/{}/pins/{}", group_id, channel_id, message_id))
+            .await
+    }
+
+    pub async fn add_reaction(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+        message_id: &str,
+        req: &rorumall_shared::AddReactionRequest,
+    ) -> Result<rorumall_shared::Reaction, ApiError> {
+        self.post_json(
+            &format!("/api/groups/{}/channels/{}/messages/{}/reactions", group_id, channel_id, message_id),
+            req,
+        )
+        .await
+    }
+
+    pub async fn remove_reaction(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+        message_id: &str,
+        reaction_id: &str,
+    ) -> Result<(), ApiError> {
+        self.delete(&format!(
+            "/api/groups/{}/channels/{}/messages/{}/reactions/{}",
+            group_id, channel_id, message_id, reaction_id
+        ))
+        .await
+    }
+
+    pub async fn list_group_emoji(
+        &self,
+        group_id: &str,
+    ) -> Result<rorumall_shared::ListGroupEmojiResponse, ApiError> {
+        self.get_json(&format!("/api/groups/{}/emoji", group_id)).await
+    }
+
+    pub async fn create_group_emoji(
+        &self,
+        group_id: &str,
+        req: &rorumall_shared::CreateGroupEmojiRequest,
+    ) -> Result<rorumall_shared::GroupEmoji, ApiError> {
+        self.post_json(&format!("/api/groups/{}/emoji", group_id), req).await
+    }
+
+    pub async fn delete_group_emoji(&self, group_id: &str, shortcode: &str) -> Result<(), ApiError> {
+        self.delete(&format!("/api/groups/{}/emoji/{}", group_id, urlencoding::encode(shortcode)))
+            .await
+    }
+
+    pub async fn get_discovery(&self) -> Result<rorumall_shared::DiscoveryDocument, ApiError> {
+        self.get_json("/.well-known/ofscp-provider").await
+    }
+
+    pub async fn schedule_message(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+        req: &rorumall_shared::ScheduleMessageRequest,
+    ) -> Result<rorumall_shared::ScheduledMessageResponse, ApiError> {
+        self.post_json(&format!("/api/groups/{}/channels/{}/scheduled", group_id, channel_id), req)
+            .await
+    }
+
+    pub async fn update_scheduled_message(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+        scheduled_id: &str,
+        req: &rorumall_shared::UpdateScheduledMessageRequest,
+    ) -> Result<rorumall_shared::ScheduledMessageResponse, ApiError> {
+        self.patch_json(
+            &format!("/api/groups/{}/channels/{}/scheduled/{}", group_id, channel_id, scheduled_id),
+            req,
+        )
+        .await
+    }
+
+    pub async fn cancel_scheduled_message(
+        &self,
+        group_id: &str,
+        channel_id: &str,
+        scheduled_id: &str,
+    ) -> Result<(), ApiError> {
+        self.delete(&format!("/api/groups/{}/channels/{}/scheduled/{}", group_id, channel_id, scheduled_id))
+            .await
+    }
+
     pub async fn get_bytes(&self, path: &str) -> Result<Vec<u8>, ApiError> {
         let url = self.url(path);
         let rb = self.client.get(&url);
@@ -391,22 +680,56 @@ impl ApiClient {
             .map_err(|e| ApiError::Network(format!("failed to read bytes: {e}")))
     }
 
+    /// GET `path` and hand back the response unread, so large bodies can be
+    /// streamed with `Response::chunk`.
+    pub async fn get_response(&self, path: &str) -> Result<reqwest::Response, ApiError> {
+        let url = self.url(path);
+        let rb = self.client.get(&url);
+        let rb = self.apply_signing(rb, "GET", &url, path, &[]);
+
+        let resp = rb.send().await.map_err(|e| ApiError::Network(e.to_string()))?;
+        if !resp.status().is_success() {
+            let status = resp.status().as_u16();
+            let text = resp
+                .text()
+                .await
+                .map_err(|e| ApiError::Network(format!("failed to read body: {e}")))?;
+            return Err(ApiError::Http { status, body: text });
+        }
+        Ok(resp)
+    }
+
     pub async fn upload_file(
         &self,
         file_data: Vec<u8>,
         filename: &str,
         content_type: &str,
+    ) -> Result<rorumall_shared::Attachment, ApiError> {
+        let boundary = Boundary::for_data(&file_data);
+        let body = boundary.encode(filename, content_type, &file_data);
+        self.post_multipart("/api/uploads", &boundary, body).await
+    }
+
+    /// Upload a multipart body built around `boundary` and streamed from
+    /// disk. `body_hash` must be the hash of exactly the bytes streamed.
+    pub async fn upload_stream(
+        &self,
+        body: reqwest::Body,
+        boundary: &Boundary,
+        content_length: u64,
+        body_hash: &str,
     ) -> Result<rorumall_shared::Attachment, ApiError> {
         let url = self.url("/api/uploads");
-        let part = reqwest::multipart::Part::bytes(file_data)
-            .file_name(filename.to_string())
-            .mime_str(content_type)
-            .map_err(|e| ApiError::Network(format!("Invalid MIME type: {}", e)))?;
-        let form = reqwest::multipart::Form::new().part("file", part);
-        let rb = self.client.post(&url).multipart(form);
-        let rb = self.apply_signing(rb, "POST", &url, "/api/uploads", &[]);
+        let rb = self.client.post(&url);
+        let rb = self.apply_signing_digest(rb, "POST", &url, "/api/uploads", body_hash);
 
-        let resp = rb.send().await.map_err(|e| ApiError::Network(e.to_string()))?;
+        let resp = rb
+            .header("Content-Type", boundary.content_type())
+            .header("Content-Length", content_length)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
         let status = resp.status().as_u16();
         let is_success = resp.status().is_success();
         let text = resp.text().await.map_err(|e| ApiError::Network(e.to_string()))?;
@@ -417,6 +740,62 @@ impl ApiClient {
         serde_json::from_str(&text).map_err(|e| ApiError::Deserialize(e.to_string()))
     }
 
+    pub async fn create_upload_session(
+        &self,
+        req: &rorumall_shared::CreateUploadSessionRequest,
+    ) -> Result<rorumall_shared::UploadSession, ApiError> {
+        self.post_json("/api/uploads/sessions", req).await
+    }
+
+    pub async fn get_upload_session(&self, session_id: &str) -> Result<rorumall_shared::UploadSession, ApiError> {
+        self.get_json(&format!("/api/uploads/sessions/{}", session_id)).await
+    }
+
+    /// Send the bytes of an upload session starting at `offset`.
+    pub async fn put_upload_chunk(
+        &self,
+        session_id: &str,
+        offset: u64,
+        total: u64,
+        chunk: Vec<u8>,
+    ) -> Result<rorumall_shared::UploadSession, ApiError> {
+        let path = format!("/api/uploads/sessions/{}", session_id);
+        let url = self.url(&path);
+        let end = offset + chunk.len() as u64;
+        let rb = self.client.put(&url);
+        let rb = self.apply_signing(rb, "PUT", &url, &path, &chunk);
+
+        let resp = rb
+            .header("Content-Type", "application/octet-stream")
+            .header("Content-Range", format!("bytes {}-{}/{}", offset, end.saturating_sub(1), total))
+            .body(chunk)
+            .send()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+
+        let status = resp.status().as_u16();
+        let is_success = resp.status().is_success();
+        let text = resp.text().await.map_err(|e| ApiError::Network(e.to_string()))?;
+
+        if !is_success {
+            return Err(ApiError::Http { status, body: text });
+        }
+        serde_json::from_str(&text).map_err(|e| ApiError::Deserialize(e.to_string()))
+    }
+
+    pub async fn complete_upload_session(
+        &self,
+        session_id: &str,
+        req: &rorumall_shared::CompleteUploadSessionRequest,
+    ) -> Result<rorumall_shared::Attachment, ApiError> {
+        self.post_json(&format!("/api/uploads/sessions/{}/complete", session_id), req)
+            .await
+    }
+
+    pub async fn cancel_upload_session(&self, session_id: &str) -> Result<(), ApiError> {
+        self.delete(&format!("/api/uploads/sessions/{}", session_id)).await
+    }
+
     pub async fn upload_avatar(
         &self,
         file_data: Vec<u8>,
@@ -437,16 +816,29 @@ impl ApiClient {
         filename: &str,
         content_type: &str,
     ) -> Result<rorumall_shared::Upload, ApiError> {
-        let url = self.url("/api/uploads");
-        let part = reqwest::multipart::Part::bytes(file_data)
-            .file_name(filename.to_string())
-            .mime_str(content_type)
-            .map_err(|e| ApiError::Network(format!("Invalid MIME type: {}", e)))?;
-        let form = reqwest::multipart::Form::new().part("file", part);
-        let rb = self.client.post(&url).multipart(form);
-        let rb = self.apply_signing(rb, "POST", &url, "/api/uploads", &[]);
+        let boundary = Boundary::for_data(&file_data);
+        let body = boundary.encode(filename, content_type, &file_data);
+        self.post_multipart("/api/uploads", &boundary, body).await
+    }
 
-        let resp = rb.send().await.map_err(|e| ApiError::Network(e.to_string()))?;
+    /// POST a multipart body built around `boundary`, signed over its exact
+    /// bytes like any JSON request.
+    async fn post_multipart<TRes: DeserializeOwned>(
+        &self,
+        path: &str,
+        boundary: &Boundary,
+        body: Vec<u8>,
+    ) -> Result<TRes, ApiError> {
+        let url = self.url(path);
+        let rb = self.client.post(&url);
+        let rb = self.apply_signing(rb, "POST", &url, path, &body);
+
+        let resp = rb
+            .header("Content-Type", boundary.content_type())
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
         let status = resp.status().as_u16();
         let is_success = resp.status().is_success();
         let text = resp.text().await.map_err(|e| ApiError::Network(e.to_string()))?;
@@ -1,6 +1,6 @@
 use rinch::prelude::*;
 use crate::navigation::{init_nav, get_nav, AppRoute};
-use crate::stores::{AuthStore, GroupsStore, MessagesStore, MembersStore, PresenceStore, ProfileStore};
+use crate::stores::{AuthStore, DraftsStore, EmojiStore, ExportStore, GroupsStore, InvitesStore, LightboxStore, LinkPreviewsStore, MediaStore, MessagesStore, MembersStore, PinsStore, PresenceStore, ProfileStore, ScheduledStore, SearchStore, SettingsStore};
 
 #[component]
 pub fn app() -> NodeHandle {
@@ -9,11 +9,28 @@ pub fn app() -> NodeHandle {
 
     // Initialize all stores
     AuthStore::init();
+    SettingsStore::init();
     GroupsStore::init();
     MessagesStore::init();
     MembersStore::init();
     PresenceStore::init();
     ProfileStore::init();
+    SearchStore::init();
+    DraftsStore::init();
+    LinkPreviewsStore::init();
+    PinsStore::init();
+    ScheduledStore::init();
+    EmojiStore::init();
+    ExportStore::init();
+    LightboxStore::init();
+    MediaStore::init();
+    InvitesStore::init();
+
+    // Keys go to the open lightbox, or else the composer
+    crate::keyboard::install();
+
+    // Built-in slash commands
+    crate::commands::init();
 
     let nav = get_nav();
 
@@ -33,13 +50,13 @@ pub fn app() -> NodeHandle {
                     {crate::views::register::register_view(__scope)}
                 }
             }
-            if matches!(nav.get().clone(), AppRoute::Home | AppRoute::Group { .. } | AppRoute::Channel { .. }) {
+            if matches!(nav.get().clone(), AppRoute::Home | AppRoute::Group { .. } | AppRoute::Channel { .. } | AppRoute::Article { .. } | AppRoute::Tag { .. } | AppRoute::Search | AppRoute::Scheduled | AppRoute::Discover | AppRoute::UserProfile { .. }) {
                 div {
                     style: "flex: 1; display: flex; overflow: hidden;",
                     {crate::views::home::home_view(__scope)}
                 }
             }
-            if matches!(nav.get().clone(), AppRoute::ComposeArticle { .. }) {
+            if matches!(nav.get().clone(), AppRoute::ComposeArticle { .. } | AppRoute::EditArticle { .. }) {
                 div {
                     style: "flex: 1; display: flex; flex-direction: column;",
                     {crate::views::article_editor::article_editor_view(__scope)}
@@ -51,6 +68,8 @@ pub fn app() -> NodeHandle {
                     {crate::views::profile_view::profile_view(__scope)}
                 }
             }
+
+            {crate::components::messages::lightbox::lightbox(__scope)}
         }
     }
 }
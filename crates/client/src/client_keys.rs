@@ -53,6 +53,24 @@ pub fn sign_request(
     keys: &KeyPair,
     handle: &str,
     domain: &str,
+) -> Option<SignedHeaders> {
+    sign_request_digest(method, path, &body_hash(body), keys, handle, domain)
+}
+
+/// Hex SHA-256 of a request body, as covered by the signature.
+pub fn body_hash(body: &[u8]) -> String {
+    hex::encode(Sha256::digest(body))
+}
+
+/// Like `sign_request`, for a body too large to hold in memory whose
+/// `body_hash` was computed while streaming it.
+pub fn sign_request_digest(
+    method: &str,
+    path: &str,
+    body_hash: &str,
+    keys: &KeyPair,
+    handle: &str,
+    domain: &str,
 ) -> Option<SignedHeaders> {
     let key_id = keys.key_id.as_ref()?;
 
@@ -61,7 +79,6 @@ pub fn sign_request(
     let signing_key = SigningKey::from_bytes(&priv_arr);
 
     let timestamp = Utc::now().to_rfc3339();
-    let body_hash = hex::encode(Sha256::digest(body));
     let canonical = format!("{}\n{}\n{}\n{}", method, path, timestamp, body_hash);
 
     let signature = signing_key.sign(canonical.as_bytes());
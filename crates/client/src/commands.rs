@@ -0,0 +1,353 @@
+//! Slash commands typed into the composer.
+//!
+//! A line starting with `/` is looked up in a registry of commands. Built-in
+//! commands are added by [`init`]; other modules can add their own with
+//! [`register`]. Start a message with `//` to send a literal leading slash.
+
+use rinch::prelude::*;
+use rorumall_shared::{Availability, MessageType, UpdateChannelRequest, UpdatePresenceRequest};
+use std::cell::RefCell;
+
+use crate::navigation::{navigate, navigate_to_group, AppRoute};
+use crate::stores::{
+    article_draft_key, get_auth_store, get_drafts_store, get_groups_store, get_presence_store, ArticleDraft,
+};
+
+/// Where a command was typed.
+#[derive(Clone, Copy)]
+pub struct CommandContext<'a> {
+    pub host: &'a str,
+    pub group_id: &'a str,
+    pub channel_id: &'a str,
+    /// Set when the composer is replying to a message.
+    pub parent_id: Option<&'a str>,
+    /// Line shown under the composer. Commands that finish asynchronously
+    /// report back through it.
+    pub feedback: Signal<Option<String>>,
+}
+
+/// What the composer should do after running a command.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommandOutcome {
+    /// Post this instead of the typed text.
+    Send {
+        body: String,
+        message_type: Option<MessageType>,
+    },
+    /// Handled locally; clear the input.
+    Handled,
+    /// Bad usage; keep the input and show why.
+    Invalid(String),
+}
+
+#[derive(Clone, Copy)]
+pub struct SlashCommand {
+    /// Name without the slash.
+    pub name: &'static str,
+    /// Argument hint such as `<text>`; empty when the command takes none.
+    pub args: &'static str,
+    pub description: &'static str,
+    pub run: fn(&CommandContext, &str) -> CommandOutcome,
+}
+
+impl SlashCommand {
+    /// `/name <args>` as shown in hints and help.
+    pub fn usage(&self) -> String {
+        if self.args.is_empty() {
+            format!("/{}", self.name)
+        } else {
+            format!("/{} {}", self.name, self.args)
+        }
+    }
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Vec<SlashCommand>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Add a command, replacing any existing command with the same name.
+pub fn register(command: SlashCommand) {
+    REGISTRY.with(|r| {
+        let mut commands = r.borrow_mut();
+        commands.retain(|c| c.name != command.name);
+        commands.push(command);
+        commands.sort_by_key(|c| c.name);
+    });
+}
+
+/// All registered commands, sorted by name.
+pub fn commands() -> Vec<SlashCommand> {
+    REGISTRY.with(|r| r.borrow().clone())
+}
+
+pub fn find(name: &str) -> Option<SlashCommand> {
+    let name = name.to_lowercase();
+    commands().into_iter().find(|c| c.name == name)
+}
+
+/// Split `/name args` into its parts. `None` for ordinary text and for
+/// `//`-escaped text.
+pub fn parse(input: &str) -> Option<(&str, &str)> {
+    let rest = input.strip_prefix('/')?;
+    if rest.starts_with('/') {
+        return None;
+    }
+    match rest.split_once(char::is_whitespace) {
+        Some((name, args)) => Some((name, args.trim())),
+        None => Some((rest.trim(), "")),
+    }
+}
+
+/// Commands matching a partly typed name, for autocomplete. Empty once the
+/// name is followed by a space.
+pub fn suggestions(input: &str) -> Vec<SlashCommand> {
+    match parse(input) {
+        Some((name, _)) if !input.contains(char::is_whitespace) => {
+            let name = name.to_lowercase();
+            commands().into_iter().filter(|c| c.name.starts_with(&name)).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// The command being typed, once its name is complete, for the argument hint.
+pub fn current(input: &str) -> Option<SlashCommand> {
+    parse(input).filter(|_| input.contains(char::is_whitespace)).and_then(|(name, _)| find(name))
+}
+
+/// Run the command in `input`. `None` when the input isn't a command.
+/// A unique prefix runs its command; an ambiguous one asks to narrow down.
+pub fn execute(ctx: &CommandContext, input: &str) -> Option<CommandOutcome> {
+    if let Some(literal) = input.strip_prefix("//") {
+        return Some(CommandOutcome::Send {
+            body: format!("/{}", literal),
+            message_type: None,
+        });
+    }
+    let (name, args) = parse(input)?;
+    let command = match find(name) {
+        Some(command) => command,
+        None => {
+            let matches = suggestions(&format!("/{}", name));
+            match matches.as_slice() {
+                [only] => *only,
+                [] => return Some(CommandOutcome::Invalid(format!("Unknown command /{}. Type /help for a list.", name))),
+                _ => {
+                    let names: Vec<String> = matches.iter().map(|c| format!("/{}", c.name)).collect();
+                    return Some(CommandOutcome::Invalid(format!("Did you mean {}?", names.join(", "))));
+                }
+            }
+        }
+    };
+    Some((command.run)(ctx, args))
+}
+
+/// Register the built-in commands. Call once at startup.
+pub fn init() {
+    for command in [
+        SlashCommand { name: "help", args: "[command]", description: "List commands or explain one", run: help },
+        SlashCommand { name: "me", args: "<action>", description: "Describe what you're doing", run: me },
+        SlashCommand { name: "shrug", args: "[text]", description: "Append ¯\\_(ツ)_/¯", run: shrug },
+        SlashCommand { name: "memo", args: "<text>", description: "Post a memo", run: memo },
+        SlashCommand { name: "article", args: "[title]", description: "Start writing an article", run: article },
+        SlashCommand { name: "status", args: "[text]", description: "Set your status message, or clear it", run: status },
+        SlashCommand { name: "away", args: "", description: "Mark yourself away", run: away },
+        SlashCommand { name: "dnd", args: "", description: "Turn on do not disturb", run: dnd },
+        SlashCommand { name: "online", args: "", description: "Mark yourself online", run: online },
+        SlashCommand { name: "join", args: "<group>", description: "Switch to a group you belong to", run: join },
+        SlashCommand { name: "topic", args: "[text]", description: "Show or set the channel topic", run: topic },
+    ] {
+        register(command);
+    }
+}
+
+fn help(ctx: &CommandContext, args: &str) -> CommandOutcome {
+    let text = if args.is_empty() {
+        commands()
+            .iter()
+            .map(|c| format!("{} — {}", c.usage(), c.description))
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        match find(args.trim_start_matches('/')) {
+            Some(c) => format!("{} — {}", c.usage(), c.description),
+            None => return CommandOutcome::Invalid(format!("Unknown command /{}", args.trim_start_matches('/'))),
+        }
+    };
+    ctx.feedback.set(Some(text));
+    CommandOutcome::Handled
+}
+
+fn me(_ctx: &CommandContext, args: &str) -> CommandOutcome {
+    if args.is_empty() {
+        return CommandOutcome::Invalid("Usage: /me <action>".to_string());
+    }
+    let handle = get_auth_store().handle().unwrap_or_default();
+    CommandOutcome::Send {
+        body: format!("_{} {}_", handle, args),
+        message_type: None,
+    }
+}
+
+fn shrug(_ctx: &CommandContext, args: &str) -> CommandOutcome {
+    // Escaped so Markdown keeps the backslash and underscores
+    let shrug = r"¯\\\_(ツ)\_/¯";
+    let body = if args.is_empty() {
+        shrug.to_string()
+    } else {
+        format!("{} {}", args, shrug)
+    };
+    CommandOutcome::Send { body, message_type: None }
+}
+
+fn memo(_ctx: &CommandContext, args: &str) -> CommandOutcome {
+    if args.is_empty() {
+        return CommandOutcome::Invalid("Usage: /memo <text>".to_string());
+    }
+    CommandOutcome::Send {
+        body: args.to_string(),
+        message_type: Some(MessageType::Memo),
+    }
+}
+
+fn article(ctx: &CommandContext, args: &str) -> CommandOutcome {
+    if ctx.parent_id.is_some() {
+        return CommandOutcome::Invalid("Articles can't be replies".to_string());
+    }
+    if !args.is_empty() {
+        let key = article_draft_key(ctx.host, ctx.group_id, ctx.channel_id, None);
+        let mut draft = get_drafts_store().get_article_draft(&key).unwrap_or_else(|| ArticleDraft {
+            title: String::new(),
+            body: String::new(),
+            attachments: Vec::new(),
+            tags: Vec::new(),
+            updated_at: chrono::Utc::now(),
+        });
+        if draft.title.trim().is_empty() {
+            draft.title = args.to_string();
+            draft.updated_at = chrono::Utc::now();
+            get_drafts_store().set_article_draft(&key, draft);
+        }
+    }
+    navigate(AppRoute::ComposeArticle {
+        host: ctx.host.to_string(),
+        group_id: ctx.group_id.to_string(),
+        channel_id: ctx.channel_id.to_string(),
+    });
+    CommandOutcome::Handled
+}
+
+fn status(ctx: &CommandContext, args: &str) -> CommandOutcome {
+    let availability = get_presence_store()
+        .current
+        .get()
+        .map(|p| p.availability)
+        .filter(|a| *a != Availability::Offline)
+        .unwrap_or(Availability::Online);
+    let status = (!args.is_empty()).then(|| args.to_string());
+    let done = if status.is_some() { "Status updated" } else { "Status cleared" };
+    update_presence(ctx, availability, status, done);
+    CommandOutcome::Handled
+}
+
+fn away(ctx: &CommandContext, _args: &str) -> CommandOutcome {
+    set_availability(ctx, Availability::Away, "You're marked away");
+    CommandOutcome::Handled
+}
+
+fn dnd(ctx: &CommandContext, _args: &str) -> CommandOutcome {
+    set_availability(ctx, Availability::Dnd, "Do not disturb is on");
+    CommandOutcome::Handled
+}
+
+fn online(ctx: &CommandContext, _args: &str) -> CommandOutcome {
+    set_availability(ctx, Availability::Online, "You're online");
+    CommandOutcome::Handled
+}
+
+/// Change availability, keeping the current status message.
+fn set_availability(ctx: &CommandContext, availability: Availability, done: &'static str) {
+    let status = get_presence_store().current.get().and_then(|p| p.status);
+    update_presence(ctx, availability, status, done);
+}
+
+fn update_presence(ctx: &CommandContext, availability: Availability, status: Option<String>, done: &'static str) {
+    let client = get_auth_store().make_client();
+    let feedback = ctx.feedback;
+    crate::runtime::spawn(
+        async move {
+            let req = UpdatePresenceRequest { availability, status };
+            client.update_presence(&req).await
+        },
+        move |result| match result {
+            Ok(presence) => {
+                get_presence_store().current.set(Some(presence));
+                feedback.set(Some(done.to_string()));
+            }
+            Err(e) => feedback.set(Some(format!("Failed to update presence: {}", e))),
+        },
+    );
+}
+
+fn join(_ctx: &CommandContext, args: &str) -> CommandOutcome {
+    if args.is_empty() {
+        return CommandOutcome::Invalid("Usage: /join <group>".to_string());
+    }
+    let wanted = args.trim_start_matches('#').to_lowercase();
+    let groups = get_groups_store().joined_groups.get().clone();
+    let group = groups
+        .iter()
+        .find(|g| g.group_id == args || g.name.to_lowercase() == wanted)
+        .or_else(|| groups.iter().find(|g| g.name.to_lowercase().starts_with(&wanted)));
+    match group {
+        Some(group) => {
+            navigate_to_group(group.host.clone().unwrap_or_default(), group.group_id.clone());
+            CommandOutcome::Handled
+        }
+        None => CommandOutcome::Invalid(format!("You're not a member of a group called \"{}\"", args)),
+    }
+}
+
+fn topic(ctx: &CommandContext, args: &str) -> CommandOutcome {
+    if args.is_empty() {
+        let current = get_groups_store()
+            .channels
+            .get()
+            .iter()
+            .find(|c| c.id == ctx.channel_id)
+            .and_then(|c| c.topic.clone())
+            .filter(|t| !t.is_empty());
+        ctx.feedback.set(Some(match current {
+            Some(topic) => format!("Topic: {}", topic),
+            None => "This channel has no topic".to_string(),
+        }));
+        return CommandOutcome::Handled;
+    }
+
+    let client = get_auth_store().make_client_for(ctx.host);
+    let group_id = ctx.group_id.to_string();
+    let channel_id = ctx.channel_id.to_string();
+    let feedback = ctx.feedback;
+    let req = UpdateChannelRequest {
+        name: None,
+        topic: Some(args.to_string()),
+        discoverability: None,
+        settings: None,
+        tags: None,
+    };
+    crate::runtime::spawn(
+        async move { client.update_channel(&group_id, &channel_id, &req).await },
+        move |result| match result {
+            Ok(channel) => {
+                get_groups_store().channels.update(|chs| {
+                    if let Some(c) = chs.iter_mut().find(|c| c.id == channel.id) {
+                        *c = channel;
+                    }
+                });
+                feedback.set(Some("Topic updated".to_string()));
+            }
+            Err(e) => feedback.set(Some(format!("Failed to set topic: {}", e))),
+        },
+    );
+    CommandOutcome::Handled
+}
@@ -1,26 +1,37 @@
 use rinch::prelude::*;
-use crate::stores::{get_members_store, StoredMessage};
-
-pub fn render_markdown(text: &str) -> String {
-    let parser = pulldown_cmark::Parser::new(text);
-    let mut html_output = String::new();
-    pulldown_cmark::html::push_html(&mut html_output, parser);
-    ammonia::clean(&html_output)
-}
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use crate::navigation::{navigate, AppRoute};
+use crate::stores::{get_auth_store, get_members_store, get_messages_store, StoredMessage};
 
 #[component]
-pub fn article_item(msg: StoredMessage, group_id: String) -> NodeHandle {
+pub fn article_item(msg: StoredMessage, group_id: String, channel_id: String) -> NodeHandle {
     let user_display = msg.user_id.split('@').next().unwrap_or(&msg.user_id).to_string();
     let time = msg.created_at.format("%b %d, %Y at %H:%M").to_string();
-    let expanded = Signal::new(false);
 
     let title = msg.title.clone().unwrap_or_else(|| "Untitled Article".to_string());
     let content = msg.content.clone();
     let attachments = Signal::new(msg.attachments.clone());
-    let preview = if content.len() > 200 {
-        format!("{}...", &content[..200])
-    } else {
-        content.clone()
+    let preview = crate::markdown::preview(&content, 200);
+    let minutes = crate::markdown::reading_time_minutes(&content);
+
+    // Authors can reopen their article in the editor
+    let is_author = get_auth_store().user_id().as_deref() == Some(msg.user_id.as_str());
+    let host = get_messages_store()
+        .get_channel_messages(&channel_id)
+        .and_then(|ch| ch.location)
+        .map(|loc| loc.host)
+        .unwrap_or_default();
+    let read_route = AppRoute::Article {
+        host: host.clone(),
+        group_id: group_id.clone(),
+        channel_id: channel_id.clone(),
+        message_id: msg.id.clone(),
+    };
+    let edit_route = AppRoute::EditArticle {
+        host,
+        group_id: group_id.clone(),
+        channel_id,
+        message_id: msg.id.clone(),
     };
 
     let avatar_url = {
@@ -51,10 +62,27 @@ pub fn article_item(msg: StoredMessage, group_id: String) -> NodeHandle {
                         "Article"
                     }
 
-                    Text {
-                        size: "xs",
-                        color: "dimmed",
-                        {time}
+                    Group {
+                        gap: "xs",
+
+                        Text {
+                            size: "xs",
+                            color: "dimmed",
+                            {time}
+                        }
+
+                        if is_author {
+                            Tooltip {
+                                label: "Edit article",
+
+                                ActionIcon {
+                                    variant: "subtle",
+                                    size: "xs",
+                                    onclick: move || navigate(edit_route.clone()),
+                                    {render_tabler_icon(__scope, TablerIcon::Pencil, TablerIconStyle::Outline)}
+                                }
+                            }
+                        }
                     }
                 }
 
@@ -80,26 +108,30 @@ pub fn article_item(msg: StoredMessage, group_id: String) -> NodeHandle {
                     }
                 }
 
-                if expanded.get() {
-                    div {
-                        class: "markdown-content",
-                        {render_markdown(&content)}
+                Text {
+                    size: "sm",
+                    color: "dimmed",
+                    {preview}
+                }
+
+                Group {
+                    gap: "xs",
+
+                    Button {
+                        variant: "subtle",
+                        size: "xs",
+                        onclick: move || navigate(read_route.clone()),
+                        {render_tabler_icon(__scope, TablerIcon::Book, TablerIconStyle::Outline)}
+                        " Read article"
                     }
-                } else {
+
                     Text {
-                        size: "sm",
+                        size: "xs",
                         color: "dimmed",
-                        {preview.clone()}
+                        {format!("{} min read", minutes)}
                     }
                 }
 
-                Button {
-                    variant: "subtle",
-                    size: "xs",
-                    onclick: move || expanded.update(|v| *v = !*v),
-                    {|| if expanded.get() { "Show less".to_string() } else { "Read more".to_string() }}
-                }
-
                 // Attachments
                 if !attachments.get().is_empty() {
                     Divider {}
@@ -0,0 +1,187 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use crate::stores::get_emoji_store;
+
+/// Emoji button for a composer. Picked emoji are appended to `input_text`:
+/// unicode for built-in emoji and `:shortcode:` for the group's custom ones.
+#[component]
+pub fn emoji_picker(group_id: String, input_text: Signal<String>) -> NodeHandle {
+    let open = Signal::new(false);
+    let picked = Signal::new(None::<String>);
+
+    Effect::new(move || {
+        let Some(emoji) = picked.get().clone() else {
+            return;
+        };
+        picked.set(None);
+        input_text.update(|t| {
+            if !t.is_empty() && !t.ends_with(' ') {
+                t.push(' ');
+            }
+            t.push_str(&emoji);
+        });
+    });
+
+    rsx! {
+        div {
+            style: "position: relative;",
+
+            ActionIcon {
+                variant: "subtle",
+                onclick: move || open.update(|o| *o = !*o),
+                {render_tabler_icon(__scope, TablerIcon::MoodSmile, TablerIconStyle::Outline)}
+            }
+
+            if open.get() {
+                {emoji_panel(__scope, group_id.clone(), picked, "bottom: 40px; left: 0;".to_string())}
+            }
+        }
+    }
+}
+
+/// "Add reaction" button under a message. The picked emoji, in the same
+/// form as for [`emoji_picker`], is put in `picked` and the panel closes.
+#[component]
+pub fn reaction_picker(group_id: String, picked: Signal<Option<String>>) -> NodeHandle {
+    let open = Signal::new(false);
+
+    Effect::new(move || {
+        if picked.get().is_some() {
+            open.set(false);
+        }
+    });
+
+    rsx! {
+        div {
+            style: "position: relative;",
+
+            Tooltip {
+                label: "Add reaction",
+
+                ActionIcon {
+                    variant: "subtle",
+                    size: "xs",
+                    onclick: move || open.update(|o| *o = !*o),
+                    {render_tabler_icon(__scope, TablerIcon::MoodPlus, TablerIconStyle::Outline)}
+                }
+            }
+
+            if open.get() {
+                {emoji_panel(__scope, group_id.clone(), picked, "top: 28px; left: 0;".to_string())}
+            }
+        }
+    }
+}
+
+/// Search box and emoji grids: recently used, the group's custom emoji and
+/// the built-in set. A click puts the emoji in `picked`.
+#[component]
+fn emoji_panel(group_id: String, picked: Signal<Option<String>>, position: String) -> NodeHandle {
+    let query = Signal::new(String::new());
+    let gid = Signal::new(group_id);
+
+    let pick = move |emoji: String| {
+        get_emoji_store().use_emoji(&emoji);
+        picked.set(Some(emoji));
+    };
+
+    let custom = move || {
+        let q = query.get().trim().trim_matches(':').to_lowercase();
+        get_emoji_store()
+            .get_group_emoji(&gid.get())
+            .into_iter()
+            .filter(|e| e.shortcode.contains(&q))
+            .collect::<Vec<_>>()
+    };
+
+    rsx! {
+        div {
+            style: {format!("position: absolute; {} width: 320px; max-height: 360px; display: flex; flex-direction: column; gap: 8px; padding: 10px; background: var(--rinch-color-dark-6, #25262b); border: 1px solid var(--rinch-color-dark-4, #373a40); border-radius: 8px; z-index: 20;", position)},
+
+            TextInput {
+                placeholder: "Search emoji",
+                value_fn: move || query.get().clone(),
+                oninput: move |val: String| query.set(val),
+            }
+
+            div {
+                style: "flex: 1; overflow-y: auto; min-height: 0;",
+
+                if query.get().is_empty() && !get_emoji_store().recents.get().is_empty() {
+                    Text {
+                        size: "xs",
+                        color: "dimmed",
+                        "Recently used"
+                    }
+
+                    div {
+                        class: "emoji-grid",
+
+                        for recent in get_emoji_store().recents.get().clone() {
+                            let url = recent
+                                .strip_prefix(':')
+                                .and_then(|r| r.strip_suffix(':'))
+                                .and_then(|code| get_emoji_store().custom_map(&gid.get()).get(code).cloned());
+                            let value = recent.clone();
+                            div {
+                                key: recent.clone(),
+                                class: "emoji-cell",
+                                onclick: move || pick(value.clone()),
+
+                                if let Some(url) = url.clone() {
+                                    img { class: "custom-emoji", src: url, alt: recent.clone() }
+                                } else if !recent.starts_with(':') {
+                                    {recent.clone()}
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if !custom().is_empty() {
+                    Text {
+                        size: "xs",
+                        color: "dimmed",
+                        "Group emoji"
+                    }
+
+                    div {
+                        class: "emoji-grid",
+
+                        for emoji in custom() {
+                            let code = emoji.reaction_key();
+                            div {
+                                key: emoji.shortcode.clone(),
+                                class: "emoji-cell",
+                                title: code.clone(),
+                                onclick: move || pick(code.clone()),
+                                img { class: "custom-emoji", src: emoji.url.clone(), alt: emoji.reaction_key() }
+                            }
+                        }
+                    }
+                }
+
+                Text {
+                    size: "xs",
+                    color: "dimmed",
+                    "Emoji"
+                }
+
+                div {
+                    class: "emoji-grid",
+
+                    for emoji in crate::emoji::search(&query.get()) {
+                        let value = emoji.unicode.to_string();
+                        div {
+                            key: emoji.shortcode.to_string(),
+                            class: "emoji-cell",
+                            title: format!(":{}:", emoji.shortcode),
+                            onclick: move || pick(value.clone()),
+                            {emoji.unicode}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use rinch::prelude::*;
+use crate::export::{ExportFormat, ExportOptions, ExportUpdate};
+use crate::stores::{get_export_store, ExportStatus};
+
+/// Export a channel's full history to a file, with progress and cancel.
+#[component]
+pub fn export_modal(
+    host: String,
+    group_id: String,
+    channel_id: String,
+    channel_name: String,
+    opened: Signal<bool>,
+) -> NodeHandle {
+    let format = Signal::new(ExportFormat::Html.extension().to_string());
+    let attachments = Signal::new("link".to_string());
+    let h = Signal::new(host);
+    let gid = Signal::new(group_id);
+    let cid = Signal::new(channel_id);
+    let name = Signal::new(channel_name);
+
+    // Progress belongs to this channel's export only
+    let job = move || get_export_store().job.get().clone().filter(|j| j.channel_id == cid.get());
+    let other_running = move || get_export_store().is_running() && job().is_none();
+
+    let on_export = move || {
+        let format = ExportFormat::from_extension(&format.get()).unwrap_or(ExportFormat::Html);
+        let file_name = crate::export::default_file_name(&name.get(), format);
+        crate::runtime::spawn(
+            async move {
+                rinch::dialogs::save_file()
+                    .set_file_name(&file_name)
+                    .add_filter(format.label(), &[format.extension()])
+                    .save_file()
+            },
+            move |path| {
+                let Some(path) = path else { return };
+                get_export_store().start(&h.get(), ExportOptions {
+                    group_id: gid.get().clone(),
+                    channel_id: cid.get().clone(),
+                    channel_name: name.get().clone(),
+                    format,
+                    path,
+                    download_attachments: attachments.get() == "download",
+                });
+            },
+        );
+    };
+
+    let on_close = move || {
+        if !get_export_store().is_running() {
+            get_export_store().dismiss();
+        }
+        opened.set(false);
+    };
+
+    rsx! {
+        Modal {
+            opened_fn: move || opened.get(),
+            onclose: move || on_close(),
+            title: "Export channel history",
+
+            Stack {
+                gap: "md",
+
+                if let Some(job) = job() {
+                    if let ExportStatus::Running(update) = job.status.clone() {
+                        Stack {
+                            gap: "sm",
+
+                            Group {
+                                gap: "sm",
+                                Loader { size: "sm" }
+                                Text {
+                                    size: "sm",
+                                    {progress_label(update.as_ref())}
+                                }
+                            }
+
+                            Group {
+                                justify: "flex-end",
+
+                                Text {
+                                    size: "xs",
+                                    color: "dimmed",
+                                    style: "flex: 1;",
+                                    "You can close this window; the export keeps running."
+                                }
+
+                                Button {
+                                    variant: "light",
+                                    color: "red",
+                                    onclick: move || get_export_store().cancel(),
+                                    "Cancel export"
+                                }
+                            }
+                        }
+                    } else if let ExportStatus::Done(summary) = job.status.clone() {
+                        Stack {
+                            gap: "sm",
+
+                            Alert {
+                                color: "green",
+                                variant: "light",
+                                {format!(
+                                    "Exported {} messages and {} attachments to {}",
+                                    summary.messages,
+                                    summary.attachments,
+                                    summary.path.display(),
+                                )}
+                            }
+
+                            if summary.failed_attachments > 0 {
+                                Text {
+                                    size: "xs",
+                                    color: "dimmed",
+                                    {format!("{} attachment(s) could not be downloaded and link to the server instead.", summary.failed_attachments)}
+                                }
+                            }
+
+                            Group {
+                                justify: "flex-end",
+
+                                Button {
+                                    variant: "subtle",
+                                    onclick: {
+                                        let dir = summary.path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+                                        move || {
+                                            if let Err(e) = open::that(&dir) {
+                                                tracing::error!("Failed to open {}: {}", dir.display(), e);
+                                            }
+                                        }
+                                    },
+                                    "Show in folder"
+                                }
+
+                                Button {
+                                    variant: "filled",
+                                    color: "indigo",
+                                    onclick: move || get_export_store().dismiss(),
+                                    "Export again"
+                                }
+                            }
+                        }
+                    } else {
+                        Stack {
+                            gap: "sm",
+
+                            Alert {
+                                color: {if job.status == ExportStatus::Cancelled { "gray" } else { "red" }},
+                                variant: "light",
+                                {match job.status.clone() {
+                                    ExportStatus::Failed(e) => e,
+                                    _ => "Export cancelled.".to_string(),
+                                }}
+                            }
+
+                            Group {
+                                justify: "flex-end",
+
+                                Button {
+                                    variant: "filled",
+                                    color: "indigo",
+                                    onclick: move || get_export_store().dismiss(),
+                                    "Try again"
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    Stack {
+                        gap: "sm",
+
+                        Select {
+                            label: "Format",
+                            value_fn: move || format.get().clone(),
+                            onchange: move |val: String| format.set(val),
+                            option { value: "html", "Standalone HTML" }
+                            option { value: "md", "Markdown transcript" }
+                            option { value: "json", "JSON" }
+                        }
+
+                        Select {
+                            label: "Attachments",
+                            value_fn: move || attachments.get().clone(),
+                            onchange: move |val: String| attachments.set(val),
+                            option { value: "link", "Link to the server" }
+                            option { value: "download", "Download into a folder next to the export" }
+                        }
+
+                        if other_running() {
+                            Text {
+                                size: "xs",
+                                color: "dimmed",
+                                "Another channel is being exported. Wait for it to finish or cancel it first."
+                            }
+                        }
+
+                        Group {
+                            justify: "flex-end",
+
+                            Button {
+                                variant: "subtle",
+                                onclick: move || on_close(),
+                                "Close"
+                            }
+
+                            Button {
+                                variant: "filled",
+                                color: "indigo",
+                                disabled: other_running(),
+                                onclick: move || on_export(),
+                                "Export..."
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn progress_label(update: Option<&ExportUpdate>) -> String {
+    match update {
+        None => "Starting export...".to_string(),
+        Some(ExportUpdate::Fetched(n)) => format!("Fetched {} messages...", n),
+        Some(ExportUpdate::Downloaded(done, total)) => format!("Downloading attachments ({}/{})...", done, total),
+        Some(ExportUpdate::Writing) => "Writing file...".to_string(),
+    }
+}
@@ -0,0 +1,220 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use std::path::PathBuf;
+
+use crate::stores::{get_auth_store, get_lightbox_store};
+
+/// Full-size image viewer over the whole window. Arrow keys and Page
+/// Up/Down step through the images (or pan once zoomed in), + and - zoom,
+/// 0 fits the image again and Escape closes.
+#[component]
+pub fn lightbox() -> NodeHandle {
+    let store = get_lightbox_store();
+    let status = Signal::new(None::<String>);
+
+    let on_save = move || {
+        let Some(view) = store.view.get().clone() else {
+            return;
+        };
+        let att = view.current().clone();
+        let name = crate::download::safe_file_name(&crate::search::attachment_name(&att), &att.id);
+        crate::runtime::spawn(
+            async move {
+                rinch::dialogs::save_file()
+                    .set_file_name(&name)
+                    .save_file()
+            },
+            move |path: Option<PathBuf>| {
+                let Some(path) = path else { return };
+                status.set(Some("Saving...".to_string()));
+                let client = get_auth_store().make_client();
+                let url = att.url.clone();
+                crate::runtime::spawn(
+                    async move { crate::download::download(&client, &url, &path, true, |_, _| {}).await },
+                    move |result| {
+                        status.set(Some(match result {
+                            Ok(path) => format!("Saved to {}", path.display()),
+                            Err(e) => format!("Couldn't save: {}", e),
+                        }));
+                    },
+                );
+            },
+        );
+    };
+
+    let on_copy = move || {
+        let Some(view) = store.view.get().clone() else {
+            return;
+        };
+        let url = view.current().url.clone();
+        let client = get_auth_store().make_client();
+        crate::runtime::spawn(
+            async move {
+                let bytes = client.get_bytes(&url).await.map_err(|e| e.to_string())?;
+                let image = image::load_from_memory(&bytes).map_err(|e| e.to_string())?.to_rgba8();
+                Ok::<_, String>((image.width() as usize, image.height() as usize, image.into_raw()))
+            },
+            move |result| {
+                let copied = result.and_then(|(width, height, rgba)| {
+                    rinch::clipboard::copy_image(rinch::clipboard::ImageData { width, height, bytes: rgba.into() })
+                        .map_err(|e| e.to_string())
+                });
+                status.set(Some(match copied {
+                    Ok(()) => "Image copied".to_string(),
+                    Err(e) => {
+                        // The link is better than nothing
+                        tracing::warn!("Couldn't copy image, copying its link instead: {}", e);
+                        match rinch::clipboard::copy_text(&url) {
+                            Ok(()) => "Link copied".to_string(),
+                            Err(e) => format!("Couldn't copy: {}", e),
+                        }
+                    }
+                }));
+            },
+        );
+    };
+
+    let on_close = move || {
+        status.set(None);
+        store.close();
+    };
+
+    rsx! {
+        if let Some(view) = store.view.get().clone() {
+            div {
+                style: "position: fixed; inset: 0; z-index: 1000; display: flex; flex-direction: column; background: rgba(0, 0, 0, 0.92);",
+
+                // Toolbar
+                div {
+                    style: "display: flex; align-items: center; gap: 8px; padding: 8px 16px; flex-shrink: 0;",
+
+                    Text {
+                        size: "sm",
+                        color: "dimmed",
+                        {format!("{} / {}", view.index + 1, view.images.len())}
+                    }
+
+                    Text {
+                        size: "sm",
+                        style: "flex: 1; overflow: hidden; text-overflow: ellipsis; white-space: nowrap;",
+                        {crate::search::attachment_name(view.current())}
+                    }
+
+                    if let Some(message) = status.get().clone() {
+                        Text {
+                            size: "xs",
+                            color: "dimmed",
+                            {message}
+                        }
+                    }
+
+                    Tooltip {
+                        label: "Zoom out (-)",
+
+                        ActionIcon {
+                            variant: "subtle",
+                            disabled: !view.is_zoomed(),
+                            onclick: move || store.zoom_out(),
+                            {render_tabler_icon(__scope, TablerIcon::ZoomOut, TablerIconStyle::Outline)}
+                        }
+                    }
+
+                    Tooltip {
+                        label: "Fit to window (0)",
+
+                        Button {
+                            variant: "subtle",
+                            size: "xs",
+                            onclick: move || store.reset_zoom(),
+                            {format!("{}%", view.zoom())}
+                        }
+                    }
+
+                    Tooltip {
+                        label: "Zoom in (+)",
+
+                        ActionIcon {
+                            variant: "subtle",
+                            onclick: move || store.zoom_in(),
+                            {render_tabler_icon(__scope, TablerIcon::ZoomIn, TablerIconStyle::Outline)}
+                        }
+                    }
+
+                    Tooltip {
+                        label: "Save as...",
+
+                        ActionIcon {
+                            variant: "subtle",
+                            onclick: move || on_save(),
+                            {render_tabler_icon(__scope, TablerIcon::DeviceFloppy, TablerIconStyle::Outline)}
+                        }
+                    }
+
+                    Tooltip {
+                        label: "Copy image",
+
+                        ActionIcon {
+                            variant: "subtle",
+                            onclick: move || on_copy(),
+                            {render_tabler_icon(__scope, TablerIcon::Copy, TablerIconStyle::Outline)}
+                        }
+                    }
+
+                    Tooltip {
+                        label: "Close (Esc)",
+
+                        ActionIcon {
+                            variant: "subtle",
+                            onclick: move || on_close(),
+                            {render_tabler_icon(__scope, TablerIcon::X, TablerIconStyle::Outline)}
+                        }
+                    }
+                }
+
+                // Image, zoomed around its centre and panned with the arrow keys
+                div {
+                    style: "flex: 1; position: relative; overflow: hidden; display: flex; align-items: center; justify-content: center; min-height: 0;",
+
+                    img {
+                        src: {view.current().url.clone()},
+                        alt: {crate::search::attachment_name(view.current())},
+                        style: {format!(
+                            "max-width: 100%; max-height: 100%; object-fit: contain; transform: translate({}px, {}px) scale({}); transform-origin: center;",
+                            view.pan_x,
+                            view.pan_y,
+                            view.zoom() as f32 / 100.0,
+                        )},
+                    }
+
+                    if view.images.len() > 1 {
+                        div {
+                            style: "position: absolute; left: 16px; top: 50%; transform: translateY(-50%);",
+
+                            ActionIcon {
+                                variant: "filled",
+                                color: "dark",
+                                size: "xl",
+                                radius: "xl",
+                                onclick: move || store.step(-1),
+                                {render_tabler_icon(__scope, TablerIcon::ChevronLeft, TablerIconStyle::Outline)}
+                            }
+                        }
+
+                        div {
+                            style: "position: absolute; right: 16px; top: 50%; transform: translateY(-50%);",
+
+                            ActionIcon {
+                                variant: "filled",
+                                color: "dark",
+                                size: "xl",
+                                radius: "xl",
+                                onclick: move || store.step(1),
+                                {render_tabler_icon(__scope, TablerIcon::ChevronRight, TablerIconStyle::Outline)}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use rinch::prelude::*;
+use crate::link_preview::{extract_urls, LinkPreview};
+use crate::stores::{get_auth_store, get_invites_store, get_link_previews_store, get_settings_store, PreviewState};
+
+/// Preview cards for the links in a message body, shown only for domains the
+/// user's link preview policy allows.
+#[component]
+pub fn link_previews(content: String) -> NodeHandle {
+    let policy = get_settings_store().preferences.get().link_previews.clone();
+    let urls: Vec<String> = extract_urls(&content)
+        .into_iter()
+        .filter(|url| policy.allows(url))
+        .collect();
+
+    let store = get_link_previews_store();
+    for url in &urls {
+        store.request(url);
+    }
+
+    rsx! {
+        div {
+            for url in urls.clone() {
+                div {
+                    key: url.clone(),
+                    if let Some(PreviewState::Ready(preview)) = get_link_previews_store().get(&url) {
+                        {preview_card(__scope, preview)}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn preview_card(preview: LinkPreview) -> NodeHandle {
+    let site = preview.site_name.clone().unwrap_or_else(|| preview.domain());
+    let url = preview.url.clone();
+
+    // The image host is contacted too, so it has to pass the policy on its own
+    let policy = get_settings_store().preferences.get().link_previews.clone();
+    let image_url = preview.image.clone().filter(|image| policy.allows(image));
+    if let Some(image) = &image_url {
+        get_link_previews_store().request_image(image);
+    }
+
+    rsx! {
+        div {
+            style: "cursor: pointer; margin-top: 6px; max-width: 420px;",
+            onclick: move || {
+                // Invite links open the accept-invite dialog instead of the browser
+                if let Some(link) = crate::invites::InviteLink::find(&url, &get_auth_store().domain()) {
+                    get_invites_store().open(link);
+                } else if let Err(e) = open::that(&url) {
+                    tracing::error!("Failed to open {}: {}", url, e);
+                }
+            },
+
+            Card {
+                shadow: "xs",
+                p: "sm",
+                style: "border-left: 3px solid var(--rinch-color-dark-3, #5c5f66);",
+
+                Stack {
+                    gap: "xs",
+
+                    Text {
+                        size: "xs",
+                        color: "dimmed",
+                        {site}
+                    }
+
+                    if let Some(title) = preview.title.clone() {
+                        Text {
+                            size: "sm",
+                            weight: "600",
+                            color: "indigo",
+                            {title}
+                        }
+                    }
+
+                    if let Some(description) = preview.description.clone() {
+                        Text {
+                            size: "xs",
+                            {description}
+                        }
+                    }
+
+                    if let Some(image) = image_url.as_ref().and_then(|u| get_link_previews_store().image(u)) {
+                        img {
+                            src: image,
+                            style: "max-width: 100%; max-height: 200px; object-fit: cover; border-radius: var(--rinch-radius-sm, 4px);",
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use crate::markdown::MarkdownBlock;
+
+/// Rendered Markdown with highlighted code blocks. `class` is added to the
+/// container alongside `markdown-content`.
+#[component]
+pub fn markdown_view(blocks: Vec<MarkdownBlock>, class: String) -> NodeHandle {
+    rsx! {
+        div {
+            class: {format!("markdown-content {}", class)},
+
+            for (i, block) in blocks.into_iter().enumerate() {
+                div {
+                    key: i.to_string(),
+
+                    if let MarkdownBlock::Html(html) = block.clone() {
+                        div { {html} }
+                    }
+
+                    if let MarkdownBlock::Code { language, code } = block.clone() {
+                        {code_block(__scope, language, code)}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A fenced code block with a language label and copy button. Unknown
+/// languages are shown as plain text.
+#[component]
+pub fn code_block(language: Option<String>, code: String) -> NodeHandle {
+    let copied = Signal::new(false);
+    let code = Signal::new(code);
+
+    let highlighted = language
+        .as_deref()
+        .and_then(|lang| crate::highlight::highlight(&code.get(), lang));
+    let label = language.clone().unwrap_or_else(|| "text".to_string());
+    let html = highlighted.unwrap_or_else(|| crate::highlight::escape(&code.get()));
+
+    let on_copy = move || {
+        match rinch::clipboard::copy_text(&code.get()) {
+            Ok(()) => copied.set(true),
+            Err(e) => tracing::error!("Failed to copy code block: {}", e),
+        }
+    };
+
+    rsx! {
+        div {
+            class: "code-block",
+
+            div {
+                class: "code-block-header",
+
+                Text {
+                    size: "xs",
+                    color: "dimmed",
+                    {label}
+                }
+
+                Tooltip {
+                    label: "Copy code",
+
+                    ActionIcon {
+                        variant: "subtle",
+                        size: "xs",
+                        onclick: move || on_copy(),
+                        {if copied.get() {
+                            render_tabler_icon(__scope, TablerIcon::Check, TablerIconStyle::Outline)
+                        } else {
+                            render_tabler_icon(__scope, TablerIcon::Copy, TablerIconStyle::Outline)
+                        }}
+                    }
+                }
+            }
+
+            pre {
+                code {
+                    {html}
+                }
+            }
+        }
+    }
+}
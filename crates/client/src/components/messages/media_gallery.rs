@@ -0,0 +1,85 @@
+use rinch::prelude::*;
+use rorumall_shared::Attachment;
+
+use crate::stores::{get_lightbox_store, get_media_store};
+
+/// Everything posted to a channel as attachments, newest first: images as
+/// a grid that opens the lightbox, then other files.
+#[component]
+pub fn media_gallery(host: String, channel_id: String) -> NodeHandle {
+    get_media_store().load(&host, &channel_id);
+    let cid = Signal::new(channel_id);
+    let filter = Signal::new("all".to_string());
+
+    // Reads the messages and media stores, so new attachments and older
+    // cached ones show up as they arrive
+    let media = move || crate::media::channel_media(&cid.get());
+    let images = move || -> Vec<Attachment> {
+        media().into_iter().filter(|item| item.is_image()).map(|item| item.attachment).collect()
+    };
+    let files = move || -> Vec<Attachment> {
+        media().into_iter().filter(|item| !item.is_image()).rev().map(|item| item.attachment).collect()
+    };
+    let show_images = move || filter.get() != "files";
+    let show_files = move || filter.get() != "images";
+
+    rsx! {
+        div {
+            style: "flex: 1; overflow-y: auto; padding: 16px; min-height: 0;",
+
+            Stack {
+                gap: "md",
+
+                Select {
+                    label: "Show",
+                    value_fn: move || filter.get().clone(),
+                    onchange: move |val: String| filter.set(val),
+                    option { value: "all", "Images and files" }
+                    option { value: "images", "Images" }
+                    option { value: "files", "Files" }
+                }
+
+                if media().is_empty() {
+                    Text {
+                        size: "sm",
+                        color: "dimmed",
+                        "No images or files have been posted in this channel yet."
+                    }
+                }
+
+                if show_images() && !images().is_empty() {
+                    div {
+                        style: "display: grid; grid-template-columns: repeat(auto-fill, minmax(140px, 1fr)); gap: 8px;",
+
+                        for (index, att) in images().into_iter().enumerate().rev() {
+                            div {
+                                key: att.id.clone(),
+                                style: "aspect-ratio: 1; overflow: hidden; border-radius: 4px; cursor: pointer; background: var(--rinch-color-dark-6, #25262b);",
+                                onclick: move || get_lightbox_store().open(images(), index),
+
+                                img {
+                                    src: {att.thumbnail_url.clone().unwrap_or_else(|| att.url.clone())},
+                                    alt: {crate::search::attachment_name(&att)},
+                                    style: "width: 100%; height: 100%; object-fit: cover; display: block;",
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if show_files() && !files().is_empty() {
+                    Stack {
+                        gap: "xs",
+
+                        for att in files() {
+                            div {
+                                key: att.id.clone(),
+                                {crate::components::ui::attachment_display::attachment_display(__scope, att)}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
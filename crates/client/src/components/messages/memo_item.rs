@@ -58,10 +58,7 @@ pub fn memo_item(msg: StoredMessage, group_id: String) -> NodeHandle {
                     }
                 }
 
-                Text {
-                    size: "sm",
-                    {msg.content.clone()}
-                }
+                {crate::components::messages::message_body::message_body(__scope, msg.content.clone(), msg.mime.clone(), group_id.clone())}
             }
         }
     }
@@ -0,0 +1,27 @@
+use rinch::prelude::*;
+use crate::markdown::{is_markdown, message_blocks_with_emoji};
+use crate::stores::{get_emoji_store, get_settings_store};
+
+/// Body text of a chat message or memo, rendered as Markdown unless the
+/// content is declared `text/plain` or the user has turned rendering off.
+/// `:shortcode:`s of the group's custom emoji are shown as images.
+#[component]
+pub fn message_body(content: String, mime: Option<String>, group_id: String) -> NodeHandle {
+    let content = Signal::new(content);
+    let gid = Signal::new(group_id);
+    let markdown = is_markdown(mime.as_deref());
+
+    rsx! {
+        div {
+            if markdown && get_settings_store().preferences.get().render_markdown {
+                {crate::components::messages::markdown_view::markdown_view(__scope, message_blocks_with_emoji(&content.get(), &get_emoji_store().custom_map(&gid.get())), "message-markdown".to_string())}
+            } else {
+                Text {
+                    size: "sm",
+                    style: "white-space: pre-wrap;",
+                    {content.get().clone()}
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,16 @@
 use rinch::prelude::*;
 use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use crate::components::messages::reply_thread::reply_label;
 use crate::navigation::{navigate, AppRoute};
-use crate::stores::get_auth_store;
+use rorumall_shared::MessageType;
+use crate::stores::{draft_key, get_auth_store, get_drafts_store, get_groups_store, get_members_store, get_messages_store, get_scheduled_store, get_settings_store, Draft, ScheduledMessage};
+use crate::api_client::ApiClient;
+use crate::commands::CommandOutcome;
+use crate::imaging::ImageSettings;
+use crate::upload::{UploadControl, UploadError, UploadSource};
+use std::path::PathBuf;
+use std::sync::Arc;
+use crate::ws::MessageOptions;
 
 /// A pending attachment that shows a preview immediately while uploading in the background.
 #[derive(Clone, PartialEq)]
@@ -13,73 +22,434 @@ struct PendingAttachment {
     /// Local temp file path for immediate image preview (before upload completes).
     local_preview: String,
     server: Option<rorumall_shared::Attachment>,
+    /// Bytes sent so far.
+    uploaded: u64,
+    /// Cancels the upload while it runs.
+    control: Option<UploadControl>,
+    /// The file as picked or pasted, kept so the upload can be redone with
+    /// or without image processing.
+    source: Option<PathBuf>,
+    /// Upload an image untouched instead of scaled and stripped.
+    send_original: bool,
+    /// Temp directory holding `source` for a pasted image.
+    temp_dir: Option<Arc<TempDir>>,
 }
 
+impl PendingAttachment {
+    /// Rebuild a completed attachment restored from a draft.
+    fn from_attachment(att: rorumall_shared::Attachment) -> Self {
+        Self {
+            local_id: uuid::Uuid::new_v4().to_string(),
+            filename: crate::search::attachment_name(&att),
+            mime: att.mime.clone(),
+            size: att.size,
+            local_preview: String::new(),
+            uploaded: att.size,
+            server: Some(att),
+            control: None,
+            source: None,
+            send_original: false,
+            temp_dir: None,
+        }
+    }
+}
+
+/// A pasted image's temp directory. It is removed when the last holder
+/// drops it: the pending attachment once it is sent or discarded, or its
+/// running upload.
+#[derive(Debug, PartialEq)]
+struct TempDir(PathBuf);
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.0) {
+            tracing::debug!("Failed to remove {}: {}", self.0.display(), e);
+        }
+    }
+}
+
+/// What a Ctrl+V found on the clipboard.
+#[derive(Clone, PartialEq)]
+enum ClipboardContent {
+    /// An image, saved to a temp file, with a data URI for its preview.
+    Image { dir: Arc<TempDir>, path: PathBuf, preview: String, size: u64 },
+    /// Files copied in a file manager.
+    Files(Vec<PathBuf>),
+    /// Formatted text, converted.
+    Markdown(String),
+    /// Nothing the native paste doesn't already handle.
+    Text,
+}
+
+/// A Ctrl+V being handled. The native paste puts any plain text at the
+/// cursor while the clipboard is read in the background; once both are
+/// known, the inserted text is replaced by what the clipboard really held.
+#[derive(Clone, PartialEq)]
+struct PasteInProgress {
+    id: String,
+    /// Composer text before the paste.
+    before: String,
+    /// Composer text right after the native paste, if it changed anything.
+    after: Option<String>,
+    content: Option<ClipboardContent>,
+    started: std::time::Instant,
+}
+
+/// How long after Ctrl+V an input change counts as the native paste.
+const PASTE_WINDOW_MS: u64 = 500;
+
 #[component]
 pub fn message_input(channel_id: String, group_id: String, host: String) -> NodeHandle {
-    let input_text = Signal::new(String::new());
+    // Restore any draft left in this channel
+    let key = draft_key(&host, &group_id, &channel_id);
+    let draft = get_drafts_store().get_draft(&key);
+    let input_text = Signal::new(draft.as_ref().map(|d| d.text.clone()).unwrap_or_default());
     let message_type = Signal::new("message".to_string());
-    let reply_to = Signal::new(None::<String>);
-    let pending = Signal::new(Vec::<PendingAttachment>::new());
+    let reply_to = Signal::new(draft.as_ref().and_then(|d| d.reply_to.clone()));
+    let quote = Signal::new(draft.as_ref().and_then(|d| d.quote.clone()));
+    let pending = Signal::new(
+        draft
+            .map(|d| d.attachments.into_iter().map(PendingAttachment::from_attachment).collect())
+            .unwrap_or_else(Vec::<PendingAttachment>::new),
+    );
+
+    // Keep the draft in sync with the composer
+    Effect::new(move || {
+        let draft = Draft {
+            text: input_text.get().clone(),
+            reply_to: reply_to.get().clone(),
+            attachments: pending.get().iter().filter_map(|pa| pa.server.clone()).collect(),
+            quote: quote.get().clone(),
+            updated_at: chrono::Utc::now(),
+        };
+        get_drafts_store().set_draft(&key, draft);
+    });
+
+    // Pick up a quote started from a message in this channel
+    Effect::new(move || {
+        let compose_quote = get_messages_store().compose_quote;
+        if let Some(reference) = compose_quote.get().clone() {
+            quote.set(Some(reference));
+            compose_quote.set(None);
+        }
+    });
 
     let cid = Signal::new(channel_id.clone());
+    let gid = Signal::new(group_id.clone());
     let h = Signal::new(host.clone());
 
-    // Keyboard interceptor for clipboard image paste (Ctrl+V)
-    {
-        tracing::info!("Setting keyboard interceptor for clipboard paste");
-        rinch_core::set_keyboard_interceptor(move |key_data| {
-            tracing::info!("Keyboard interceptor called: key={}, ctrl={}", key_data.key, key_data.ctrl);
-            if key_data.ctrl && key_data.key == "v" {
-                // Try to get PNG bytes from clipboard (arboard first, then wl-paste fallback)
-                let png_bytes = get_clipboard_image_png();
-                if let Some(png_bytes) = png_bytes {
-                    // Encode as data URI for immediate synchronous preview in rinch
-                    let local_id = uuid::Uuid::new_v4().to_string();
-                    let preview_path = encode_data_uri(&png_bytes, "image/png");
-                    let size = png_bytes.len() as u64;
-                    pending.update(|atts| atts.push(PendingAttachment {
-                        local_id: local_id.clone(),
-                        filename: "clipboard-image.png".to_string(),
-                        mime: "image/png".to_string(),
-                        size,
-                        local_preview: preview_path,
-                        server: None,
-                    }));
-
-                    // Upload in background
-                    let client = get_auth_store().make_client();
-                    crate::runtime::spawn(
-                        async move {
-                            client
-                                .upload_file(png_bytes, "clipboard-image.png", "image/png")
-                                .await
-                        },
-                        move |result| match result {
-                            Ok(attachment) => {
-                                pending.update(|atts| {
-                                    if let Some(pa) = atts.iter_mut().find(|a| a.local_id == local_id) {
-                                        pa.server = Some(attachment);
-                                    }
-                                });
-                            }
-                            Err(e) => {
-                                tracing::error!("Clipboard image upload failed: {}", e);
-                                pending.update(|atts| atts.retain(|a| a.local_id != local_id));
+    // Re-evaluated whenever channel settings or our roles change
+    let access = move || get_members_store().channel_access(&gid.get(), &cid.get());
+    let send_type = move || {
+        let is_reply = reply_to.get().is_some();
+        effective_type(access().allowed_types(is_reply), &message_type.get())
+    };
+    let can_toggle = move || {
+        let access = access();
+        let allowed = access.allowed_types(reply_to.get().is_some());
+        allowed.contains(&MessageType::Message) && allowed.contains(&MessageType::Memo)
+    };
+
+    // Slash command output and errors, shown under the composer
+    let feedback = Signal::new(None::<String>);
+    // Row of the slash command autocomplete picked with the arrow keys;
+    // the first row is highlighted until then
+    let selected_suggestion = Signal::new(None::<usize>);
+    let paste_state = Signal::new(None::<PasteInProgress>);
+
+    // Upload a pending attachment in the background, streamed from disk.
+    // Images are scaled and stripped first unless `original` is set. Calling
+    // this again for the same attachment replaces the running upload.
+    let start_upload = move |local_id: String, path: PathBuf, original: bool| {
+        let source = match UploadSource::from_path(path) {
+            Ok(source) => source,
+            Err(e) => {
+                feedback.set(Some(format!("Couldn't read file: {}", e)));
+                pending.update(|atts| atts.retain(|a| a.local_id != local_id));
+                return;
+            }
+        };
+        let control = UploadControl::default();
+        // The upload keeps a pasted image's file until it is done with it
+        let mut temp_dir = None;
+        pending.update(|atts| {
+            if let Some(pa) = atts.iter_mut().find(|a| a.local_id == local_id) {
+                if let Some(previous) = pa.control.replace(control.clone()) {
+                    previous.cancel();
+                }
+                pa.server = None;
+                pa.uploaded = 0;
+                pa.send_original = original;
+                temp_dir = pa.temp_dir.clone();
+            }
+        });
+
+        let client = get_auth_store().make_client();
+        let settings = get_settings_store().preferences.get().images.clone();
+        // Reports from a replaced upload are dropped
+        let prepared_id = local_id.clone();
+        let prepared_control = control.clone();
+        let on_prepared = move |size: u64| {
+            if prepared_control.is_cancelled() {
+                return;
+            }
+            rinch::run_on_main_thread(move || {
+                pending.update(|atts| {
+                    if let Some(pa) = atts.iter_mut().find(|a| a.local_id == prepared_id) {
+                        pa.size = size;
+                    }
+                });
+            });
+        };
+        let progress_id = local_id.clone();
+        let progress_control = control.clone();
+        let on_progress = move |sent: u64| {
+            if progress_control.is_cancelled() {
+                return;
+            }
+            let id = progress_id.clone();
+            rinch::run_on_main_thread(move || {
+                pending.update(|atts| {
+                    if let Some(pa) = atts.iter_mut().find(|a| a.local_id == id) {
+                        pa.uploaded = sent;
+                    }
+                });
+            });
+        };
+        let task_control = control.clone();
+        crate::runtime::spawn(
+            async move {
+                let filename = source.filename.clone();
+                let result = upload_attachment(&client, source, original, settings, &task_control, on_prepared, on_progress).await;
+                drop(temp_dir);
+                (filename, result)
+            },
+            move |(filename, result)| {
+                // A replaced upload no longer owns the attachment
+                let current = pending.get().iter().any(|a| a.local_id == local_id && a.control.as_ref() == Some(&control));
+                if !current {
+                    return;
+                }
+                match result {
+                    Ok(attachment) => {
+                        pending.update(|atts| {
+                            if let Some(pa) = atts.iter_mut().find(|a| a.local_id == local_id) {
+                                pa.uploaded = pa.size;
+                                pa.server = Some(attachment);
+                                pa.control = None;
                             }
-                        },
-                    );
-                    return true;
+                        });
+                    }
+                    Err(e) => {
+                        if e != UploadError::Cancelled {
+                            tracing::error!("Upload failed: {}", e);
+                            feedback.set(Some(format!("Couldn't upload {}: {}", filename, e)));
+                        }
+                        pending.update(|atts| atts.retain(|a| a.local_id != local_id));
+                    }
+                }
+            },
+        );
+    };
+
+    // "Send original" toggled on a pending image: start its upload over
+    let toggle_original = Signal::new(None::<String>);
+    Effect::new(move || {
+        let Some(id) = toggle_original.get() else {
+            return;
+        };
+        toggle_original.set(None);
+        let Some(pa) = pending.get().iter().find(|a| a.local_id == id).cloned() else {
+            return;
+        };
+        if let Some(path) = pa.source {
+            start_upload(id, path, !pa.send_original);
+        }
+    });
+
+    // Attach a file from disk: previewed right away, uploaded in the background
+    let add_file = move |path: PathBuf| {
+        let source = match UploadSource::from_path(path) {
+            Ok(source) => source,
+            Err(e) => {
+                feedback.set(Some(format!("Couldn't read file: {}", e)));
+                return;
+            }
+        };
+
+        let local_id = uuid::Uuid::new_v4().to_string();
+        // For image files, use the original file path as local preview
+        let local_preview = if source.mime.starts_with("image/") {
+            source.path.to_string_lossy().to_string()
+        } else {
+            String::new()
+        };
+        pending.update(|atts| atts.push(PendingAttachment {
+            local_id: local_id.clone(),
+            filename: source.filename.clone(),
+            mime: source.mime.clone(),
+            size: source.size,
+            local_preview,
+            server: None,
+            uploaded: 0,
+            control: None,
+            source: Some(source.path.clone()),
+            send_original: false,
+            temp_dir: None,
+        }));
+        start_upload(local_id, source.path, false);
+    };
+
+    // Pick up files dropped onto the channel view
+    Effect::new(move || {
+        let compose_files = get_messages_store().compose_files;
+        let files = compose_files.get().clone();
+        if files.is_empty() {
+            return;
+        }
+        compose_files.set(Vec::new());
+        for path in files {
+            add_file(path);
+        }
+    });
+
+    // Apply a Ctrl+V once the clipboard is read and the native paste has
+    // happened (or the window for it has passed): images and files become
+    // attachments, formatted text replaces its plain version
+    let finish_paste = move || {
+        let Some(paste) = paste_state.get().clone() else {
+            return;
+        };
+        let Some(content) = paste.content.clone() else {
+            return;
+        };
+        let window = std::time::Duration::from_millis(PASTE_WINDOW_MS);
+        if paste.after.is_none() && paste.started.elapsed() < window {
+            return;
+        }
+        paste_state.set(None);
+
+        let replacement = match &content {
+            ClipboardContent::Text => None,
+            ClipboardContent::Markdown(markdown) => Some(markdown.as_str()),
+            ClipboardContent::Image { .. } | ClipboardContent::Files(_) => Some(""),
+        };
+        if let (Some(after), Some(replacement)) = (&paste.after, replacement) {
+            // Left alone if the text was edited since
+            if input_text.get().as_str() == after.as_str() {
+                input_text.set(crate::paste::splice_paste(&paste.before, after, replacement));
+            }
+        }
+
+        match content {
+            ClipboardContent::Text => {}
+            ClipboardContent::Image { dir, path, preview, size } => {
+                let local_id = uuid::Uuid::new_v4().to_string();
+                pending.update(|atts| atts.push(PendingAttachment {
+                    local_id: local_id.clone(),
+                    filename: "clipboard-image.png".to_string(),
+                    mime: "image/png".to_string(),
+                    size,
+                    local_preview: preview,
+                    server: None,
+                    uploaded: 0,
+                    control: None,
+                    source: Some(path.clone()),
+                    send_original: false,
+                    temp_dir: Some(dir),
+                }));
+                start_upload(local_id, path, false);
+            }
+            ClipboardContent::Files(files) => {
+                for path in files {
+                    add_file(path);
+                }
+            }
+            ClipboardContent::Markdown(markdown) => {
+                // No plain text was pasted to replace
+                if paste.after.is_none() {
+                    input_text.update(|text| {
+                        if !text.is_empty() && !text.ends_with(char::is_whitespace) {
+                            text.push(' ');
+                        }
+                        text.push_str(&markdown);
+                    });
+                }
+            }
+        }
+    };
+
+    // Composer keys: command suggestions and clipboard paste (Ctrl+V) of
+    // images, copied files and rich text
+    {
+        tracing::info!("Setting keyboard handler for the composer");
+        crate::keyboard::set_composer_handler(move |key, ctrl| {
+            // Arrows move through command suggestions and Tab picks one.
+            // Enter picks one too, unless the typed name is already a whole
+            // command and no row was chosen: then it sends.
+            let text = input_text.get().clone();
+            let suggestions = crate::commands::suggestions(&text);
+            if !ctrl && !suggestions.is_empty() {
+                let count = suggestions.len();
+                let chosen = selected_suggestion.get();
+                let current = chosen.unwrap_or(0).min(count - 1);
+                match key {
+                    "ArrowDown" => selected_suggestion.set(Some((current + 1) % count)),
+                    "ArrowUp" => selected_suggestion.set(Some((current + count - 1) % count)),
+                    "Enter" if chosen.is_none()
+                        && crate::commands::parse(&text).and_then(|(name, _)| crate::commands::find(name)).is_some() =>
+                    {
+                        return false;
+                    }
+                    "Tab" | "Enter" => {
+                        input_text.set(format!("/{} ", suggestions[current].name));
+                        selected_suggestion.set(None);
+                    }
+                    _ => return false,
                 }
+                return true;
+            }
+            if ctrl && key == "v" {
+                let id = uuid::Uuid::new_v4().to_string();
+                paste_state.set(Some(PasteInProgress {
+                    id: id.clone(),
+                    before: input_text.get().clone(),
+                    after: None,
+                    content: None,
+                    started: std::time::Instant::now(),
+                }));
+                crate::runtime::spawn(
+                    async {
+                        tokio::task::spawn_blocking(read_clipboard)
+                            .await
+                            .unwrap_or(ClipboardContent::Text)
+                    },
+                    move |content| {
+                        paste_state.update(|state| {
+                            if let Some(state) = state.as_mut().filter(|s| s.id == id) {
+                                state.content = Some(content);
+                            }
+                        });
+                        finish_paste();
+                    },
+                );
+                // Settles a paste that never changed the text
+                crate::runtime::spawn(
+                    async { tokio::time::sleep(std::time::Duration::from_millis(PASTE_WINDOW_MS)).await },
+                    move |()| finish_paste(),
+                );
+                // Let the text input paste any plain text at the cursor
+                return false;
             }
             false
         });
     }
 
-    let on_send = move || {
-        let text = input_text.get().clone();
-        if text.trim().is_empty() && pending.get().is_empty() {
-            return;
+    // What the composer would post with `text`, if it can post at all. A
+    // command may ask for a specific type, which the channel must allow.
+    let compose = move |text: String, requested: Option<MessageType>| -> Option<(String, Option<String>, MessageOptions)> {
+        if text.trim().is_empty() && pending.get().is_empty() && quote.get().is_none() {
+            return None;
         }
 
         // Only send attachments that have finished uploading
@@ -91,9 +461,81 @@ pub fn message_input(channel_id: String, group_id: String, host: String) -> Node
         // Don't send if any are still uploading
         if attachments.len() != pending.get().len() {
             tracing::warn!("Some attachments still uploading, please wait");
-            return;
+            return None;
         }
 
+        // The channel may not allow this kind of post at all
+        let send_as = match requested {
+            Some(mt) if access().allows(&mt, reply_to.get().is_some()) => Some(mt),
+            Some(mt) => {
+                feedback.set(Some(format!("This channel doesn't allow {}s here", type_label(&mt))));
+                None
+            }
+            None => send_type(),
+        };
+        let Some(send_as) = send_as else {
+            tracing::warn!("Channel doesn't allow messages or memos here");
+            return None;
+        };
+
+        let text = crate::emoji::convert_shortcodes(&text);
+        let options = MessageOptions {
+            message_type: match send_as {
+                MessageType::Message => None,
+                other => Some(other),
+            },
+            attachments,
+            tags: crate::tags::extract_hashtags(&text),
+            reference: quote.get().clone(),
+            ..MessageOptions::default()
+        };
+        Some((text, reply_to.get().clone(), options))
+    };
+
+    let clear_composer = move || {
+        feedback.set(None);
+        input_text.set(String::new());
+        reply_to.set(None);
+        quote.set(None);
+        pending.set(Vec::new());
+    };
+
+    // Run a slash command in the input. Gives back the text to post and the
+    // type it asks for, or `None` when nothing should be posted.
+    let run_command = move || -> Option<(String, Option<MessageType>)> {
+        let raw = input_text.get().clone();
+        let (host, group_id, channel_id) = (h.get().clone(), gid.get().clone(), cid.get().clone());
+        let parent_id = reply_to.get().clone();
+        let ctx = crate::commands::CommandContext {
+            host: &host,
+            group_id: &group_id,
+            channel_id: &channel_id,
+            parent_id: parent_id.as_deref(),
+            feedback,
+        };
+        match crate::commands::execute(&ctx, &raw) {
+            None => Some((raw, None)),
+            Some(CommandOutcome::Send { body, message_type }) => Some((body, message_type)),
+            Some(CommandOutcome::Handled) => {
+                input_text.set(String::new());
+                None
+            }
+            Some(CommandOutcome::Invalid(reason)) => {
+                feedback.set(Some(reason));
+                None
+            }
+        }
+    };
+
+    let on_send = move || {
+        let Some((text, requested)) = run_command() else {
+            return;
+        };
+
+        let Some((text, parent, options)) = compose(text, requested) else {
+            return;
+        };
+
         let domain = get_auth_store().domain();
         let h_val = h.get().clone();
         let ws_host = if h_val.is_empty() { domain } else { h_val };
@@ -101,26 +543,14 @@ pub fn message_input(channel_id: String, group_id: String, host: String) -> Node
 
         if let Some(handle) = crate::ws::get_handle(&ws_host) {
             let nonce = uuid::Uuid::new_v4().to_string();
-            let mt = match message_type.get().as_str() {
-                "memo" => Some(rorumall_shared::MessageType::Memo),
-                "article" => Some(rorumall_shared::MessageType::Article),
-                _ => None,
-            };
-
-            let parent = reply_to.get().clone();
-
             let result = if let Some(pid) = parent {
-                handle.send_reply(&cid_val, &text, &nonce, &pid, mt, attachments)
+                handle.send_reply(&cid_val, &text, &nonce, &pid, options)
             } else {
-                handle.send_message_with_options(&cid_val, &text, &nonce, None, mt, attachments)
+                handle.send_message_with_options(&cid_val, &text, &nonce, options)
             };
 
             match result {
-                Ok(()) => {
-                    input_text.set(String::new());
-                    reply_to.set(None);
-                    pending.set(Vec::new());
-                }
+                Ok(()) => clear_composer(),
                 Err(e) => {
                     tracing::error!("Failed to send message: {}", e);
                 }
@@ -128,63 +558,58 @@ pub fn message_input(channel_id: String, group_id: String, host: String) -> Node
         }
     };
 
+    // Send later: the picker hands back a time, and the composed message
+    // joins the scheduled queue
+    let show_schedule = Signal::new(false);
+    let schedule_at = Signal::new(None::<chrono::DateTime<chrono::Utc>>);
+    Effect::new(move || {
+        let Some(send_at) = schedule_at.get() else {
+            return;
+        };
+        schedule_at.set(None);
+        let Some((text, requested)) = run_command() else {
+            return;
+        };
+        let Some((text, parent, options)) = compose(text, requested) else {
+            return;
+        };
+        let channel_id = cid.get().clone();
+        let channel_name = get_groups_store()
+            .channels
+            .get()
+            .iter()
+            .find(|c| c.id == channel_id)
+            .map(|c| c.name.clone())
+            .unwrap_or_default();
+        get_scheduled_store().schedule(ScheduledMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            host: h.get().clone(),
+            group_id: gid.get().clone(),
+            channel_id,
+            channel_name,
+            body: text,
+            send_at,
+            title: options.title,
+            message_type: options.message_type,
+            parent_id: parent,
+            attachments: options.attachments,
+            tags: options.tags,
+            reference: options.reference,
+            remote_id: None,
+        });
+        clear_composer();
+    });
+
     let host_for_article = host.clone();
     let gid_for_article = group_id.clone();
     let cid_for_article = channel_id.clone();
 
     let on_file_pick = move || {
-        let path = rinch::dialogs::open_file()
+        let paths = rinch::dialogs::open_file()
             .add_filter("All files", &["*"])
-            .pick_file();
-        if let Some(path) = path {
-            let filename = path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| "file".to_string());
-            let mime = mime_from_extension(&filename);
-
-            // Read file size for the preview
-            let file_size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
-
-            // Add preview immediately
-            let local_id = uuid::Uuid::new_v4().to_string();
-            // For image files, use the original file path as local preview
-            let local_preview = if mime.starts_with("image/") {
-                path.to_string_lossy().to_string()
-            } else {
-                String::new()
-            };
-            pending.update(|atts| atts.push(PendingAttachment {
-                local_id: local_id.clone(),
-                filename: filename.clone(),
-                mime: mime.clone(),
-                size: file_size,
-                local_preview,
-                server: None,
-            }));
-
-            // Upload in background
-            let client = get_auth_store().make_client();
-            crate::runtime::spawn(
-                async move {
-                    let data = std::fs::read(&path)
-                        .map_err(|e| rorumall_shared::ApiError::Network(e.to_string()))?;
-                    client.upload_file(data, &filename, &mime).await
-                },
-                move |result| match result {
-                    Ok(attachment) => {
-                        pending.update(|atts| {
-                            if let Some(pa) = atts.iter_mut().find(|a| a.local_id == local_id) {
-                                pa.server = Some(attachment);
-                            }
-                        });
-                    }
-                    Err(e) => {
-                        tracing::error!("Upload failed: {}", e);
-                        pending.update(|atts| atts.retain(|a| a.local_id != local_id));
-                    }
-                },
-            );
+            .pick_files();
+        for path in paths.unwrap_or_default() {
+            add_file(path);
         }
     };
 
@@ -192,6 +617,19 @@ pub fn message_input(channel_id: String, group_id: String, host: String) -> Node
         div {
             style: "border-top: 1px solid var(--rinch-color-dark-4, #373a40); padding: 12px 16px; background: var(--rinch-color-dark-7, #1a1b1e);",
 
+            // Read-only channels explain themselves instead of showing a composer
+            if let Some(reason) = access().read_only_reason() {
+                Group {
+                    gap: "xs",
+                    {render_tabler_icon(__scope, TablerIcon::Lock, TablerIconStyle::Outline)}
+                    Text {
+                        size: "sm",
+                        color: "dimmed",
+                        {reason}
+                    }
+                }
+            }
+
             // Reply indicator
             if reply_to.get().is_some() {
                 Group {
@@ -201,7 +639,7 @@ pub fn message_input(channel_id: String, group_id: String, host: String) -> Node
                     Text {
                         size: "xs",
                         color: "dimmed",
-                        {|| { let p = reply_to.get().clone().unwrap_or_default(); format!("Replying to {}...", &p[..8.min(p.len())]) }}
+                        {|| { let p = reply_to.get().clone().unwrap_or_default(); format!("Replying to {}", reply_label(&cid.get(), &p)) }}
                     }
 
                     ActionIcon {
@@ -213,6 +651,71 @@ pub fn message_input(channel_id: String, group_id: String, host: String) -> Node
                 }
             }
 
+            // Quoted message
+            if let Some(reference) = quote.get().clone() {
+                Group {
+                    gap: "xs",
+                    style: "margin-bottom: 8px; align-items: flex-start;",
+
+                    div {
+                        style: "flex: 1; min-width: 0;",
+                        {crate::components::messages::reference::reference_card(__scope, reference)}
+                    }
+
+                    ActionIcon {
+                        variant: "subtle",
+                        size: "xs",
+                        onclick: move || quote.set(None),
+                        {render_tabler_icon(__scope, TablerIcon::X, TablerIconStyle::Outline)}
+                    }
+                }
+            }
+
+            // Slash command autocomplete and argument hint
+            if !crate::commands::suggestions(&input_text.get()).is_empty() {
+                Stack {
+                    gap: "2px",
+                    style: "margin-bottom: 8px;",
+
+                    for (index, command) in crate::commands::suggestions(&input_text.get()).into_iter().enumerate() {
+                        let completion = format!("/{} ", command.name);
+                        let highlighted = index == selected_suggestion.get().unwrap_or(0);
+                        div {
+                            key: command.name.to_string(),
+                            style: {format!(
+                                "display: flex; gap: 8px; padding: 4px 8px; border-radius: 4px; cursor: pointer;{}",
+                                if highlighted { " background: var(--rinch-color-dark-5, #2c2e33);" } else { "" }
+                            )},
+                            onclick: move || {
+                                input_text.set(completion.clone());
+                                selected_suggestion.set(None);
+                            },
+
+                            Text {
+                                size: "sm",
+                                weight: "600",
+                                {command.usage()}
+                            }
+
+                            Text {
+                                size: "sm",
+                                color: "dimmed",
+                                {command.description}
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(command) = crate::commands::current(&input_text.get()) {
+                Text {
+                    size: "xs",
+                    color: "dimmed",
+                    style: "margin-bottom: 6px;",
+                    {format!("{} — {}", command.usage(), command.description)}
+                }
+            }
+
             // Pending attachments with instant preview
             if !pending.get().is_empty() {
                 div {
@@ -221,68 +724,139 @@ pub fn message_input(channel_id: String, group_id: String, host: String) -> Node
                     for pa in pending.get().clone() {
                         div {
                             key: pa.local_id.clone(),
-                            {pending_attachment_preview(__scope, pa, pending)}
+                            {pending_attachment_preview(__scope, pa, pending, toggle_original)}
                         }
                     }
                 }
             }
 
-            Group {
-                gap: "sm",
+            if access().read_only_reason().is_none() {
+                Group {
+                    gap: "sm",
 
-                // Message type selector
-                ActionIcon {
-                    variant: "subtle",
-                    onclick: move || {
-                        let current = message_type.get().clone();
-                        let next = match current.as_str() {
-                            "message" => "memo",
-                            "memo" => "message",
-                            _ => "message",
-                        };
-                        message_type.set(next.to_string());
-                    },
-                    {match message_type.get().as_str() {
-                        "memo" => render_tabler_icon(__scope, TablerIcon::Note, TablerIconStyle::Outline),
-                        _ => render_tabler_icon(__scope, TablerIcon::Message, TablerIconStyle::Outline),
-                    }}
-                }
+                    // Message type selector, only when the channel allows both
+                    if can_toggle() {
+                        ActionIcon {
+                            variant: "subtle",
+                            onclick: move || {
+                                let current = message_type.get().clone();
+                                let next = match current.as_str() {
+                                    "message" => "memo",
+                                    "memo" => "message",
+                                    _ => "message",
+                                };
+                                message_type.set(next.to_string());
+                            },
+                            {match send_type() {
+                                Some(MessageType::Memo) => render_tabler_icon(__scope, TablerIcon::Note, TablerIconStyle::Outline),
+                                _ => render_tabler_icon(__scope, TablerIcon::Message, TablerIconStyle::Outline),
+                            }}
+                        }
+                    }
 
-                // File attachment
-                ActionIcon {
-                    variant: "subtle",
-                    onclick: move || on_file_pick(),
-                    {render_tabler_icon(__scope, TablerIcon::Paperclip, TablerIconStyle::Outline)}
-                }
+                    // Article compose
+                    if access().allows(&MessageType::Article, false) && reply_to.get().is_none() {
+                        ActionIcon {
+                            variant: "subtle",
+                            onclick: move || navigate(AppRoute::ComposeArticle {
+                                host: host_for_article.clone(),
+                                group_id: gid_for_article.clone(),
+                                channel_id: cid_for_article.clone(),
+                            }),
+                            {render_tabler_icon(__scope, TablerIcon::Article, TablerIconStyle::Outline)}
+                        }
+                    }
 
-                // Article compose
-                ActionIcon {
-                    variant: "subtle",
-                    onclick: move || navigate(AppRoute::ComposeArticle {
-                        host: host_for_article.clone(),
-                        group_id: gid_for_article.clone(),
-                        channel_id: cid_for_article.clone(),
-                    }),
-                    {render_tabler_icon(__scope, TablerIcon::Article, TablerIconStyle::Outline)}
-                }
+                    if send_type().is_some() {
+                        // File attachment
+                        ActionIcon {
+                            variant: "subtle",
+                            onclick: move || on_file_pick(),
+                            {render_tabler_icon(__scope, TablerIcon::Paperclip, TablerIconStyle::Outline)}
+                        }
+
+                        {crate::components::messages::emoji_picker::emoji_picker(__scope, gid.get().clone(), input_text)}
+
+                        // Text input
+                        TextInput {
+                            placeholder: {match send_type() {
+                                Some(MessageType::Memo) => "Write a memo...",
+                                _ => "Type a message...",
+                            }},
+                            style: "flex: 1;",
+                            value_fn: move || input_text.get().clone(),
+                            oninput: move |val: String| {
+                                // The first change after Ctrl+V is the native paste
+                                let pasting = paste_state.get().as_ref().is_some_and(|p| {
+                                    p.after.is_none()
+                                        && p.started.elapsed() < std::time::Duration::from_millis(PASTE_WINDOW_MS)
+                                });
+                                input_text.set(val.clone());
+                                selected_suggestion.set(None);
+                                if pasting {
+                                    paste_state.update(|p| {
+                                        if let Some(p) = p.as_mut() {
+                                            p.after = Some(val);
+                                        }
+                                    });
+                                    finish_paste();
+                                }
+                            },
+                            onsubmit: move || on_send(),
+                        }
+
+                        // Send later
+                        Tooltip {
+                            label: "Send later",
+
+                            ActionIcon {
+                                variant: "subtle",
+                                onclick: move || show_schedule.set(true),
+                                {render_tabler_icon(__scope, TablerIcon::Clock, TablerIconStyle::Outline)}
+                            }
+                        }
 
-                // Text input
-                TextInput {
-                    placeholder: "Type a message...",
-                    style: "flex: 1;",
-                    value_fn: move || input_text.get().clone(),
-                    oninput: move |val: String| input_text.set(val),
-                    onsubmit: move || on_send(),
+                        // Send button
+                        ActionIcon {
+                            variant: "filled",
+                            color: "indigo",
+                            onclick: move || on_send(),
+                            {render_tabler_icon(__scope, TablerIcon::Send, TablerIconStyle::Outline)}
+                        }
+                    } else {
+                        Text {
+                            size: "sm",
+                            color: "dimmed",
+                            {if reply_to.get().is_some() { "This channel doesn't allow message or memo replies" } else { "Only articles can be posted in this channel" }}
+                        }
+                    }
                 }
+            }
 
-                // Send button
-                ActionIcon {
-                    variant: "filled",
-                    color: "indigo",
-                    onclick: move || on_send(),
-                    {render_tabler_icon(__scope, TablerIcon::Send, TablerIconStyle::Outline)}
+            if let Some(message) = feedback.get().clone() {
+                Group {
+                    gap: "xs",
+                    style: "margin-top: 6px; align-items: flex-start;",
+
+                    Text {
+                        size: "xs",
+                        color: "dimmed",
+                        style: "flex: 1; white-space: pre-wrap;",
+                        {message}
+                    }
+
+                    ActionIcon {
+                        variant: "subtle",
+                        size: "xs",
+                        onclick: move || feedback.set(None),
+                        {render_tabler_icon(__scope, TablerIcon::X, TablerIconStyle::Outline)}
+                    }
                 }
             }
+
+            if show_schedule.get() {
+                {crate::components::messages::schedule::schedule_modal(__scope, show_schedule, schedule_at)}
+            }
         }
     }
 }
@@ -291,11 +865,16 @@ pub fn message_input(channel_id: String, group_id: String, host: String) -> Node
 fn pending_attachment_preview(
     pa: PendingAttachment,
     pending: Signal<Vec<PendingAttachment>>,
+    toggle_original: Signal<Option<String>>,
 ) -> NodeHandle {
     let is_image = pa.mime.starts_with("image/");
+    let can_process = pa.source.is_some() && crate::imaging::is_processable(&pa.mime);
+    let send_original = pa.send_original;
     let uploading = pa.server.is_none();
     let size = pa.size;
+    let percent = (pa.uploaded * 100).checked_div(size).unwrap_or(0).min(100);
     let local_id = Signal::new(pa.local_id.clone());
+    let control = pa.control.clone();
     let filename = Signal::new(pa.filename.clone());
 
     // Use local file for immediate preview, or server URL once uploaded
@@ -336,8 +915,19 @@ fn pending_attachment_preview(
 
             if uploading {
                 div {
-                    style: "position: absolute; bottom: 2px; left: 2px;",
+                    style: "position: absolute; bottom: 2px; left: 2px; display: flex; align-items: center; gap: 4px;",
                     Loader { size: "xs" }
+                    Text {
+                        size: "xs",
+                        {format!("{}%", percent)}
+                    }
+                }
+
+                div {
+                    style: "height: 3px; background: var(--rinch-color-dark-4, #373a40); border-radius: 2px; margin-top: 2px;",
+                    div {
+                        style: {format!("height: 100%; width: {}%; background: var(--rinch-color-indigo-6, #4c6ef5); border-radius: 2px;", percent)},
+                    }
                 }
             }
 
@@ -347,6 +937,19 @@ fn pending_attachment_preview(
                 {format_size(size)}
             }
 
+            if can_process {
+                Tooltip {
+                    label: {if send_original { "Scale down and strip location and camera data" } else { "Upload the file untouched, metadata included" }},
+
+                    Button {
+                        variant: "subtle",
+                        size: "xs",
+                        onclick: move || toggle_original.set(Some(local_id.get().clone())),
+                        {if send_original { "Compress" } else { "Send original" }}
+                    }
+                }
+            }
+
             // Remove button
             div {
                 style: "position: absolute; top: -4px; right: -4px;",
@@ -357,6 +960,10 @@ fn pending_attachment_preview(
                     size: "xs",
                     radius: "xl",
                     onclick: move || {
+                        // Removing an attachment mid-upload cancels it
+                        if let Some(control) = &control {
+                            control.cancel();
+                        }
                         let rid = local_id.get().clone();
                         pending.update(|atts| { atts.retain(|a| a.local_id != rid); });
                     },
@@ -367,6 +974,82 @@ fn pending_attachment_preview(
     }
 }
 
+/// Upload a picked or pasted file. Images `crate::imaging` can handle are
+/// prepared first and get their size and a thumbnail recorded; if decoding
+/// fails the file goes up as it is. `on_prepared` gets the final size.
+async fn upload_attachment(
+    client: &ApiClient,
+    source: UploadSource,
+    original: bool,
+    settings: ImageSettings,
+    control: &UploadControl,
+    on_prepared: impl FnOnce(u64) + Send,
+    progress: impl FnMut(u64) + Send + 'static,
+) -> Result<rorumall_shared::Attachment, UploadError> {
+    if !crate::imaging::is_processable(&source.mime) {
+        on_prepared(source.size);
+        return crate::upload::upload(client, &source, control, progress).await;
+    }
+
+    let (path, filename) = (source.path.clone(), source.filename.clone());
+    let prepared = tokio::task::spawn_blocking(move || crate::imaging::prepare(&path, &filename, original, &settings))
+        .await
+        .map_err(|e| UploadError::Io(e.to_string()))?;
+    let prepared = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            tracing::warn!("Couldn't process {}, uploading it as is: {}", source.filename, e);
+            on_prepared(source.size);
+            return crate::upload::upload(client, &source, control, progress).await;
+        }
+    };
+
+    let result = async {
+        let mut upload_source = UploadSource::from_path(prepared.path.clone())?;
+        upload_source.filename = prepared.filename.clone();
+        upload_source.mime = prepared.mime.clone();
+        on_prepared(upload_source.size);
+        if control.is_cancelled() {
+            return Err(UploadError::Cancelled);
+        }
+
+        // Without a thumbnail receivers just load the full image
+        let thumbnail_url = match client.upload_file(prepared.thumbnail.clone(), "thumbnail.jpg", "image/jpeg").await {
+            Ok(thumbnail) => Some(thumbnail.url),
+            Err(e) => {
+                tracing::warn!("Thumbnail upload failed: {}", e);
+                None
+            }
+        };
+
+        let mut attachment = crate::upload::upload(client, &upload_source, control, progress).await?;
+        attachment.width = Some(prepared.width);
+        attachment.height = Some(prepared.height);
+        attachment.thumbnail_url = thumbnail_url;
+        Ok(attachment)
+    }
+    .await;
+
+    if prepared.temporary {
+        let _ = std::fs::remove_file(&prepared.path);
+    }
+    result
+}
+
+/// Write a pasted image to its own temp directory, under the name the
+/// attachment should get. The directory goes away with the returned guard.
+fn save_clipboard_image(png_bytes: &[u8]) -> std::io::Result<(Arc<TempDir>, PathBuf)> {
+    let dir = TempDir(
+        std::env::temp_dir()
+            .join("rorumall-uploads")
+            .join(uuid::Uuid::new_v4().simple().to_string()),
+    );
+    std::fs::create_dir_all(&dir.0)?;
+    let path = dir.0.join("clipboard-image.png");
+    std::fs::write(&path, png_bytes)?;
+    Ok((Arc::new(dir), path))
+}
+
 /// Encode raw bytes as a base64 data URI for inline image display.
 fn encode_data_uri(bytes: &[u8], mime: &str) -> String {
     use base64::Engine;
@@ -374,48 +1057,105 @@ fn encode_data_uri(bytes: &[u8], mime: &str) -> String {
     format!("data:{};base64,{}", mime, b64)
 }
 
-/// Try to get an image from the clipboard as PNG bytes.
-/// Attempts arboard (X11) first, then falls back to `wl-paste` for Wayland.
-fn get_clipboard_image_png() -> Option<Vec<u8>> {
-    tracing::info!("get_clipboard_image_png: trying arboard...");
-    // Try arboard first (works on X11 and macOS/Windows)
-    match rinch::clipboard::paste_image() {
-        Ok(img_data) => {
-            tracing::info!("arboard: got image {}x{}", img_data.width, img_data.height);
-            if let Some(png) = encode_rgba_to_png(
-                img_data.width as u32,
-                img_data.height as u32,
-                &img_data.bytes,
-            ) {
-                tracing::info!("arboard: encoded to {} bytes PNG", png.len());
-                return Some(png);
-            }
+/// Read the clipboard for a Ctrl+V: list what it offers, then fetch only
+/// the most useful type. Blocks on `wl-paste` or `xclip`, so it runs off
+/// the UI thread.
+fn read_clipboard() -> ClipboardContent {
+    let Some((tool, targets)) = clipboard_targets() else {
+        // Neither tool is available; arboard still sees images
+        return arboard_image_png().map(image_content).unwrap_or(ClipboardContent::Text);
+    };
+    let offers = |target: &str| targets.iter().any(|t| t == target);
+
+    if targets.iter().any(|t| t.starts_with("image/")) {
+        let png = if offers("image/png") { tool.read(Some("image/png")) } else { None };
+        if let Some(png) = png.or_else(arboard_image_png) {
+            return image_content(png);
         }
-        Err(e) => {
-            tracing::info!("arboard failed: {}", e);
+    }
+
+    for target in ["text/uri-list", "x-special/gnome-copied-files"] {
+        if !offers(target) {
+            continue;
+        }
+        let files = tool
+            .read(Some(target))
+            .map(|list| crate::paste::parse_uri_list(&String::from_utf8_lossy(&list)))
+            .unwrap_or_default();
+        if !files.is_empty() {
+            return ClipboardContent::Files(files);
         }
     }
 
-    tracing::info!("get_clipboard_image_png: trying wl-paste...");
-    // Fallback: try wl-paste for Wayland
-    match std::process::Command::new("wl-paste")
-        .args(["--type", "image/png"])
-        .output()
-    {
-        Ok(output) => {
-            tracing::info!("wl-paste: status={}, stdout={} bytes, stderr={}",
-                output.status, output.stdout.len(), String::from_utf8_lossy(&output.stderr));
-            if output.status.success() && !output.stdout.is_empty() {
-                return Some(output.stdout);
-            }
+    // Formatted text becomes Markdown; plain text pastes as usual
+    if offers("text/html") {
+        let markdown = tool
+            .read(Some("text/html"))
+            .and_then(|html| crate::paste::rich_text_markdown(&String::from_utf8_lossy(&html)));
+        if let Some(markdown) = markdown {
+            return ClipboardContent::Markdown(markdown);
         }
+    }
+    ClipboardContent::Text
+}
+
+/// A pasted image, saved so it goes through the same pipeline as a picked file.
+fn image_content(png: Vec<u8>) -> ClipboardContent {
+    match save_clipboard_image(&png) {
+        Ok((dir, path)) => ClipboardContent::Image {
+            dir,
+            path,
+            preview: encode_data_uri(&png, "image/png"),
+            size: png.len() as u64,
+        },
         Err(e) => {
-            tracing::info!("wl-paste failed to run: {}", e);
+            tracing::error!("Failed to save clipboard image: {}", e);
+            ClipboardContent::Text
         }
     }
+}
+
+/// Command-line clipboard access: `wl-paste` on Wayland, `xclip` on X11.
+#[derive(Clone, Copy)]
+enum ClipboardTool {
+    WlPaste,
+    Xclip,
+}
+
+impl ClipboardTool {
+    /// Content of `target`, or the list of offered targets for `None`.
+    fn read(self, target: Option<&str>) -> Option<Vec<u8>> {
+        let (program, args): (&str, Vec<&str>) = match (self, target) {
+            (Self::WlPaste, None) => ("wl-paste", vec!["--list-types"]),
+            (Self::WlPaste, Some(target)) => ("wl-paste", vec!["--no-newline", "--type", target]),
+            (Self::Xclip, target) => (
+                "xclip",
+                vec!["-selection", "clipboard", "-target", target.unwrap_or("TARGETS"), "-out"],
+            ),
+        };
+        let output = std::process::Command::new(program).args(args).output().ok()?;
+        (output.status.success() && !output.stdout.is_empty()).then_some(output.stdout)
+    }
+}
 
-    tracing::info!("get_clipboard_image_png: no image found");
-    None
+/// The types the clipboard offers, from the first tool that answers.
+fn clipboard_targets() -> Option<(ClipboardTool, Vec<String>)> {
+    [ClipboardTool::WlPaste, ClipboardTool::Xclip].into_iter().find_map(|tool| {
+        let list = tool.read(None)?;
+        let targets = String::from_utf8_lossy(&list).lines().map(|l| l.trim().to_string()).collect();
+        Some((tool, targets))
+    })
+}
+
+/// A clipboard image through arboard (X11, macOS, Windows), as PNG bytes.
+fn arboard_image_png() -> Option<Vec<u8>> {
+    match rinch::clipboard::paste_image() {
+        Ok(img_data) => encode_rgba_to_png(img_data.width as u32, img_data.height as u32, &img_data.bytes),
+        Err(e) => {
+            tracing::debug!("No clipboard image through arboard: {}", e);
+            None
+        }
+    }
 }
 
 /// Encode RGBA pixel data to PNG bytes in memory.
@@ -445,7 +1185,28 @@ fn truncate_filename(name: &str, max: usize) -> String {
     }
 }
 
-fn mime_from_extension(filename: &str) -> String {
+fn type_label(mt: &MessageType) -> &'static str {
+    match mt {
+        MessageType::Message => "message",
+        MessageType::Memo => "memo",
+        MessageType::Article => "article",
+    }
+}
+
+/// The type a composed post goes out as: the selected one if the channel
+/// allows it, otherwise whichever of message or memo it does allow. `None`
+/// when neither is allowed.
+fn effective_type(allowed: &[MessageType], selected: &str) -> Option<MessageType> {
+    let selected = match selected {
+        "memo" => MessageType::Memo,
+        _ => MessageType::Message,
+    };
+    [selected, MessageType::Message, MessageType::Memo]
+        .into_iter()
+        .find(|t| allowed.contains(t))
+}
+
+pub fn mime_from_extension(filename: &str) -> String {
     let ext = filename.rsplit('.').next().unwrap_or("").to_lowercase();
     match ext.as_str() {
         "png" => "image/png",
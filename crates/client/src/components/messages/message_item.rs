@@ -1,30 +1,83 @@
 use rinch::prelude::*;
+use crate::components::messages::reply_thread::reply_label;
 use crate::stores::{get_members_store, StoredMessage};
 use rorumall_shared::MessageType;
 
+/// Render a message by type, followed by any quoted or forwarded message and
+/// link previews for messages and memos. Outside the thread panel a thread
+/// summary line with reply count and an "open thread" action is shown below it,
+/// alongside reactions and pin, quote and forward actions.
 #[component]
-pub fn message_item(msg: StoredMessage, group_id: String) -> NodeHandle {
-    match msg.message_type {
+pub fn message_item(msg: StoredMessage, group_id: String, channel_id: String, in_thread: bool) -> NodeHandle {
+    let message_id = msg.id.clone();
+    let pin_group_id = group_id.clone();
+    let tags = msg.tags.clone();
+    let reference = msg.reference.clone();
+    let action_msg = msg.clone();
+    let preview_content = if msg.message_type == MessageType::Article {
+        None
+    } else {
+        Some(msg.content.clone())
+    };
+    let body = match msg.message_type {
         MessageType::Article => {
-            crate::components::messages::article_item::article_item(__scope, msg, group_id)
+            crate::components::messages::article_item::article_item(__scope, msg, group_id, channel_id.clone())
         }
         MessageType::Memo => {
             crate::components::messages::memo_item::memo_item(__scope, msg, group_id)
         }
         MessageType::Message => {
-            chat_message(__scope, msg, group_id)
+            chat_message(__scope, msg, group_id, channel_id.clone())
+        }
+    };
+
+    rsx! {
+        div {
+            {body}
+
+            if let Some(reference) = reference.clone() {
+                div {
+                    style: "padding-left: 52px;",
+                    {crate::components::messages::reference::reference_card(__scope, reference)}
+                }
+            }
+
+            if !tags.is_empty() {
+                div {
+                    style: "padding-left: 52px;",
+                    {crate::components::messages::tags::tag_chips(__scope, tags.clone())}
+                }
+            }
+
+            if let Some(content) = preview_content.clone() {
+                div {
+                    style: "padding-left: 52px;",
+                    {crate::components::messages::link_previews::link_previews(__scope, content)}
+                }
+            }
+
+            if !in_thread {
+                div {
+                    style: "padding-left: 52px; display: flex; align-items: center; gap: 4px;",
+                    {crate::components::messages::reactions::reaction_bar(__scope, pin_group_id.clone(), channel_id.clone(), message_id.clone())}
+                    {crate::components::messages::pins::pin_button(__scope, pin_group_id.clone(), channel_id.clone(), message_id.clone())}
+                    {crate::components::messages::reference::quote_button(__scope, action_msg.clone(), channel_id.clone())}
+                    {crate::components::messages::reference::forward_button(__scope, action_msg.clone(), channel_id.clone())}
+                    {crate::components::messages::reply_thread::thread_summary(__scope, message_id.clone(), channel_id.clone())}
+                }
+            }
         }
     }
 }
 
 #[component]
-fn chat_message(msg: StoredMessage, group_id: String) -> NodeHandle {
+fn chat_message(msg: StoredMessage, group_id: String, channel_id: String) -> NodeHandle {
     let user_display = msg.user_id.split('@').next().unwrap_or(&msg.user_id).to_string();
     let time = msg.created_at.format("%H:%M").to_string();
     let has_attachments = !msg.attachments.is_empty();
     let parent_id = Signal::new(msg.parent_id.clone());
+    let cid = Signal::new(channel_id);
     let attachments = Signal::new(msg.attachments.clone());
-    let content = Signal::new(msg.content.clone());
 
     let avatar_url = {
         let members = get_members_store()
@@ -72,14 +125,11 @@ fn chat_message(msg: StoredMessage, group_id: String) -> NodeHandle {
                         size: "xs",
                         color: "dimmed",
                         style: "margin-bottom: 4px;",
-                        {|| { let p = parent_id.get().clone().unwrap_or_default(); format!("Replying to {}", &p[..8.min(p.len())]) }}
+                        {|| { let p = parent_id.get().clone().unwrap_or_default(); format!("Replying to {}", reply_label(&cid.get(), &p)) }}
                     }
                 }
 
-                Text {
-                    size: "sm",
-                    {content.get().clone()}
-                }
+                {crate::components::messages::message_body::message_body(__scope, msg.content.clone(), msg.mime.clone(), group_id.clone())}
 
                 // Attachments
                 if has_attachments {
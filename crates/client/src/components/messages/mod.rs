@@ -1,5 +1,18 @@
 pub mod article_item;
+pub mod emoji_picker;
+pub mod export;
+pub mod lightbox;
+pub mod markdown_view;
+pub mod media_gallery;
 pub mod memo_item;
+pub mod link_previews;
+pub mod message_body;
 pub mod message_input;
 pub mod message_item;
+pub mod pins;
+pub mod reactions;
+pub mod reference;
 pub mod reply_thread;
+pub mod schedule;
+pub mod tags;
+pub mod thread_panel;
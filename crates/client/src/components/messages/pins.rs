@@ -0,0 +1,118 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use crate::stores::{can_pin, get_messages_store, get_pins_store};
+
+/// Pin toggle shown under a message for owners and admins; everyone else
+/// only sees a marker on pinned messages.
+#[component]
+pub fn pin_button(group_id: String, channel_id: String, message_id: String) -> NodeHandle {
+    let gid = Signal::new(group_id);
+    let cid = Signal::new(channel_id);
+    let mid = Signal::new(message_id);
+    let pinned = move || get_pins_store().is_pinned(&cid.get(), &mid.get());
+
+    rsx! {
+        div {
+            if can_pin(&gid.get()) {
+                Tooltip {
+                    label: {if pinned() { "Unpin".to_string() } else { "Pin to channel".to_string() }},
+
+                    ActionIcon {
+                        variant: "subtle",
+                        size: "xs",
+                        color: {if pinned() { "indigo".to_string() } else { "gray".to_string() }},
+                        onclick: move || get_pins_store().toggle(&gid.get(), &cid.get(), &mid.get()),
+                        {match pinned() {
+                            true => render_tabler_icon(__scope, TablerIcon::PinnedOff, TablerIconStyle::Outline),
+                            false => render_tabler_icon(__scope, TablerIcon::Pin, TablerIconStyle::Outline),
+                        }}
+                    }
+                }
+            } else if pinned() {
+                Tooltip {
+                    label: "Pinned",
+                    {render_tabler_icon(__scope, TablerIcon::Pin, TablerIconStyle::Outline)}
+                }
+            }
+        }
+    }
+}
+
+/// Side drawer listing a channel's pinned messages, newest pin first.
+/// Clicking one highlights it in the channel.
+#[component]
+pub fn pinned_drawer(group_id: String, channel_id: String, open: Signal<bool>) -> NodeHandle {
+    let cid = Signal::new(channel_id.clone());
+
+    rsx! {
+        div {
+            style: "display: flex; flex-direction: column; height: 100%; overflow: hidden;",
+
+            // Header — matches channel header height
+            div {
+                style: "height: 52px; min-height: 52px; display: flex; align-items: center; justify-content: space-between; padding: 0 16px; border-bottom: 1px solid var(--rinch-color-dark-4, #373a40); flex-shrink: 0;",
+
+                Text {
+                    size: "md",
+                    weight: "600",
+                    "Pinned messages"
+                }
+
+                ActionIcon {
+                    variant: "subtle",
+                    size: "sm",
+                    onclick: move || open.set(false),
+                    {render_tabler_icon(__scope, TablerIcon::X, TablerIconStyle::Outline)}
+                }
+            }
+
+            div {
+                style: "flex: 1; overflow-y: auto; padding: 12px; min-height: 0;",
+
+                if get_pins_store().get_pins(&cid.get()).is_empty() {
+                    Text {
+                        size: "sm",
+                        color: "dimmed",
+                        "Nothing is pinned here yet. Memos make good standing notes for a channel."
+                    }
+                }
+
+                for pin in get_pins_store().get_pins(&cid.get()) {
+                    let message = get_messages_store().find_message(&cid.get(), &pin.message_id);
+                    let focus_id = pin.message_id.clone();
+                    let pinned_by = pin.pinned_by.split('@').next().unwrap_or(&pin.pinned_by).to_string();
+                    div {
+                        key: pin.message_id.clone(),
+                        style: "margin-bottom: 12px; cursor: pointer;",
+                        onclick: move || get_messages_store().focus_message.set(Some(focus_id.clone())),
+
+                        if let Some(msg) = message.clone() {
+                            {crate::components::messages::message_item::message_item(__scope, msg, group_id.clone(), channel_id.clone(), true)}
+                        } else {
+                            Text {
+                                size: "sm",
+                                color: "dimmed",
+                                "Loading pinned message..."
+                            }
+                        }
+
+                        Group {
+                            gap: "xs",
+                            style: "padding-left: 8px;",
+
+                            Text {
+                                size: "xs",
+                                color: "dimmed",
+                                {format!("Pinned by {}", pinned_by)}
+                            }
+
+                            if can_pin(&group_id) {
+                                {pin_button(__scope, group_id.clone(), channel_id.clone(), pin.message_id.clone())}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use rinch::prelude::*;
+use rorumall_shared::AddReactionRequest;
+use crate::emoji::ReactionChip;
+use crate::stores::{get_auth_store, get_emoji_store, get_messages_store};
+
+/// Reaction chips under a message, one per emoji with its count, and the
+/// button to add one. Clicking a chip adds the same reaction, or takes back
+/// the reader's own.
+#[component]
+pub fn reaction_bar(group_id: String, channel_id: String, message_id: String) -> NodeHandle {
+    let gid = Signal::new(group_id.clone());
+    let cid = Signal::new(channel_id);
+    let mid = Signal::new(message_id);
+    let picked = Signal::new(None::<String>);
+
+    let chips = move || {
+        let me = get_auth_store().user_id().unwrap_or_default();
+        let reactions = get_messages_store()
+            .find_message(&cid.get(), &mid.get())
+            .map(|m| m.reactions)
+            .unwrap_or_default();
+        crate::emoji::reaction_chips(&reactions, |r| crate::ws::manager::extract_user_id(&r.author) == me)
+    };
+
+    let toggle = move |chip: ReactionChip| {
+        let store = get_messages_store();
+        match chip.own {
+            Some(reaction_id) => store.unreact(&gid.get(), &cid.get(), &mid.get(), &reaction_id),
+            None => {
+                let req = AddReactionRequest { key: chip.key, unicode: chip.unicode, image: chip.image };
+                store.react(&gid.get(), &cid.get(), &mid.get(), req);
+            }
+        }
+    };
+
+    // An emoji from the picker: built-in ones by their unicode, custom
+    // group emoji by their reaction key with the image they show
+    Effect::new(move || {
+        let Some(emoji) = picked.get().clone() else {
+            return;
+        };
+        picked.set(None);
+        let custom = get_emoji_store()
+            .get_group_emoji(&gid.get())
+            .into_iter()
+            .find(|e| e.reaction_key() == emoji);
+        let req = match custom {
+            Some(custom) => AddReactionRequest { key: custom.reaction_key(), unicode: None, image: Some(custom.url) },
+            None if emoji.starts_with(':') => return,
+            None => AddReactionRequest { key: emoji.clone(), unicode: Some(emoji), image: None },
+        };
+        // Already there: the picker adds, it doesn't take back
+        if chips().iter().any(|c| c.key == req.key && c.own.is_some()) {
+            return;
+        }
+        get_messages_store().react(&gid.get(), &cid.get(), &mid.get(), req);
+    });
+
+    rsx! {
+        div {
+            style: "display: flex; flex-wrap: wrap; align-items: center; gap: 4px;",
+
+            for chip in chips() {
+                let clicked = chip.clone();
+                div {
+                    key: chip.key.clone(),
+                    class: {if chip.own.is_some() { "reaction-chip reaction-chip-own".to_string() } else { "reaction-chip".to_string() }},
+                    title: chip.key.clone(),
+                    onclick: move || toggle(clicked.clone()),
+
+                    if let Some(image) = chip.image.clone() {
+                        img { class: "custom-emoji", src: image, alt: chip.key.clone() }
+                    } else {
+                        {chip.unicode.clone().unwrap_or_else(|| chip.key.clone())}
+                    }
+
+                    Text {
+                        size: "xs",
+                        {chip.count.to_string()}
+                    }
+                }
+            }
+
+            {crate::components::messages::emoji_picker::reaction_picker(__scope, group_id.clone(), picked)}
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use rorumall_shared::{Channel, MessageReference, MessageType, REFERENCE_FORWARD, REFERENCE_QUOTE};
+use crate::navigation::navigate_to_message;
+use crate::stores::{get_auth_store, get_groups_store, get_messages_store, StoredMessage};
+use crate::ws::MessageOptions;
+
+const EXCERPT_CHARS: usize = 160;
+
+/// Reference to `msg` carrying where it lives and a short snapshot, so the
+/// card still renders for readers who can't load the original.
+pub fn reference_to(msg: &StoredMessage, channel_id: &str, kind: &str) -> MessageReference {
+    let location = get_messages_store().location(channel_id);
+    let excerpt = match (&msg.message_type, &msg.title) {
+        (MessageType::Article, Some(title)) if !title.is_empty() => title.clone(),
+        _ => crate::markdown::preview(&msg.content, EXCERPT_CHARS),
+    };
+    MessageReference {
+        r#type: kind.to_string(),
+        id: msg.id.clone(),
+        host: location.as_ref().map(|l| l.host.clone()),
+        group_id: location.as_ref().map(|l| l.group_id.clone()),
+        channel_id: Some(channel_id.to_string()),
+        author: Some(msg.user_id.clone()),
+        excerpt: Some(excerpt),
+    }
+}
+
+/// Quoted or forwarded message card. Clicking it jumps to the original.
+#[component]
+pub fn reference_card(reference: MessageReference) -> NodeHandle {
+    let author = reference
+        .author
+        .as_deref()
+        .map(|a| a.split('@').next().unwrap_or(a).to_string())
+        .unwrap_or_else(|| "Unknown".to_string());
+    let label = if reference.r#type == REFERENCE_FORWARD {
+        format!("Forwarded from {}", author)
+    } else {
+        author
+    };
+    let excerpt = reference.excerpt.clone().unwrap_or_default();
+    let target = reference.clone();
+
+    let on_click = move || {
+        let message_id = target.id.clone();
+        match (target.group_id.clone(), target.channel_id.clone()) {
+            (Some(group_id), Some(channel_id)) => navigate_to_message(
+                target.host.clone().unwrap_or_default(),
+                group_id,
+                channel_id,
+                message_id,
+            ),
+            // Older references only carry the id; try the open channel
+            _ => get_messages_store().focus_message.set(Some(message_id)),
+        }
+    };
+
+    rsx! {
+        div {
+            style: "margin-top: 6px; padding: 6px 10px; border-left: 3px solid var(--rinch-color-indigo-6, #4c6ef5); border-radius: 4px; background: var(--rinch-color-dark-6, #25262b); cursor: pointer;",
+            onclick: move || on_click(),
+
+            Group {
+                gap: "xs",
+
+                {match reference.r#type.as_str() {
+                    REFERENCE_FORWARD => render_tabler_icon(__scope, TablerIcon::ArrowForwardUp, TablerIconStyle::Outline),
+                    _ => render_tabler_icon(__scope, TablerIcon::Quote, TablerIconStyle::Outline),
+                }}
+
+                Text {
+                    size: "xs",
+                    weight: "600",
+                    {label}
+                }
+            }
+
+            Text {
+                size: "sm",
+                color: "dimmed",
+                {excerpt}
+            }
+        }
+    }
+}
+
+/// Quote action: hands the message to the channel composer.
+#[component]
+pub fn quote_button(msg: StoredMessage, channel_id: String) -> NodeHandle {
+    rsx! {
+        Tooltip {
+            label: "Quote",
+
+            ActionIcon {
+                variant: "subtle",
+                size: "xs",
+                color: "gray",
+                onclick: move || {
+                    let reference = reference_to(&msg, &channel_id, REFERENCE_QUOTE);
+                    get_messages_store().compose_quote.set(Some(reference));
+                },
+                {render_tabler_icon(__scope, TablerIcon::Quote, TablerIconStyle::Outline)}
+            }
+        }
+    }
+}
+
+/// Forward action and its destination picker. Any joined group can be the
+/// target, including groups on other hosts.
+#[component]
+pub fn forward_button(msg: StoredMessage, channel_id: String) -> NodeHandle {
+    let opened = Signal::new(false);
+
+    rsx! {
+        div {
+            Tooltip {
+                label: "Forward",
+
+                ActionIcon {
+                    variant: "subtle",
+                    size: "xs",
+                    color: "gray",
+                    onclick: move || opened.set(true),
+                    {render_tabler_icon(__scope, TablerIcon::ArrowForwardUp, TablerIconStyle::Outline)}
+                }
+            }
+
+            {forward_modal(__scope, msg, channel_id, opened)}
+        }
+    }
+}
+
+#[component]
+fn forward_modal(msg: StoredMessage, channel_id: String, opened: Signal<bool>) -> NodeHandle {
+    let target_group = Signal::new(String::new());
+    let target_channel = Signal::new(String::new());
+    let channels = Signal::new(Vec::<Channel>::new());
+    let sending = Signal::new(false);
+    let error = Signal::new(Option::<String>::None);
+
+    let host_of = |group_id: &str| {
+        get_groups_store()
+            .joined_groups
+            .get()
+            .iter()
+            .find(|g| g.group_id == group_id)
+            .and_then(|g| g.host.clone())
+            .unwrap_or_default()
+    };
+
+    let on_group = move |group_id: String| {
+        target_group.set(group_id.clone());
+        target_channel.set(String::new());
+        channels.set(Vec::new());
+        if group_id.is_empty() {
+            return;
+        }
+        let client = get_auth_store().make_client_for(&host_of(&group_id));
+        crate::runtime::spawn(
+            async move { client.get_channels(&group_id).await },
+            move |result| match result {
+                Ok(list) => {
+                    if let Some(first) = list.first() {
+                        target_channel.set(first.id.clone());
+                    }
+                    channels.set(list);
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            },
+        );
+    };
+
+    let on_forward = move || {
+        let gid = target_group.get().clone();
+        let cid = target_channel.get().clone();
+        if gid.is_empty() || cid.is_empty() {
+            return;
+        }
+        let host = host_of(&gid);
+        let ws_host = if host.is_empty() { get_auth_store().domain() } else { host };
+        let Some(handle) = crate::ws::get_handle(&ws_host) else {
+            error.set(Some(format!("Not connected to {}", ws_host)));
+            return;
+        };
+
+        // Forwarding a forward points at the original source
+        let reference = match &msg.reference {
+            Some(r) if r.r#type == REFERENCE_FORWARD => r.clone(),
+            _ => reference_to(&msg, &channel_id, REFERENCE_FORWARD),
+        };
+        let options = MessageOptions {
+            title: msg.title.clone(),
+            message_type: match msg.message_type {
+                MessageType::Message => None,
+                other => Some(other),
+            },
+            attachments: msg.attachments.clone(),
+            tags: msg.tags.clone(),
+            reference: Some(reference),
+        };
+
+        sending.set(true);
+        let nonce = uuid::Uuid::new_v4().to_string();
+        match handle.send_message_with_options(&cid, &msg.content, &nonce, options) {
+            Ok(()) => {
+                error.set(None);
+                opened.set(false);
+            }
+            Err(e) => error.set(Some(e.to_string())),
+        }
+        sending.set(false);
+    };
+
+    rsx! {
+        Modal {
+            opened_fn: move || opened.get(),
+            onclose: move || opened.set(false),
+            title: "Forward message",
+
+            Stack {
+                gap: "md",
+
+                if error.get().is_some() {
+                    Alert {
+                        color: "red",
+                        {error.get().clone().unwrap_or_default()}
+                    }
+                }
+
+                Select {
+                    label: "Group",
+                    value_fn: move || target_group.get().clone(),
+                    onchange: move |val: String| on_group(val),
+                    option { value: "", "Choose a group" }
+                    for group in get_groups_store().joined_groups.get().clone() {
+                        option {
+                            value: group.group_id.clone(),
+                            {match &group.host {
+                                Some(host) if !host.is_empty() => format!("{} ({})", group.name, host),
+                                _ => group.name.clone(),
+                            }}
+                        }
+                    }
+                }
+
+                Select {
+                    label: "Channel",
+                    value_fn: move || target_channel.get().clone(),
+                    onchange: move |val: String| target_channel.set(val),
+                    for channel in channels.get().clone() {
+                        option {
+                            value: channel.id.clone(),
+                            {format!("#{}", channel.name)}
+                        }
+                    }
+                }
+
+                Group {
+                    justify: "flex-end",
+
+                    Button {
+                        variant: "subtle",
+                        onclick: move || opened.set(false),
+                        "Cancel"
+                    }
+
+                    Button {
+                        variant: "filled",
+                        color: "indigo",
+                        loading: sending.get(),
+                        disabled: target_channel.get().is_empty(),
+                        onclick: move || on_forward(),
+                        "Forward"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,35 +1,115 @@
 use rinch::prelude::*;
-use crate::stores::{get_messages_store, StoredMessage};
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use crate::stores::{get_messages_store, ThreadNode};
 
+/// Nested replies below `node`, each with a button to reply to it directly.
 #[component]
-pub fn reply_thread(parent_id: String, channel_id: String, group_id: String) -> NodeHandle {
-    let messages_store = get_messages_store();
-
-    let replies: Vec<StoredMessage> = messages_store
-        .messages
-        .get()
-        .get(&channel_id)
-        .map(|ch| {
-            ch.messages
-                .iter()
-                .filter(|m| m.parent_id.as_deref() == Some(&parent_id))
-                .cloned()
-                .collect()
-        })
-        .unwrap_or_default();
-    let replies = Signal::new(replies);
-
-    if replies.get().is_empty() {
+pub fn reply_thread(
+    node: ThreadNode,
+    group_id: String,
+    channel_id: String,
+    reply_target: Signal<Option<String>>,
+) -> NodeHandle {
+    if node.replies.is_empty() {
         return rsx! { div {} };
     }
 
     rsx! {
         div {
-            style: "margin-left: 40px; padding-left: 12px; border-left: 2px solid var(--rinch-color-dark-4, #373a40);",
+            style: "margin-left: 16px; padding-left: 10px; border-left: 2px solid var(--rinch-color-dark-4, #373a40);",
 
-            for reply in replies.get().clone() {
-                {crate::components::messages::message_item::message_item(__scope, reply, group_id.clone())}
+            for reply in node.replies.clone() {
+                let reply_id = reply.message.id.clone();
+                let is_target = reply_target.get().as_deref() == Some(reply_id.as_str());
+                let target_id = reply_id.clone();
+                div {
+                    key: reply_id.clone(),
+                    style: {if is_target { "background: rgba(92, 124, 250, 0.08); border-radius: 6px;".to_string() } else { String::new() }},
+
+                    {crate::components::messages::message_item::message_item(__scope, reply.message.clone(), group_id.clone(), channel_id.clone(), true)}
+
+                    Button {
+                        variant: "subtle",
+                        size: "xs",
+                        onclick: move || reply_target.set(Some(target_id.clone())),
+                        {render_tabler_icon(__scope, TablerIcon::ArrowBackUp, TablerIconStyle::Outline)}
+                        " Reply"
+                    }
+
+                    {reply_thread(__scope, reply, group_id.clone(), channel_id.clone(), reply_target)}
+                }
+            }
+        }
+    }
+}
+
+/// Reply count and last-reply line shown under a message, opening its thread on click.
+#[component]
+pub fn thread_summary(message_id: String, channel_id: String) -> NodeHandle {
+    let mid = Signal::new(message_id);
+    let cid = Signal::new(channel_id);
+    let open_thread = move || get_messages_store().open_thread.set(Some(mid.get().clone()));
+
+    rsx! {
+        Group {
+            gap: "xs",
+            style: "margin-top: 2px;",
+
+            ActionIcon {
+                variant: "subtle",
+                size: "xs",
+                onclick: move || open_thread(),
+                {render_tabler_icon(__scope, TablerIcon::MessageCircle, TablerIconStyle::Outline)}
+            }
+
+            if get_messages_store().thread_summary(&cid.get(), &mid.get()).reply_count > 0 {
+                div {
+                    style: "cursor: pointer;",
+                    onclick: move || open_thread(),
+
+                    Text {
+                        size: "xs",
+                        color: "indigo",
+                        {move || summary_label(&cid.get(), &mid.get())}
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn summary_label(channel_id: &str, message_id: &str) -> String {
+    let summary = get_messages_store().thread_summary(channel_id, message_id);
+    let count = if summary.reply_count == 1 {
+        "1 reply".to_string()
+    } else {
+        format!("{} replies", summary.reply_count)
+    };
+    match summary.last_reply {
+        Some(last) => format!(
+            "{} · last reply by {} at {}",
+            count,
+            last.user_id.split('@').next().unwrap_or(&last.user_id),
+            last.created_at.format("%b %d %H:%M")
+        ),
+        None => count,
+    }
+}
+
+/// "alice: first words of the message" for a reply target, falling back to the id
+/// when the parent isn't loaded.
+pub fn reply_label(channel_id: &str, parent_id: &str) -> String {
+    match get_messages_store().find_message(channel_id, parent_id) {
+        Some(parent) => {
+            let author = parent.user_id.split('@').next().unwrap_or(&parent.user_id).to_string();
+            let text = parent.title.clone().unwrap_or(parent.content);
+            let snippet: String = text.chars().take(60).collect();
+            if snippet.len() < text.len() {
+                format!("{}: {}…", author, snippet)
+            } else {
+                format!("{}: {}", author, snippet)
             }
         }
+        None => format!("message {}", parent_id.chars().take(8).collect::<String>()),
     }
 }
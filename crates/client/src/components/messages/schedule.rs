@@ -0,0 +1,126 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDateTime, NaiveTime, Utc, Weekday};
+use rinch::prelude::*;
+
+const INPUT_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Parse a local `YYYY-MM-DD HH:MM` time typed by the user.
+pub fn parse_local_time(input: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(input.trim(), INPUT_FORMAT)
+        .ok()?
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// A time in the form `parse_local_time` accepts.
+pub fn format_input_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&Local).format(INPUT_FORMAT).to_string()
+}
+
+/// Friendly local time for lists.
+pub fn format_local_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&Local).format("%a %b %d, %H:%M").to_string()
+}
+
+/// Local 9:00 on the next day that is `weekday`, or tomorrow when `None`.
+fn next_morning(weekday: Option<Weekday>) -> Option<DateTime<Utc>> {
+    let today = Local::now().date_naive();
+    let mut day = today + Duration::days(1);
+    if let Some(weekday) = weekday {
+        while day.weekday() != weekday {
+            day += Duration::days(1);
+        }
+    }
+    day.and_time(NaiveTime::from_hms_opt(9, 0, 0)?)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+fn presets() -> Vec<(&'static str, DateTime<Utc>)> {
+    let now = Utc::now();
+    let mut presets = vec![
+        ("In 1 hour", now + Duration::hours(1)),
+        ("In 3 hours", now + Duration::hours(3)),
+    ];
+    presets.extend(next_morning(None).map(|t| ("Tomorrow at 9:00", t)));
+    presets.extend(next_morning(Some(Weekday::Mon)).map(|t| ("Monday at 9:00", t)));
+    presets
+}
+
+/// Time picker for "send later". The chosen time is written to `picked`.
+#[component]
+pub fn schedule_modal(opened: Signal<bool>, picked: Signal<Option<DateTime<Utc>>>) -> NodeHandle {
+    let custom = Signal::new(format_input_time(Utc::now() + Duration::hours(1)));
+    let error = Signal::new(Option::<String>::None);
+
+    let on_custom = move || match parse_local_time(&custom.get()) {
+        Some(time) if time > Utc::now() => {
+            error.set(None);
+            picked.set(Some(time));
+            opened.set(false);
+        }
+        Some(_) => error.set(Some("Pick a time in the future".to_string())),
+        None => error.set(Some("Use the format YYYY-MM-DD HH:MM".to_string())),
+    };
+
+    rsx! {
+        Modal {
+            opened_fn: move || opened.get(),
+            onclose: move || opened.set(false),
+            title: "Send later",
+
+            Stack {
+                gap: "md",
+
+                if error.get().is_some() {
+                    Alert {
+                        color: "red",
+                        {error.get().clone().unwrap_or_default()}
+                    }
+                }
+
+                for (label, time) in presets() {
+                    div {
+                        key: label.to_string(),
+
+                        Button {
+                            variant: "light",
+                            full_width: true,
+                            onclick: move || {
+                                picked.set(Some(time));
+                                opened.set(false);
+                            },
+                            {format!("{} ({})", label, format_local_time(time))}
+                        }
+                    }
+                }
+
+                TextInput {
+                    label: "Custom time",
+                    placeholder: "YYYY-MM-DD HH:MM",
+                    value_fn: move || custom.get().clone(),
+                    oninput: move |val: String| custom.set(val),
+                    onsubmit: move || on_custom(),
+                }
+
+                Group {
+                    justify: "flex-end",
+
+                    Button {
+                        variant: "subtle",
+                        onclick: move || opened.set(false),
+                        "Cancel"
+                    }
+
+                    Button {
+                        variant: "filled",
+                        color: "indigo",
+                        onclick: move || on_custom(),
+                        "Schedule"
+                    }
+                }
+            }
+        }
+    }
+}
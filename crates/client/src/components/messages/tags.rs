@@ -0,0 +1,106 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use crate::navigation::{navigate, AppRoute};
+use crate::stores::{get_groups_store, get_messages_store};
+
+/// Tags on a message. Clicking one filters the channel to that tag.
+#[component]
+pub fn tag_chips(tags: Vec<String>) -> NodeHandle {
+    rsx! {
+        div {
+            style: "display: flex; flex-wrap: wrap; gap: 4px; margin-top: 4px;",
+
+            for tag in tags {
+                let filter = tag.clone();
+                div {
+                    key: tag.clone(),
+                    style: "cursor: pointer;",
+                    onclick: move || get_messages_store().tag_filter.set(Some(filter.clone())),
+
+                    Badge {
+                        variant: "light",
+                        color: "teal",
+                        size: "xs",
+                        {format!("#{}", tag)}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Channel tags plus tags used in loaded messages, with the active filter
+/// highlighted and a link to the group-wide view for it.
+#[component]
+pub fn tag_filter_bar(host: String, group_id: String, channel_id: String) -> NodeHandle {
+    let cid = Signal::new(channel_id);
+    let tag_filter = get_messages_store().tag_filter;
+
+    let tags = move || {
+        let channel_tags = get_groups_store()
+            .channels
+            .get()
+            .iter()
+            .find(|c| c.id == *cid.get())
+            .map(|c| c.tags.clone())
+            .unwrap_or_default();
+        let used = get_messages_store()
+            .get_channel_messages(&cid.get())
+            .map(|ch| ch.tags())
+            .unwrap_or_default();
+        crate::tags::merge_tags(channel_tags, used)
+    };
+
+    rsx! {
+        div {
+            if !tags().is_empty() {
+                div {
+                    style: "display: flex; flex-wrap: wrap; align-items: center; gap: 6px; padding: 8px 20px; border-bottom: 1px solid var(--rinch-color-dark-4, #373a40);",
+
+                    {render_tabler_icon(__scope, TablerIcon::Tag, TablerIconStyle::Outline)}
+
+                    for tag in tags() {
+                        let active = tag_filter.get().as_deref() == Some(tag.as_str());
+                        let toggle = tag.clone();
+                        div {
+                            key: tag.clone(),
+                            style: "cursor: pointer;",
+                            onclick: move || {
+                                let next = if tag_filter.get().as_deref() == Some(toggle.as_str()) {
+                                    None
+                                } else {
+                                    Some(toggle.clone())
+                                };
+                                tag_filter.set(next);
+                            },
+
+                            Badge {
+                                variant: {if active { "filled".to_string() } else { "light".to_string() }},
+                                color: "teal",
+                                {format!("#{}", tag)}
+                            }
+                        }
+                    }
+
+                    for tag in tag_filter.get().clone().into_iter() {
+                        let route = AppRoute::Tag {
+                            host: host.clone(),
+                            group_id: group_id.clone(),
+                            tag: tag.clone(),
+                        };
+                        div {
+                            key: tag.clone(),
+
+                            Button {
+                                variant: "subtle",
+                                size: "xs",
+                                onclick: move || navigate(route.clone()),
+                                {format!("All #{} in group", tag)}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
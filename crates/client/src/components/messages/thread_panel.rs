@@ -0,0 +1,267 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use rorumall_shared::MessageType;
+use crate::components::messages::reply_thread::{reply_label, reply_thread};
+use crate::stores::{get_auth_store, get_members_store, get_messages_store, StoredMessage};
+use crate::ws::MessageOptions;
+
+/// Side panel showing the full reply tree for a message, with its own composer.
+#[component]
+pub fn thread_panel(host: String, group_id: String, channel_id: String, message_id: String) -> NodeHandle {
+    let loading = Signal::new(true);
+    let error = Signal::new(None::<String>);
+    let reply_target = Signal::new(None::<String>);
+
+    let cid = Signal::new(channel_id.clone());
+    let mid = Signal::new(message_id.clone());
+
+    // Fetch the whole thread; the channel page may only contain part of it
+    {
+        let client = get_auth_store().make_client_for(&host);
+        let gid = group_id.clone();
+        let ch = channel_id.clone();
+        let msg_id = message_id.clone();
+        crate::runtime::spawn(
+            async move {
+                let result = client.get_thread(&gid, &ch, &msg_id).await;
+                (ch, result)
+            },
+            move |(ch, result)| {
+                match result {
+                    Ok(page) => {
+                        let stored: Vec<StoredMessage> = page
+                            .items
+                            .into_iter()
+                            .map(StoredMessage::from_channel_message)
+                            .collect();
+                        get_messages_store().merge_messages(&ch, stored);
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to load thread: {}", e);
+                        error.set(Some(format!("Failed to load thread: {}", e)));
+                    }
+                }
+                loading.set(false);
+            },
+        );
+    }
+
+    rsx! {
+        div {
+            style: "display: flex; flex-direction: column; height: 100%; overflow: hidden;",
+
+            // Header — matches channel header height
+            div {
+                style: "height: 52px; min-height: 52px; display: flex; align-items: center; justify-content: space-between; padding: 0 16px; border-bottom: 1px solid var(--rinch-color-dark-4, #373a40); flex-shrink: 0;",
+
+                Text {
+                    size: "md",
+                    weight: "600",
+                    "Thread"
+                }
+
+                ActionIcon {
+                    variant: "subtle",
+                    size: "sm",
+                    onclick: move || get_messages_store().open_thread.set(None),
+                    {render_tabler_icon(__scope, TablerIcon::X, TablerIconStyle::Outline)}
+                }
+            }
+
+            div {
+                style: "flex: 1; overflow-y: auto; padding: 12px; min-height: 0;",
+
+                if let Some(err) = error.get().clone() {
+                    Alert {
+                        color: "red",
+                        variant: "light",
+                        {err}
+                    }
+                }
+
+                if let Some(tree) = get_messages_store()
+                    .get_channel_messages(&cid.get())
+                    .and_then(|ch| ch.reply_tree(&ch.thread_root(&mid.get())))
+                {
+                    div {
+                        {crate::components::messages::message_item::message_item(__scope, tree.message.clone(), group_id.clone(), channel_id.clone(), true)}
+
+                        Divider {}
+
+                        {reply_thread(__scope, tree, group_id.clone(), channel_id.clone(), reply_target)}
+                    }
+                } else if loading.get() {
+                    Stack {
+                        align: "center",
+                        p: "xl",
+                        Loader {}
+                    }
+                }
+            }
+
+            // Reply composer
+            div {
+                style: "padding: 12px; border-top: 1px solid var(--rinch-color-dark-4, #373a40);",
+                {reply_composer(__scope, host.clone(), group_id.clone(), channel_id.clone(), message_id.clone(), reply_target, "Reply to thread...".to_string())}
+            }
+        }
+    }
+}
+
+/// Composer for replying to `root_id`, or to the reply selected in
+/// `reply_target`. Only the reply types the channel allows are offered.
+#[component]
+pub fn reply_composer(
+    host: String,
+    group_id: String,
+    channel_id: String,
+    root_id: String,
+    reply_target: Signal<Option<String>>,
+    placeholder: String,
+) -> NodeHandle {
+    let reply_text = Signal::new(String::new());
+    let reply_type = Signal::new("message".to_string());
+    let error = Signal::new(None::<String>);
+
+    let h = Signal::new(host);
+    let cid = Signal::new(channel_id.clone());
+    let mid = Signal::new(root_id);
+
+    // Replies may only use the types the channel allows us; articles are never replies
+    let access = get_members_store().channel_access(&group_id, &channel_id);
+    let reply_types: Vec<MessageType> = access
+        .reply_types
+        .iter()
+        .filter(|t| **t != MessageType::Article)
+        .cloned()
+        .collect();
+    if let Some(first) = reply_types.first() {
+        reply_type.set(type_value(first).to_string());
+    }
+    let allows_memo = reply_types.contains(&MessageType::Memo);
+    let allows_message = reply_types.contains(&MessageType::Message);
+    let replies_enabled = !reply_types.is_empty();
+
+    let on_send = move || {
+        let text = reply_text.get().clone();
+        if text.trim().is_empty() {
+            return;
+        }
+        let text = crate::emoji::convert_shortcodes(&text);
+
+        let domain = get_auth_store().domain();
+        let h_val = h.get().clone();
+        let ws_host = if h_val.is_empty() { domain } else { h_val };
+        let parent = reply_target.get().clone().unwrap_or_else(|| mid.get().clone());
+        let mt = match reply_type.get().as_str() {
+            "memo" => Some(MessageType::Memo),
+            _ => None,
+        };
+
+        let Some(handle) = crate::ws::get_handle(&ws_host) else {
+            error.set(Some(format!("Not connected to {}", ws_host)));
+            return;
+        };
+        let nonce = uuid::Uuid::new_v4().to_string();
+        let options = MessageOptions {
+            message_type: mt,
+            tags: crate::tags::extract_hashtags(&text),
+            ..MessageOptions::default()
+        };
+        match handle.send_reply(&cid.get(), &text, &nonce, &parent, options) {
+            Ok(()) => {
+                reply_text.set(String::new());
+                reply_target.set(None);
+                error.set(None);
+            }
+            Err(e) => {
+                tracing::error!("Failed to send reply: {}", e);
+                error.set(Some(format!("Failed to send reply: {}", e)));
+            }
+        }
+    };
+
+    rsx! {
+        div {
+            if let Some(err) = error.get().clone() {
+                Alert {
+                    color: "red",
+                    variant: "light",
+                    {err}
+                }
+            }
+
+            if replies_enabled {
+                Stack {
+                    gap: "xs",
+
+                    Group {
+                        gap: "xs",
+                        style: "justify-content: space-between;",
+
+                        Text {
+                            size: "xs",
+                            color: "dimmed",
+                            {move || {
+                                let target = reply_target.get().clone().unwrap_or_else(|| mid.get().clone());
+                                format!("Replying to {}", reply_label(&cid.get(), &target))
+                            }}
+                        }
+
+                        if reply_target.get().is_some() {
+                            ActionIcon {
+                                variant: "subtle",
+                                size: "xs",
+                                onclick: move || reply_target.set(None),
+                                {render_tabler_icon(__scope, TablerIcon::X, TablerIconStyle::Outline)}
+                            }
+                        }
+                    }
+
+                    if allows_memo && allows_message {
+                        Select {
+                            value_fn: move || reply_type.get().clone(),
+                            onchange: move |val: String| reply_type.set(val),
+                            option { value: "message", "Message" }
+                            option { value: "memo", "Memo" }
+                        }
+                    }
+
+                    Textarea {
+                        placeholder: placeholder,
+                        min_rows: 2,
+                        value_fn: move || reply_text.get().clone(),
+                        oninput: move |val: String| reply_text.set(val),
+                    }
+
+                    Group {
+                        gap: "xs",
+                        style: "justify-content: space-between;",
+
+                        {crate::components::messages::emoji_picker::emoji_picker(__scope, group_id.clone(), reply_text)}
+
+                        Button {
+                            size: "xs",
+                            onclick: move || on_send(),
+                            "Reply"
+                        }
+                    }
+                }
+            } else {
+                Text {
+                    size: "sm",
+                    color: "dimmed",
+                    {access.read_only_reason().unwrap_or("Replies are disabled in this channel")}
+                }
+            }
+        }
+    }
+}
+
+fn type_value(mt: &MessageType) -> &'static str {
+    match mt {
+        MessageType::Message => "message",
+        MessageType::Memo => "memo",
+        MessageType::Article => "article",
+    }
+}
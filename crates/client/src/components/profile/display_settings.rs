@@ -0,0 +1,121 @@
+use rinch::prelude::*;
+use crate::imaging::ImageFormatChoice;
+use crate::link_preview::parse_domain_list;
+use crate::stores::get_settings_store;
+
+/// Local display preferences. These are stored on this device only.
+#[component]
+pub fn display_settings() -> NodeHandle {
+    let settings = get_settings_store();
+    let policy = settings.preferences.get().link_previews.clone();
+    let allow_text = Signal::new(policy.allow_domains.join(", "));
+    let deny_text = Signal::new(policy.deny_domains.join(", "));
+
+    rsx! {
+        Stack {
+            gap: "md",
+
+            Title {
+                order: 5,
+                "Display Settings"
+            }
+
+            Select {
+                label: "Message formatting",
+                value_fn: move || {
+                    if settings.preferences.get().render_markdown { "markdown".to_string() } else { "plain".to_string() }
+                },
+                onchange: move |val: String| {
+                    settings.update(|p| p.render_markdown = val == "markdown");
+                },
+                option { value: "markdown", "Render Markdown" }
+                option { value: "plain", "Show raw text" }
+            }
+
+            Select {
+                label: "Link previews",
+                value_fn: move || {
+                    if settings.preferences.get().link_previews.enabled { "on".to_string() } else { "off".to_string() }
+                },
+                onchange: move |val: String| {
+                    settings.update(|p| p.link_previews.enabled = val == "on");
+                },
+                option { value: "off", "Off" }
+                option { value: "on", "Show previews for links" }
+            }
+
+            Text {
+                size: "xs",
+                color: "dimmed",
+                "Loading a preview contacts the linked site directly, which reveals your IP address to it."
+            }
+
+            if settings.preferences.get().link_previews.enabled {
+                Stack {
+                    gap: "sm",
+
+                    TextInput {
+                        label: "Only preview these domains",
+                        placeholder: "Leave empty to allow all, e.g. github.com, docs.rs",
+                        value_fn: move || allow_text.get().clone(),
+                        oninput: move |val: String| {
+                            settings.update(|p| p.link_previews.allow_domains = parse_domain_list(&val));
+                            allow_text.set(val);
+                        },
+                    }
+
+                    TextInput {
+                        label: "Never preview these domains",
+                        placeholder: "e.g. example.com",
+                        value_fn: move || deny_text.get().clone(),
+                        oninput: move |val: String| {
+                            settings.update(|p| p.link_previews.deny_domains = parse_domain_list(&val));
+                            deny_text.set(val);
+                        },
+                    }
+                }
+            }
+
+            Select {
+                label: "Images you upload",
+                value_fn: move || settings.preferences.get().images.max_dimension.to_string(),
+                onchange: move |val: String| {
+                    settings.update(|p| p.images.max_dimension = val.parse().unwrap_or_default());
+                },
+                option { value: "1280", "Scale down to 1280 px" }
+                option { value: "2048", "Scale down to 2048 px" }
+                option { value: "4096", "Scale down to 4096 px" }
+                option { value: "0", "Keep full resolution" }
+            }
+
+            Select {
+                label: "Image format",
+                value_fn: move || settings.preferences.get().images.format.key().to_string(),
+                onchange: move |val: String| {
+                    settings.update(|p| p.images.format = ImageFormatChoice::from_key(&val));
+                },
+                option { value: "auto", "Same as the original" }
+                option { value: "jpeg", "JPEG" }
+                option { value: "png", "PNG" }
+                option { value: "webp", "WebP (lossless)" }
+            }
+
+            Select {
+                label: "JPEG quality",
+                value_fn: move || settings.preferences.get().images.jpeg_quality.to_string(),
+                onchange: move |val: String| {
+                    settings.update(|p| p.images.jpeg_quality = val.parse().unwrap_or(85));
+                },
+                option { value: "95", "High" }
+                option { value: "85", "Balanced" }
+                option { value: "70", "Small" }
+            }
+
+            Text {
+                size: "xs",
+                color: "dimmed",
+                "Images are re-encoded before upload, which removes location and camera data. Choose \"Send original\" on an attachment to skip this."
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+pub mod display_settings;
 pub mod presence_indicator;
 pub mod presence_selector;
 pub mod privacy_settings;
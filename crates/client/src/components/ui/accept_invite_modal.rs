@@ -0,0 +1,209 @@
+use chrono::Utc;
+use rinch::prelude::*;
+use rorumall_shared::{ApiError, InvitePreview, JoinGroupRequest};
+use crate::discovery::{DiscoveredGroup, JoinOutcome, JoinRequest};
+use crate::navigation::{navigate, AppRoute};
+use crate::stores::{get_auth_store, get_groups_store, get_invites_store};
+
+/// The group behind a pasted or launched invite link, with a button to join.
+#[component]
+pub fn accept_invite_modal() -> NodeHandle {
+    let store = get_invites_store();
+    let preview = Signal::new(None::<InvitePreview>);
+    let loading = Signal::new(false);
+    let joining = Signal::new(false);
+    let error = Signal::new(None::<String>);
+    let notice = Signal::new(None::<String>);
+
+    // Look the invite up whenever a link comes in
+    Effect::new(move || {
+        let Some(link) = store.pending.get().clone() else {
+            return;
+        };
+        preview.set(None);
+        error.set(None);
+        notice.set(None);
+        loading.set(true);
+        let client = get_auth_store().make_client_for(&link.host);
+        crate::runtime::spawn(
+            async move {
+                let result = client.get_invite(&link.code).await;
+                (link, result)
+            },
+            move |(link, result)| {
+                // Superseded by another link in the meantime
+                if get_invites_store().pending.get().as_ref() != Some(&link) {
+                    return;
+                }
+                match result {
+                    Ok(found) => preview.set(Some(found)),
+                    Err(ApiError::Http { status: 404 | 410, .. }) => {
+                        error.set(Some("This invite link doesn't exist or was revoked.".to_string()))
+                    }
+                    Err(e) => error.set(Some(format!("Couldn't open the invite: {}", e))),
+                }
+                loading.set(false);
+            },
+        );
+    });
+
+    let is_member = move || {
+        let Some(found) = preview.get().clone() else {
+            return false;
+        };
+        get_groups_store().joined_groups.get().iter().any(|g| g.group_id == found.group.id)
+    };
+
+    let on_open = move || {
+        let (Some(link), Some(found)) = (store.pending.get().clone(), preview.get().clone()) else {
+            return;
+        };
+        store.close();
+        navigate(AppRoute::Group { host: link.host, group_id: found.group.id });
+    };
+
+    let on_accept = move || {
+        let (Some(link), Some(found)) = (store.pending.get().clone(), preview.get().clone()) else {
+            return;
+        };
+        joining.set(true);
+        error.set(None);
+        let auth = get_auth_store();
+        let group_client = auth.make_client_for(&link.host);
+        let home_client = auth.make_client();
+        let user_id = auth.user_id().unwrap_or_default();
+        crate::runtime::spawn(
+            async move {
+                let target = DiscoveredGroup { host: link.host, group: found.group };
+                let req = JoinGroupRequest { message: None, invite_code: Some(link.code) };
+                let result = crate::discovery::join(&group_client, &home_client, &user_id, &target, &req).await;
+                (target, result)
+            },
+            move |(target, result)| {
+                match result {
+                    Ok(JoinOutcome::Joined(joined)) => {
+                        get_groups_store().add_joined_group(joined);
+                        get_invites_store().close();
+                        navigate(AppRoute::Group { host: target.host, group_id: target.group.id });
+                    }
+                    Ok(JoinOutcome::Pending) => {
+                        get_groups_store().add_join_request(JoinRequest::from(&target));
+                        notice.set(Some("Your request to join was sent to the group's admins.".to_string()));
+                    }
+                    Err(e) => error.set(Some(format!("Couldn't join: {}", e))),
+                }
+                joining.set(false);
+            },
+        );
+    };
+
+    rsx! {
+        Modal {
+            opened_fn: move || store.pending.get().is_some(),
+            onclose: move || store.close(),
+            title: "Group invite",
+
+            Stack {
+                gap: "md",
+
+                if loading.get() {
+                    Loader {}
+                }
+
+                if let Some(err) = error.get().clone() {
+                    Alert {
+                        color: "red",
+                        variant: "light",
+                        {err}
+                    }
+                }
+
+                if let Some(found) = preview.get().clone() {
+                    Group {
+                        gap: "sm",
+
+                        Avatar {
+                            size: "lg",
+                            color: "indigo",
+                            src: {found.group.avatar.clone().unwrap_or_default()},
+                            name: found.group.name.clone(),
+                        }
+
+                        Stack {
+                            gap: "xs",
+                            style: "flex: 1; min-width: 0;",
+
+                            Text {
+                                size: "md",
+                                weight: "600",
+                                {found.group.name.clone()}
+                            }
+
+                            if let Some(description) = found.group.description.clone().filter(|d| !d.is_empty()) {
+                                Text {
+                                    size: "sm",
+                                    color: "dimmed",
+                                    {description}
+                                }
+                            }
+                        }
+                    }
+
+                    if is_member() {
+                        Text {
+                            size: "sm",
+                            color: "dimmed",
+                            "You're already a member of this group."
+                        }
+                    } else if found.invite.is_expired(Utc::now()) {
+                        Alert {
+                            color: "orange",
+                            variant: "light",
+                            "This invite link has expired."
+                        }
+                    } else if found.invite.is_used_up() {
+                        Alert {
+                            color: "orange",
+                            variant: "light",
+                            "This invite link has been used as often as it allows."
+                        }
+                    }
+                }
+
+                if let Some(message) = notice.get().clone() {
+                    Text {
+                        size: "sm",
+                        {message}
+                    }
+                }
+
+                Group {
+                    justify: "flex-end",
+
+                    Button {
+                        variant: "subtle",
+                        onclick: move || store.close(),
+                        "Close"
+                    }
+
+                    if is_member() {
+                        Button {
+                            variant: "filled",
+                            color: "indigo",
+                            onclick: move || on_open(),
+                            "Open group"
+                        }
+                    } else if preview.get().as_ref().is_some_and(|p| p.invite.is_usable(Utc::now())) && notice.get().is_none() {
+                        Button {
+                            variant: "filled",
+                            color: "indigo",
+                            loading: joining.get(),
+                            onclick: move || on_accept(),
+                            "Join group"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,52 +1,30 @@
 use rinch::prelude::*;
 use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
 use rorumall_shared::Attachment;
+use std::path::PathBuf;
 
-use crate::stores::get_auth_store;
+use crate::stores::{get_auth_store, get_lightbox_store};
 
 #[component]
 pub fn attachment_display(att: Attachment) -> NodeHandle {
     let is_image = att.mime.starts_with("image/");
 
-    let filename = Signal::new(att.id.clone());
+    let filename = Signal::new(crate::search::attachment_name(&att));
     let size_label = Signal::new(format_size(att.size));
     let url = Signal::new(att.url.clone());
-    let download_status = Signal::new(DownloadStatus::Idle);
-
-    let on_download = move || {
-        download_status.set(DownloadStatus::Downloading);
-        let client = get_auth_store().make_client();
-        let download_url = url.get().clone();
-        let default_name = filename.get().clone();
-        crate::runtime::spawn(
-            async move {
-                let download_dir = dirs::download_dir()
-                    .unwrap_or_else(|| dirs::home_dir().unwrap_or_default());
-                let path = download_dir.join(&default_name);
-                match client.get_bytes(&download_url).await {
-                    Ok(bytes) => {
-                        if let Err(e) = std::fs::write(&path, &bytes) {
-                            tracing::error!("Failed to write file: {}", e);
-                            return false;
-                        }
-                        tracing::info!("Saved attachment to {}", path.display());
-                        true
-                    }
-                    Err(e) => {
-                        tracing::error!("Download failed: {}", e);
-                        false
-                    }
-                }
-            },
-            move |ok| {
-                if ok {
-                    download_status.set(DownloadStatus::Done);
-                } else {
-                    download_status.set(DownloadStatus::Idle);
-                }
-            },
-        );
-    };
+    // The thumbnail is plenty for the inline view
+    let preview = Signal::new(att.thumbnail_url.clone().unwrap_or_else(|| att.url.clone()));
+    // Reserve the image's space before it loads so the list doesn't jump
+    let image_style = Signal::new(match (att.width, att.height) {
+        (Some(w), Some(h)) if w > 0 && h > 0 => format!(
+            "width: {}px; max-width: 100%; aspect-ratio: {} / {}; border-radius: var(--rinch-radius-sm, 4px);",
+            w.min(300),
+            w,
+            h,
+        ),
+        _ => "max-width: 100%; border-radius: var(--rinch-radius-sm, 4px);".to_string(),
+    });
+    let file_name = crate::download::safe_file_name(&filename.get(), &att.id);
 
     if is_image {
         rsx! {
@@ -58,16 +36,31 @@ pub fn attachment_display(att: Attachment) -> NodeHandle {
                 Stack {
                     gap: "xs",
 
-                    img {
-                        src: {url.get().clone()},
-                        style: "max-width: 100%; border-radius: var(--rinch-radius-sm, 4px);",
-                        alt: {filename.get().clone()},
+                    div {
+                        style: "cursor: zoom-in;",
+                        onclick: {
+                            let att = att.clone();
+                            move || get_lightbox_store().open_attachment(&att)
+                        },
+
+                        img {
+                            src: {preview.get().clone()},
+                            style: {image_style.get().clone()},
+                            alt: {filename.get().clone()},
+                        }
                     }
 
-                    Text {
-                        size: "xs",
-                        color: "dimmed",
-                        {format!("{} {}", filename.get(), size_label.get())}
+                    Group {
+                        gap: "xs",
+                        justify: "space-between",
+
+                        Text {
+                            size: "xs",
+                            color: "dimmed",
+                            {format!("{} {}", filename.get(), size_label.get())}
+                        }
+
+                        {download_actions(__scope, url.get().clone(), file_name.clone())}
                     }
                 }
             }
@@ -100,27 +93,151 @@ pub fn attachment_display(att: Attachment) -> NodeHandle {
                         }
                     }
 
-                    if download_status.get() == DownloadStatus::Idle {
-                        ActionIcon {
-                            variant: "subtle",
-                            onclick: move || on_download(),
-                            {render_tabler_icon(__scope, TablerIcon::Download, TablerIconStyle::Outline)}
+                    {download_actions(__scope, url.get().clone(), file_name.clone())}
+                }
+            }
+        }
+    }
+}
+
+/// Download buttons, then progress, then what to do with the saved file.
+#[component]
+fn download_actions(url: String, file_name: String) -> NodeHandle {
+    let url = Signal::new(url);
+    let file_name = Signal::new(file_name);
+    let status = Signal::new(DownloadStatus::Idle);
+
+    let start_download = move |dest: PathBuf, replace: bool| {
+        status.set(DownloadStatus::Downloading(0, None));
+        let client = get_auth_store().make_client();
+        let download_url = url.get().clone();
+        let on_progress = move |received: u64, total: Option<u64>| {
+            rinch::run_on_main_thread(move || {
+                // Late reports must not undo a finished download
+                if matches!(status.get(), DownloadStatus::Downloading(..)) {
+                    status.set(DownloadStatus::Downloading(received, total));
+                }
+            });
+        };
+        crate::runtime::spawn(
+            async move { crate::download::download(&client, &download_url, &dest, replace, on_progress).await },
+            move |result| match result {
+                Ok(path) => {
+                    tracing::info!("Saved attachment to {}", path.display());
+                    status.set(DownloadStatus::Done(path));
+                }
+                Err(e) => {
+                    tracing::error!("Download failed: {}", e);
+                    status.set(DownloadStatus::Failed(e));
+                }
+            },
+        );
+    };
+
+    // Straight to the Downloads folder, next to any file of the same name
+    let on_download = move || {
+        start_download(crate::download::default_dir().join(file_name.get().clone()), false);
+    };
+
+    // The dialog asks before replacing an existing file
+    let on_save_as = move || {
+        let name = file_name.get().clone();
+        crate::runtime::spawn(
+            async move {
+                rinch::dialogs::save_file()
+                    .set_file_name(&name)
+                    .save_file()
+            },
+            move |path: Option<PathBuf>| {
+                if let Some(path) = path {
+                    start_download(path, true);
+                }
+            },
+        );
+    };
+
+    rsx! {
+        Group {
+            gap: "xs",
+
+            if let DownloadStatus::Downloading(received, total) = status.get() {
+                Loader {
+                    size: "xs",
+                }
+
+                Text {
+                    size: "xs",
+                    color: "dimmed",
+                    {match total {
+                        Some(total) if total > 0 => format!("{}%", received * 100 / total),
+                        _ => format_size(received),
+                    }}
+                }
+            } else if let DownloadStatus::Done(path) = status.get() {
+                Button {
+                    variant: "subtle",
+                    size: "xs",
+                    onclick: {
+                        let path = path.clone();
+                        move || {
+                            if let Err(e) = open::that(&path) {
+                                tracing::error!("Failed to open {}: {}", path.display(), e);
+                            }
                         }
-                    }
+                    },
+                    "Open"
+                }
+
+                Button {
+                    variant: "subtle",
+                    size: "xs",
+                    onclick: {
+                        let dir = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+                        move || {
+                            if let Err(e) = open::that(&dir) {
+                                tracing::error!("Failed to open {}: {}", dir.display(), e);
+                            }
+                        }
+                    },
+                    "Show in folder"
+                }
+
+                ActionIcon {
+                    variant: "subtle",
+                    color: "green",
+                    onclick: move || status.set(DownloadStatus::Idle),
+                    {render_tabler_icon(__scope, TablerIcon::Check, TablerIconStyle::Outline)}
+                }
+            } else {
+                if let DownloadStatus::Failed(e) = status.get() {
+                    Tooltip {
+                        label: {e.clone()},
 
-                    if download_status.get() == DownloadStatus::Downloading {
-                        Loader {
+                        Text {
                             size: "xs",
+                            color: "red",
+                            "Download failed"
                         }
                     }
+                }
 
-                    if download_status.get() == DownloadStatus::Done {
-                        ActionIcon {
-                            variant: "subtle",
-                            color: "green",
-                            onclick: move || download_status.set(DownloadStatus::Idle),
-                            {render_tabler_icon(__scope, TablerIcon::Check, TablerIconStyle::Outline)}
-                        }
+                Tooltip {
+                    label: "Download",
+
+                    ActionIcon {
+                        variant: "subtle",
+                        onclick: move || on_download(),
+                        {render_tabler_icon(__scope, TablerIcon::Download, TablerIconStyle::Outline)}
+                    }
+                }
+
+                Tooltip {
+                    label: "Save as...",
+
+                    ActionIcon {
+                        variant: "subtle",
+                        onclick: move || on_save_as(),
+                        {render_tabler_icon(__scope, TablerIcon::DeviceFloppy, TablerIconStyle::Outline)}
                     }
                 }
             }
@@ -131,8 +248,10 @@ pub fn attachment_display(att: Attachment) -> NodeHandle {
 #[derive(Clone, PartialEq)]
 enum DownloadStatus {
     Idle,
-    Downloading,
-    Done,
+    /// Bytes received and the expected total, if the server sent one.
+    Downloading(u64, Option<u64>),
+    Done(PathBuf),
+    Failed(String),
 }
 
 fn format_size(bytes: u64) -> String {
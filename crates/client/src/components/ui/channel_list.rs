@@ -1,7 +1,7 @@
 use rinch::prelude::*;
 use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
 use crate::navigation::{navigate, AppRoute};
-use crate::stores::{get_auth_store, get_groups_store, get_members_store};
+use crate::stores::{draft_key, get_auth_store, get_drafts_store, get_emoji_store, get_groups_store, get_members_store, get_messages_store};
 
 #[component]
 pub fn channel_list(host: String, group_id: String, show_create_channel: Signal<bool>, create_channel_gid: Signal<String>) -> NodeHandle {
@@ -35,6 +35,11 @@ pub fn channel_list(host: String, group_id: String, show_create_channel: Signal<
                     get_groups_store().set_channels(channels);
                 }
                 if let Ok(members_resp) = members {
+                    // Our own roles drive channel permissions
+                    let me = get_auth_store().user_id();
+                    if let Some(member) = members_resp.members.iter().find(|m| Some(&m.user_id) == me.as_ref()) {
+                        get_members_store().set_my_roles(&gid, member.roles.clone());
+                    }
                     get_members_store().set_group_members(&gid, members_resp.members);
                 }
                 if let Ok(roles_resp) = roles {
@@ -43,6 +48,7 @@ pub fn channel_list(host: String, group_id: String, show_create_channel: Signal<
             },
         );
     }
+    get_emoji_store().load(&host, &group_id);
 
     rsx! {
         div {
@@ -78,15 +84,20 @@ pub fn channel_list(host: String, group_id: String, show_create_channel: Signal<
                 style: "flex: 1; overflow-y: auto;",
 
                 for ch in groups_store.channels.get().clone() {
+                    let has_draft = get_drafts_store().has_draft(&draft_key(&host, &group_id, &ch.id));
                     NavLink {
                         key: ch.id.clone(),
                         label: {ch.name.clone()},
                         left_section: Some(TablerIcon::Hash),
+                        right_section: if has_draft { Some(TablerIcon::Pencil) } else { None },
                         onclick: {
                             let h = host.clone();
                             let gid = group_id.clone();
                             let cid = ch.id.clone();
                             move || {
+                                get_messages_store().focus_message.set(None);
+                                get_messages_store().open_thread.set(None);
+                                get_messages_store().tag_filter.set(None);
                                 navigate(AppRoute::Channel {
                                     host: h.clone(),
                                     group_id: gid.clone(),
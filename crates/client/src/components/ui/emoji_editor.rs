@@ -0,0 +1,121 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use crate::stores::{get_emoji_store, get_members_store};
+
+/// Custom emoji of a group. Owners and admins may add and remove them.
+#[component]
+pub fn emoji_editor(host: String, group_id: String) -> NodeHandle {
+    let h = Signal::new(host.clone());
+    let gid = Signal::new(group_id.clone());
+    let shortcode = Signal::new(String::new());
+    let uploading = Signal::new(false);
+    let error = Signal::new(None::<String>);
+    let can_manage = matches!(
+        get_members_store().get_my_base_role(&group_id).as_deref(),
+        Some("owner") | Some("admin")
+    );
+
+    get_emoji_store().load(&host, &group_id);
+
+    let on_add = move || {
+        let Some(code) = crate::emoji::normalize_shortcode(&shortcode.get()) else {
+            error.set(Some("Shortcodes use up to 32 letters, digits, _, + and -".to_string()));
+            return;
+        };
+        error.set(None);
+        uploading.set(true);
+        crate::runtime::spawn(
+            async move {
+                rinch::dialogs::open_file()
+                    .add_filter("Images", &["png", "gif", "webp", "jpg", "jpeg"])
+                    .pick_file()
+            },
+            move |path| {
+                let Some(path) = path else {
+                    uploading.set(false);
+                    return;
+                };
+                get_emoji_store().add(&h.get(), &gid.get(), code, path, move |result| {
+                    uploading.set(false);
+                    match result {
+                        Ok(()) => shortcode.set(String::new()),
+                        Err(e) => error.set(Some(format!("Failed to add emoji: {}", e))),
+                    }
+                });
+            },
+        );
+    };
+
+    rsx! {
+        Stack {
+            gap: "sm",
+
+            if let Some(err) = error.get().clone() {
+                Alert {
+                    color: "red",
+                    variant: "light",
+                    {err}
+                }
+            }
+
+            if can_manage {
+                Group {
+                    gap: "xs",
+                    style: "align-items: flex-end;",
+
+                    TextInput {
+                        label: "Shortcode",
+                        placeholder: "party_parrot",
+                        style: "flex: 1;",
+                        value_fn: move || shortcode.get().clone(),
+                        oninput: move |val: String| shortcode.set(val),
+                        onsubmit: move || on_add(),
+                    }
+
+                    Button {
+                        variant: "filled",
+                        color: "indigo",
+                        loading: uploading.get(),
+                        disabled: shortcode.get().trim().is_empty(),
+                        onclick: move || on_add(),
+                        "Upload image"
+                    }
+                }
+            }
+
+            if get_emoji_store().get_group_emoji(&gid.get()).is_empty() {
+                Text {
+                    size: "sm",
+                    color: "dimmed",
+                    "This group has no custom emoji yet."
+                }
+            }
+
+            for emoji in get_emoji_store().get_group_emoji(&gid.get()) {
+                let code = emoji.shortcode.clone();
+                div {
+                    key: emoji.shortcode.clone(),
+                    style: "display: flex; align-items: center; gap: 10px; padding: 4px 0; border-bottom: 1px solid var(--rinch-color-dark-4, #373a40);",
+
+                    img { class: "custom-emoji", src: emoji.url.clone(), alt: emoji.reaction_key() }
+
+                    Text {
+                        size: "sm",
+                        style: "flex: 1;",
+                        {emoji.reaction_key()}
+                    }
+
+                    if can_manage {
+                        ActionIcon {
+                            variant: "subtle",
+                            color: "red",
+                            size: "sm",
+                            onclick: move || get_emoji_store().remove(&h.get(), &gid.get(), &code),
+                            {render_tabler_icon(__scope, TablerIcon::Trash, TablerIconStyle::Outline)}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
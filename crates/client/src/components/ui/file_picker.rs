@@ -1,12 +1,18 @@
 use rinch::prelude::*;
 use crate::stores::get_auth_store;
+use crate::upload::{UploadControl, UploadError, UploadSource};
 
 #[component]
 pub fn file_picker(attachments: Signal<Vec<rorumall_shared::Attachment>>) -> NodeHandle {
     let uploading = Signal::new(false);
+    let percent = Signal::new(0u64);
+    let control = Signal::new(UploadControl::default());
 
     let on_pick = move || {
         uploading.set(true);
+        percent.set(0);
+        let upload_control = UploadControl::default();
+        control.set(upload_control.clone());
         let client = get_auth_store().make_client();
         crate::runtime::spawn(
             async move {
@@ -15,20 +21,21 @@ pub fn file_picker(attachments: Signal<Vec<rorumall_shared::Attachment>>) -> Nod
                     .add_filter("Images", &["png", "jpg", "jpeg", "gif", "webp"])
                     .pick_file();
                 let path = path?;
-                let data = match std::fs::read(&path) {
-                    Ok(d) => d,
+                let mut source = match UploadSource::from_path(path) {
+                    Ok(s) => s,
                     Err(e) => {
                         tracing::error!("Failed to read file: {}", e);
                         return None;
                     }
                 };
-                let filename = path
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_else(|| "file".to_string());
-                let mime = mime_from_ext(&filename);
-                match client.upload_file(data, &filename, &mime).await {
+                source.mime = mime_from_ext(&source.filename);
+                let size = source.size.max(1);
+                let on_progress = move |sent: u64| {
+                    rinch::run_on_main_thread(move || percent.set((sent * 100 / size).min(100)));
+                };
+                match crate::upload::upload(&client, &source, &upload_control, on_progress).await {
                     Ok(attachment) => Some(attachment),
+                    Err(UploadError::Cancelled) => None,
                     Err(e) => {
                         tracing::error!("Upload failed: {}", e);
                         None
@@ -45,12 +52,32 @@ pub fn file_picker(attachments: Signal<Vec<rorumall_shared::Attachment>>) -> Nod
     };
 
     rsx! {
-        Button {
-            variant: "subtle",
-            size: "sm",
-            loading: uploading.get(),
-            onclick: move || on_pick(),
-            "Upload File"
+        Group {
+            gap: "xs",
+
+            Button {
+                variant: "subtle",
+                size: "sm",
+                loading: uploading.get(),
+                onclick: move || on_pick(),
+                "Upload File"
+            }
+
+            if uploading.get() && percent.get() > 0 {
+                Text {
+                    size: "xs",
+                    color: "dimmed",
+                    {format!("{}%", percent.get())}
+                }
+
+                Button {
+                    variant: "subtle",
+                    color: "red",
+                    size: "xs",
+                    onclick: move || control.get().cancel(),
+                    "Cancel"
+                }
+            }
         }
     }
 }
@@ -0,0 +1,247 @@
+use chrono::Utc;
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use rorumall_shared::{AddMemberRequest, CreateInviteRequest, GroupInvite};
+use crate::invites::{InviteLink, EXPIRY_CHOICES, MAX_USES_CHOICES};
+use crate::stores::{get_auth_store, get_members_store};
+
+/// Inviting people to a group: by handle, or with links that expire or run
+/// out of uses. Only shown to members the group lets invite.
+#[component]
+pub fn group_invites(host: String, group_id: String) -> NodeHandle {
+    let h = Signal::new(host.clone());
+    let gid = Signal::new(group_id.clone());
+    let handle = Signal::new(String::new());
+    let inviting = Signal::new(false);
+    let expiry = Signal::new("7d".to_string());
+    let max_uses = Signal::new("0".to_string());
+    let creating = Signal::new(false);
+    let invites = Signal::new(Vec::<GroupInvite>::new());
+    let feedback = Signal::new(None::<String>);
+    let error = Signal::new(None::<String>);
+
+    // Links made earlier
+    {
+        let client = get_auth_store().make_client_for(&host);
+        let gid = group_id.clone();
+        crate::runtime::spawn(
+            async move { client.list_invites(&gid).await },
+            move |result| match result {
+                Ok(list) => invites.set(list),
+                Err(e) => tracing::error!("Failed to load invites: {}", e),
+            },
+        );
+    }
+
+    let link_url = move |code: &str| {
+        let link = InviteLink { host: h.get().clone(), code: code.to_string() };
+        link.url(&get_auth_store().domain())
+    };
+
+    let copy_link = move |code: &str| {
+        let url = link_url(code);
+        feedback.set(Some(match rinch::clipboard::copy_text(&url) {
+            Ok(()) => "Invite link copied".to_string(),
+            Err(e) => format!("Couldn't copy the link: {}", e),
+        }));
+    };
+
+    let on_invite = move || {
+        let Some(target) = crate::invites::normalize_handle(&handle.get()) else {
+            error.set(Some("Enter a handle like @someone@example.org".to_string()));
+            return;
+        };
+        error.set(None);
+        feedback.set(None);
+        inviting.set(true);
+        let client = get_auth_store().make_client_for(&h.get());
+        let group_id = gid.get().clone();
+        crate::runtime::spawn(
+            async move {
+                let req = AddMemberRequest { handle: target.clone() };
+                let result = client.add_group_member(&group_id, &req).await;
+                (group_id, target, result)
+            },
+            move |(group_id, target, result)| {
+                match result {
+                    Ok(member) => {
+                        get_members_store().add_member(&group_id, member);
+                        handle.set(String::new());
+                        feedback.set(Some(format!("Added @{}", target)));
+                    }
+                    Err(e) => error.set(Some(format!("Couldn't invite @{}: {}", target, e))),
+                }
+                inviting.set(false);
+            },
+        );
+    };
+
+    let on_create = move || {
+        error.set(None);
+        feedback.set(None);
+        creating.set(true);
+        let req = CreateInviteRequest {
+            expires_at: crate::invites::expires_at(&expiry.get(), Utc::now()),
+            max_uses: max_uses.get().parse::<u32>().ok().filter(|n| *n > 0),
+        };
+        let client = get_auth_store().make_client_for(&h.get());
+        let group_id = gid.get().clone();
+        crate::runtime::spawn(
+            async move { client.create_invite(&group_id, &req).await },
+            move |result| {
+                match result {
+                    Ok(invite) => {
+                        copy_link(&invite.code);
+                        invites.update(|list| list.insert(0, invite));
+                    }
+                    Err(e) => error.set(Some(format!("Couldn't create an invite link: {}", e))),
+                }
+                creating.set(false);
+            },
+        );
+    };
+
+    let on_revoke = move |code: String| {
+        let client = get_auth_store().make_client_for(&h.get());
+        let group_id = gid.get().clone();
+        crate::runtime::spawn(
+            async move {
+                let result = client.revoke_invite(&group_id, &code).await;
+                (code, result)
+            },
+            move |(code, result)| match result {
+                Ok(()) => invites.update(|list| list.retain(|i| i.code != code)),
+                Err(e) => error.set(Some(format!("Couldn't revoke the link: {}", e))),
+            },
+        );
+    };
+
+    rsx! {
+        Stack {
+            gap: "sm",
+            style: "padding-bottom: 12px; border-bottom: 1px solid var(--rinch-color-dark-4, #373a40);",
+
+            if let Some(err) = error.get().clone() {
+                Alert {
+                    color: "red",
+                    variant: "light",
+                    {err}
+                }
+            }
+
+            if let Some(message) = feedback.get().clone() {
+                Text {
+                    size: "xs",
+                    color: "dimmed",
+                    {message}
+                }
+            }
+
+            Group {
+                gap: "xs",
+                style: "align-items: flex-end;",
+
+                TextInput {
+                    label: "Invite by handle",
+                    placeholder: "@someone@example.org",
+                    style: "flex: 1;",
+                    value_fn: move || handle.get().clone(),
+                    oninput: move |val: String| handle.set(val),
+                    onsubmit: move || on_invite(),
+                }
+
+                Button {
+                    variant: "filled",
+                    color: "indigo",
+                    loading: inviting.get(),
+                    disabled: handle.get().trim().is_empty(),
+                    onclick: move || on_invite(),
+                    "Invite"
+                }
+            }
+
+            Group {
+                gap: "xs",
+                style: "align-items: flex-end;",
+
+                Select {
+                    label: "Link expires after",
+                    value_fn: move || expiry.get().clone(),
+                    onchange: move |val: String| expiry.set(val),
+                    for (key, label) in EXPIRY_CHOICES {
+                        option { value: key, {label} }
+                    }
+                }
+
+                Select {
+                    label: "Max uses",
+                    value_fn: move || max_uses.get().clone(),
+                    onchange: move |val: String| max_uses.set(val),
+                    for n in MAX_USES_CHOICES {
+                        option {
+                            value: {n.to_string()},
+                            {if n == 0 { "No limit".to_string() } else { n.to_string() }}
+                        }
+                    }
+                }
+
+                Button {
+                    variant: "light",
+                    color: "indigo",
+                    loading: creating.get(),
+                    onclick: move || on_create(),
+                    "Create invite link"
+                }
+            }
+
+            for invite in invites.get().clone() {
+                let copy_code = invite.code.clone();
+                let revoke_code = invite.code.clone();
+                div {
+                    key: invite.code.clone(),
+                    style: "display: flex; align-items: center; gap: 8px;",
+
+                    Stack {
+                        gap: "0",
+                        style: "flex: 1; min-width: 0;",
+
+                        Text {
+                            size: "sm",
+                            style: "font-family: monospace; overflow: hidden; text-overflow: ellipsis; white-space: nowrap;",
+                            {link_url(&invite.code)}
+                        }
+
+                        Text {
+                            size: "xs",
+                            color: {if invite.is_usable(Utc::now()) { "dimmed" } else { "red" }},
+                            {crate::invites::describe(&invite, Utc::now())}
+                        }
+                    }
+
+                    Tooltip {
+                        label: "Copy link",
+
+                        ActionIcon {
+                            variant: "subtle",
+                            size: "sm",
+                            onclick: move || copy_link(&copy_code),
+                            {render_tabler_icon(__scope, TablerIcon::Copy, TablerIconStyle::Outline)}
+                        }
+                    }
+
+                    Tooltip {
+                        label: "Revoke",
+
+                        ActionIcon {
+                            variant: "subtle",
+                            color: "red",
+                            size: "sm",
+                            onclick: move || on_revoke(revoke_code.clone()),
+                            {render_tabler_icon(__scope, TablerIcon::Trash, TablerIconStyle::Outline)}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use rinch::prelude::*;
-use crate::stores::{get_groups_store, get_members_store};
+use rorumall_shared::GroupInvitePermission;
+use crate::stores::{get_auth_store, get_groups_store, get_members_store};
+use crate::components::ui::emoji_editor::emoji_editor;
+use crate::components::ui::group_invites::group_invites;
 use crate::components::ui::role_editor::role_editor;
 
 #[component]
@@ -17,9 +20,28 @@ pub fn group_settings(host: String, group_id: String) -> NodeHandle {
 
     let group_name_val = Signal::new(group_name.clone());
 
+    // The group's invite permission decides who sees the invite controls
+    let invite_permission = Signal::new(None::<GroupInvitePermission>);
+    {
+        let client = get_auth_store().make_client_for(&host);
+        let gid = group_id.clone();
+        crate::runtime::spawn(
+            async move { client.get_group(&gid).await },
+            move |result| match result {
+                Ok(group) => invite_permission.set(Some(group.privacy.invite_permission)),
+                Err(e) => tracing::error!("Failed to load group: {}", e),
+            },
+        );
+    }
+
     let host_sig = Signal::new(host);
     let gid_sig = Signal::new(group_id);
     let members_store = get_members_store();
+    let can_invite = move || {
+        invite_permission.get().as_ref().is_some_and(|permission| {
+            crate::invites::can_invite(permission, &get_members_store().get_my_roles(&gid_sig.get()))
+        })
+    };
 
     rsx! {
         Stack {
@@ -38,6 +60,7 @@ pub fn group_settings(host: String, group_id: String) -> NodeHandle {
                     Tab { value: "overview", onclick: move || active_tab.set("overview".to_string()), "Overview" }
                     Tab { value: "members", onclick: move || active_tab.set("members".to_string()), "Members" }
                     Tab { value: "roles", onclick: move || active_tab.set("roles".to_string()), "Roles" }
+                    Tab { value: "emoji", onclick: move || active_tab.set("emoji".to_string()), "Emoji" }
                 }
             }
 
@@ -58,6 +81,10 @@ pub fn group_settings(host: String, group_id: String) -> NodeHandle {
                     gap: "xs",
                     p: "md",
 
+                    if can_invite() {
+                        {group_invites(__scope, host_sig.get().clone(), gid_sig.get().clone())}
+                    }
+
                     for member in members_store.members.get().get(&gid_sig.get()).cloned().unwrap_or_default() {
                         Group {
                             gap: "sm",
@@ -93,6 +120,11 @@ pub fn group_settings(host: String, group_id: String) -> NodeHandle {
                     p: "md",
                     {role_editor(__scope, host_sig.get().clone(), gid_sig.get().clone())}
                 }
+            } else if active_tab.get().as_str() == "emoji" {
+                Stack {
+                    p: "md",
+                    {emoji_editor(__scope, host_sig.get().clone(), gid_sig.get().clone())}
+                }
             }
         }
     }
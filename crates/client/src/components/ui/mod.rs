@@ -1,11 +1,14 @@
+pub mod accept_invite_modal;
 pub mod attachment_display;
 pub mod avatar_uploader;
 pub mod channel_list;
 pub mod connection_status;
 pub mod create_channel_modal;
 pub mod create_group_modal;
+pub mod emoji_editor;
 pub mod file_picker;
 pub mod group_card;
+pub mod group_invites;
 pub mod group_list;
 pub mod group_settings;
 pub mod role_editor;
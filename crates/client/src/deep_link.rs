@@ -0,0 +1,219 @@
+//! `rorumall://` and `ofscp://` links to groups, channels, messages, invites
+//! and user profiles, and the desktop entry that hands them to the app.
+//!
+//! The part after the scheme is the provider the link points into, then a
+//! path:
+//!
+//! ```text
+//! rorumall://chat.example.org/groups/<group>
+//! rorumall://chat.example.org/groups/<group>/channels/<channel>
+//! rorumall://chat.example.org/groups/<group>/channels/<channel>/messages/<message>
+//! rorumall://chat.example.org/invite/<code>
+//! rorumall://chat.example.org/users/<handle>
+//! ```
+//!
+//! Plain `https://<provider>/invite/<code>` links are understood as well.
+
+use std::sync::Mutex;
+
+use crate::invites::InviteLink;
+use crate::navigation::{navigate, navigate_to_channel, navigate_to_group, navigate_to_message, AppRoute};
+use crate::stores::{get_auth_store, get_invites_store};
+
+/// Schemes the app handles; the first is the one it writes.
+pub const SCHEMES: [&str; 2] = ["rorumall", "ofscp"];
+
+/// Link that arrived before anyone was signed in, followed once the home
+/// screen opens.
+static PENDING: Mutex<Option<String>> = Mutex::new(None);
+
+/// A place in the app a link can lead to. An empty `host` is the home
+/// provider.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeepLink {
+    Group { host: String, group_id: String },
+    Channel { host: String, group_id: String, channel_id: String },
+    Message { host: String, group_id: String, channel_id: String, message_id: String },
+    Invite(InviteLink),
+    Profile { host: String, handle: String },
+}
+
+impl DeepLink {
+    /// The link in `text`, which may be a deep link or an https invite link.
+    /// Links into `home` come back with an empty host.
+    pub fn parse(text: &str, home: &str) -> Option<Self> {
+        let text = text.trim();
+        let Some(rest) = SCHEMES.iter().find_map(|scheme| strip_scheme(text, scheme)) else {
+            return InviteLink::find(text, home).map(Self::Invite);
+        };
+        let rest = rest.split(['?', '#']).next().unwrap_or_default();
+        let (domain, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let host = crate::discovery::host_for(domain, home)?;
+        let segments: Vec<String> = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| urlencoding::decode(s).map(|s| s.into_owned()))
+            .collect::<Result<_, _>>()
+            .ok()?;
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+        Some(match segments.as_slice() {
+            ["groups", group_id] => Self::Group { host, group_id: group_id.to_string() },
+            ["groups", group_id, "channels", channel_id] => Self::Channel {
+                host,
+                group_id: group_id.to_string(),
+                channel_id: channel_id.to_string(),
+            },
+            ["groups", group_id, "channels", channel_id, "messages", message_id] => Self::Message {
+                host,
+                group_id: group_id.to_string(),
+                channel_id: channel_id.to_string(),
+                message_id: message_id.to_string(),
+            },
+            ["invite", code] => Self::Invite(InviteLink { host, code: code.to_string() }),
+            ["users", handle] => Self::Profile {
+                host,
+                handle: handle.trim_start_matches('@').to_string(),
+            },
+            _ => return None,
+        })
+    }
+
+    /// The `rorumall://` form of this link; `home` stands in for an empty host.
+    pub fn uri(&self, home: &str) -> String {
+        let host = match self {
+            Self::Group { host, .. }
+            | Self::Channel { host, .. }
+            | Self::Message { host, .. }
+            | Self::Profile { host, .. } => host,
+            Self::Invite(link) => &link.host,
+        };
+        let domain = if host.is_empty() {
+            home.trim_start_matches("https://").trim_start_matches("http://").trim_end_matches('/')
+        } else {
+            host
+        };
+        let e = |s: &str| urlencoding::encode(s).into_owned();
+        let path = match self {
+            Self::Group { group_id, .. } => format!("groups/{}", e(group_id)),
+            Self::Channel { group_id, channel_id, .. } => {
+                format!("groups/{}/channels/{}", e(group_id), e(channel_id))
+            }
+            Self::Message { group_id, channel_id, message_id, .. } => format!(
+                "groups/{}/channels/{}/messages/{}",
+                e(group_id),
+                e(channel_id),
+                e(message_id)
+            ),
+            Self::Invite(link) => format!("invite/{}", e(&link.code)),
+            Self::Profile { handle, .. } => format!("users/{}", e(handle)),
+        };
+        format!("{}://{}/{}", SCHEMES[0], domain, path)
+    }
+
+    /// Go where the link points.
+    pub fn open(self) {
+        match self {
+            Self::Group { host, group_id } => navigate_to_group(host, group_id),
+            Self::Channel { host, group_id, channel_id } => navigate_to_channel(host, group_id, channel_id),
+            Self::Message { host, group_id, channel_id, message_id } => {
+                navigate_to_message(host, group_id, channel_id, message_id)
+            }
+            Self::Invite(link) => get_invites_store().open(link),
+            Self::Profile { host, handle } => navigate(AppRoute::UserProfile { host, handle }),
+        }
+    }
+}
+
+fn strip_scheme<'a>(text: &'a str, scheme: &str) -> Option<&'a str> {
+    let (prefix, rest) = text.split_once("://")?;
+    prefix.eq_ignore_ascii_case(scheme).then_some(rest)
+}
+
+/// Follow a link passed on the command line or forwarded by a second
+/// launch. Runs on the main thread; before sign-in the link waits for the
+/// home screen.
+pub fn handle(text: &str) {
+    if !get_auth_store().is_authenticated() {
+        set_pending(text.to_string());
+        return;
+    }
+    match DeepLink::parse(text, &get_auth_store().domain()) {
+        Some(link) => link.open(),
+        None => tracing::warn!("Ignoring unrecognised link: {}", text),
+    }
+}
+
+pub fn set_pending(text: String) {
+    *PENDING.lock().unwrap() = Some(text);
+}
+
+/// Follow the link that arrived before sign-in, if any.
+pub fn open_pending() {
+    let pending = PENDING.lock().unwrap().take();
+    if let Some(text) = pending {
+        handle(&text);
+    }
+}
+
+/// The first argument that looks like a link, skipping the program name.
+pub fn link_in_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    args.into_iter().skip(1).find(|arg| arg.contains("://"))
+}
+
+/// Desktop entry registering the app as handler of its URI schemes.
+pub fn desktop_entry(exe: &str) -> String {
+    let mime_types: String = SCHEMES.iter().map(|s| format!("x-scheme-handler/{};", s)).collect();
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Rorumall\n\
+         Comment=OFSCP chat client\n\
+         Exec=\"{}\" %u\n\
+         Terminal=false\n\
+         Categories=Network;Chat;InstantMessaging;\n\
+         MimeType={}\n",
+        exe.replace('\\', "\\\\").replace('"', "\\\""),
+        mime_types
+    )
+}
+
+/// Install the desktop entry under `~/.local/share/applications` and make
+/// it the default handler for the app's schemes. Does nothing when the
+/// entry already points at this executable.
+#[cfg(target_os = "linux")]
+pub fn register_handler() {
+    let Some(dir) = dirs::data_dir().map(|d| d.join("applications")) else {
+        return;
+    };
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    let path = dir.join("rorumall.desktop");
+    let entry = desktop_entry(&exe.to_string_lossy());
+    if std::fs::read_to_string(&path).is_ok_and(|existing| existing == entry) {
+        return;
+    }
+    if let Err(e) = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, &entry)) {
+        tracing::warn!("Couldn't write {}: {}", path.display(), e);
+        return;
+    }
+
+    let mut commands = vec![vec!["update-desktop-database".to_string(), dir.to_string_lossy().into_owned()]];
+    for scheme in SCHEMES {
+        commands.push(vec![
+            "xdg-mime".to_string(),
+            "default".to_string(),
+            "rorumall.desktop".to_string(),
+            format!("x-scheme-handler/{}", scheme),
+        ]);
+    }
+    for command in commands {
+        if let Err(e) = std::process::Command::new(&command[0]).args(&command[1..]).status() {
+            tracing::warn!("Couldn't run {}: {}", command[0], e);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn register_handler() {}
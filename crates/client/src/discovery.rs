@@ -0,0 +1,235 @@
+//! Finding public groups on the home provider and on federated ones, and
+//! joining them.
+
+use rorumall_shared::{
+    AddJoinedGroupRequest, ApiError, Group, GroupDiscoverability, JoinGroupRequest, JoinStatus,
+    UserJoinedGroup,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api_client::ApiClient;
+
+const DOMAINS_KEY: &str = "discovery_domains";
+const REQUESTS_KEY: &str = "join_requests";
+/// Federated domains remembered for the server picker.
+const MAX_DOMAINS: usize = 8;
+/// Pages fetched per search; the directory is searched server-side, so
+/// more than this means the query should be narrower.
+const MAX_PAGES: usize = 5;
+
+/// How a group takes new members, from `Group.join_policy`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JoinPolicy {
+    Open,
+    Request,
+    Invite,
+}
+
+impl JoinPolicy {
+    /// Unknown policies count as invite-only, so nothing is sent that the
+    /// group didn't ask for.
+    pub fn of(group: &Group) -> Self {
+        match group.join_policy.to_lowercase().as_str() {
+            "open" => Self::Open,
+            "request" => Self::Request,
+            _ => Self::Invite,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Open => "Open",
+            Self::Request => "Approval needed",
+            Self::Invite => "Invite only",
+        }
+    }
+}
+
+/// A group found in a provider's directory. An empty `host` is the home
+/// provider.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiscoveredGroup {
+    pub host: String,
+    pub group: Group,
+}
+
+impl DiscoveredGroup {
+    /// Key for remembering join requests, unique across providers.
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.host, self.group.id)
+    }
+}
+
+/// A request to join a group that waits for the group's admins, kept so
+/// it can be checked on later.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JoinRequest {
+    pub host: String,
+    pub group_id: String,
+    pub name: String,
+    pub avatar: Option<String>,
+}
+
+impl JoinRequest {
+    /// Same as the `DiscoveredGroup` key for the group.
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.host, self.group_id)
+    }
+}
+
+impl From<&DiscoveredGroup> for JoinRequest {
+    fn from(found: &DiscoveredGroup) -> Self {
+        Self {
+            host: found.host.clone(),
+            group_id: found.group.id.clone(),
+            name: found.group.name.clone(),
+            avatar: found.group.avatar.clone(),
+        }
+    }
+}
+
+/// What came of asking to join.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JoinOutcome {
+    Joined(UserJoinedGroup),
+    Pending,
+}
+
+/// `input` as a bare provider domain such as `chat.example.org` or
+/// `localhost:8080`. Accepts URLs and `@handle@domain` addresses.
+pub fn normalize_domain(input: &str) -> Option<String> {
+    let input = input.trim();
+    let input = input
+        .strip_prefix("https://")
+        .or_else(|| input.strip_prefix("http://"))
+        .unwrap_or(input);
+    let domain = input
+        .rsplit('@')
+        .next()
+        .unwrap_or(input)
+        .split('/')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('.')
+        .to_lowercase();
+    let valid = !domain.is_empty()
+        && domain.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'))
+        && (domain.contains('.') || domain.contains(':') || domain == "localhost");
+    valid.then_some(domain)
+}
+
+/// The host to search for `input`: empty for the home provider.
+pub fn host_for(input: &str, home: &str) -> Option<String> {
+    if input.trim().is_empty() {
+        return Some(String::new());
+    }
+    let domain = normalize_domain(input)?;
+    if normalize_domain(home).as_deref() == Some(domain.as_str()) {
+        Some(String::new())
+    } else {
+        Some(domain)
+    }
+}
+
+/// Whether `group` mentions every word of `query` in its name or description.
+pub fn matches(group: &Group, query: &str) -> bool {
+    let text = format!("{} {}", group.name, group.description.as_deref().unwrap_or_default()).to_lowercase();
+    query.to_lowercase().split_whitespace().all(|word| text.contains(word))
+}
+
+/// Public groups on the provider `client` talks to that match `query`.
+pub async fn search(client: &ApiClient, host: &str, query: &str) -> Result<Vec<DiscoveredGroup>, ApiError> {
+    let query = query.trim();
+    let mut groups = Vec::new();
+    let mut cursor = None;
+    for _ in 0..MAX_PAGES {
+        let page = client.list_public_groups(query, cursor.as_deref()).await?;
+        groups.extend(page.items);
+        cursor = page.page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    // Providers that ignore the filters still only show what they list publicly
+    Ok(groups
+        .into_iter()
+        .filter(|g| g.privacy.discoverability == GroupDiscoverability::Public && matches(g, query))
+        .map(|group| DiscoveredGroup { host: host.to_string(), group })
+        .collect())
+}
+
+/// Ask to join `found` through its own provider, and once membership is
+/// granted record it in the user's group list on the home provider.
+pub async fn join(
+    group_client: &ApiClient,
+    home_client: &ApiClient,
+    user_id: &str,
+    found: &DiscoveredGroup,
+    req: &JoinGroupRequest,
+) -> Result<JoinOutcome, ApiError> {
+    let response = group_client.join_group(&found.group.id, req).await?;
+    if response.status == JoinStatus::Pending {
+        return Ok(JoinOutcome::Pending);
+    }
+
+    add_to_group_list(home_client, user_id, &JoinRequest::from(found)).await
+}
+
+/// Look up a pending request to join. `None` once it's no longer open:
+/// turned down, or withdrawn on the provider. An approved request is
+/// recorded in the user's group list like a direct join.
+pub async fn check_request(
+    group_client: &ApiClient,
+    home_client: &ApiClient,
+    user_id: &str,
+    request: &JoinRequest,
+) -> Result<Option<JoinOutcome>, ApiError> {
+    let status = match group_client.join_status(&request.group_id).await {
+        Ok(response) => response.status,
+        Err(ApiError::Http { status: 404 | 410, .. }) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    match status {
+        JoinStatus::Pending => Ok(Some(JoinOutcome::Pending)),
+        JoinStatus::Rejected => Ok(None),
+        JoinStatus::Joined => add_to_group_list(home_client, user_id, request).await.map(Some),
+    }
+}
+
+async fn add_to_group_list(
+    home_client: &ApiClient,
+    user_id: &str,
+    request: &JoinRequest,
+) -> Result<JoinOutcome, ApiError> {
+    let req = AddJoinedGroupRequest {
+        group_id: request.group_id.clone(),
+        host: (!request.host.is_empty()).then(|| request.host.clone()),
+        name: request.name.clone(),
+        avatar: request.avatar.clone(),
+    };
+    let joined = home_client.add_joined_group(user_id, &req).await?;
+    Ok(JoinOutcome::Joined(joined))
+}
+
+/// Federated domains searched before, most recent first.
+pub fn saved_domains() -> Vec<String> {
+    crate::storage::load(DOMAINS_KEY).unwrap_or_default()
+}
+
+pub fn remember_domain(domain: &str) {
+    let mut domains = saved_domains();
+    domains.retain(|d| d != domain);
+    domains.insert(0, domain.to_string());
+    domains.truncate(MAX_DOMAINS);
+    crate::storage::save(DOMAINS_KEY, &domains);
+}
+
+/// Join requests still waiting for approval.
+pub fn saved_requests() -> Vec<JoinRequest> {
+    crate::storage::load(REQUESTS_KEY).unwrap_or_default()
+}
+
+pub fn save_requests(requests: &[JoinRequest]) {
+    crate::storage::save(REQUESTS_KEY, &requests);
+}
@@ -0,0 +1,94 @@
+//! Attachment downloads streamed to disk.
+//!
+//! Bytes are written to a hidden `.part` file beside the destination and
+//! renamed into place once complete, so a failed download never leaves a
+//! truncated file under the real name.
+
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+use crate::api_client::ApiClient;
+
+/// Fewest bytes between progress reports.
+const PROGRESS_STEP: u64 = 64 * 1024;
+
+/// Where quick downloads go: the user's Downloads folder, or home.
+pub fn default_dir() -> PathBuf {
+    dirs::download_dir().unwrap_or_else(|| dirs::home_dir().unwrap_or_default())
+}
+
+/// A name that is safe to create on any platform. Server-provided names may
+/// contain separators or `..`.
+pub fn safe_file_name(name: &str, fallback: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    let name = name.trim().trim_matches('.').to_string();
+    if name.is_empty() { fallback.to_string() } else { name }
+}
+
+/// `path`, or `name (n).ext` beside it if something already exists there.
+pub fn unique_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem.to_string(), format!(".{}", ext)),
+        _ => (name, String::new()),
+    };
+    (1..)
+        .map(|n| path.with_file_name(format!("{} ({}){}", stem, n, ext)))
+        .find(|p| !p.exists())
+        .expect("unbounded range")
+}
+
+/// Download `url` to `dest`, reporting bytes received and the expected
+/// total. With `replace` false an existing file is kept and the download
+/// gets a ` (n)` suffix instead. Returns where the file ended up.
+pub async fn download(
+    client: &ApiClient,
+    url: &str,
+    dest: &Path,
+    replace: bool,
+    mut progress: impl FnMut(u64, Option<u64>) + Send,
+) -> Result<PathBuf, String> {
+    let mut resp = client.get_response(url).await.map_err(|e| e.to_string())?;
+    let total = resp.content_length();
+
+    let name = dest.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let part = dest.with_file_name(format!(".{}.{}.part", name, uuid::Uuid::new_v4().simple()));
+    let result = async {
+        let mut file = tokio::fs::File::create(&part).await.map_err(|e| e.to_string())?;
+        let mut received = 0u64;
+        let mut reported = 0u64;
+        // About once per percent rather than for every chunk
+        let step = (total.unwrap_or_default() / 100).max(PROGRESS_STEP);
+        progress(0, total);
+        while let Some(chunk) = resp.chunk().await.map_err(|e| e.to_string())? {
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+            received += chunk.len() as u64;
+            if received - reported >= step {
+                reported = received;
+                progress(received, total);
+            }
+        }
+        progress(received, total);
+        if total.is_some_and(|t| t != received) {
+            return Err("the connection closed before the download finished".to_string());
+        }
+        file.sync_all().await.map_err(|e| e.to_string())?;
+        drop(file);
+
+        let dest = if replace { dest.to_path_buf() } else { unique_path(dest) };
+        tokio::fs::rename(&part, &dest).await.map_err(|e| e.to_string())?;
+        Ok(dest)
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&part).await;
+    }
+    result
+}
@@ -0,0 +1,412 @@
+//! Emoji shortcodes.
+//!
+//! `:shortcode:` text is turned into unicode emoji when a message is sent.
+//! Custom group emoji keep their `:shortcode:` form in the message and are
+//! drawn as images when rendered, since they only exist in that group.
+
+use rorumall_shared::Reaction;
+
+/// A built-in emoji with its shortcode and extra search words.
+pub struct Emoji {
+    pub shortcode: &'static str,
+    pub unicode: &'static str,
+    pub keywords: &'static [&'static str],
+}
+
+macro_rules! emoji {
+    ($($code:literal => $unicode:literal $([$($kw:literal),*])?),* $(,)?) => {
+        &[$(Emoji { shortcode: $code, unicode: $unicode, keywords: &[$($($kw),*)?] }),*]
+    };
+}
+
+/// Built-in emoji in picker order.
+pub const EMOJI: &[Emoji] = emoji![
+    "smile" => "😄" ["happy", "joy"],
+    "grinning" => "😀" ["happy"],
+    "joy" => "😂" ["laugh", "tears", "lol"],
+    "rofl" => "🤣" ["laugh", "lol"],
+    "slightly_smiling_face" => "🙂" ["happy"],
+    "wink" => "😉",
+    "blush" => "😊" ["happy", "shy"],
+    "innocent" => "😇" ["angel"],
+    "heart_eyes" => "😍" ["love", "crush"],
+    "star_struck" => "🤩" ["wow", "excited"],
+    "kissing_heart" => "😘" ["love"],
+    "yum" => "😋" ["tasty", "delicious"],
+    "stuck_out_tongue" => "😛" ["tongue"],
+    "zany_face" => "🤪" ["crazy", "silly"],
+    "hugs" => "🤗" ["hug"],
+    "thinking" => "🤔" ["hmm", "think"],
+    "shushing_face" => "🤫" ["quiet", "secret"],
+    "neutral_face" => "😐" ["meh"],
+    "expressionless" => "😑" ["blank"],
+    "no_mouth" => "😶" ["silent"],
+    "smirk" => "😏" ["smug"],
+    "unamused" => "😒" ["meh"],
+    "roll_eyes" => "🙄" ["eyeroll", "whatever"],
+    "grimacing" => "😬" ["awkward", "yikes"],
+    "relieved" => "😌" ["calm"],
+    "pensive" => "😔" ["sad"],
+    "sleepy" => "😪" ["tired"],
+    "sleeping" => "😴" ["zzz", "tired"],
+    "mask" => "😷" ["sick", "ill"],
+    "nauseated_face" => "🤢" ["sick", "gross"],
+    "exploding_head" => "🤯" ["mind", "blown"],
+    "cowboy_hat_face" => "🤠" ["cowboy"],
+    "partying_face" => "🥳" ["party", "celebrate"],
+    "sunglasses" => "😎" ["cool"],
+    "nerd_face" => "🤓" ["geek"],
+    "confused" => "😕",
+    "worried" => "😟" ["nervous"],
+    "open_mouth" => "😮" ["surprised", "wow"],
+    "astonished" => "😲" ["shocked", "wow"],
+    "flushed" => "😳" ["embarrassed"],
+    "pleading_face" => "🥺" ["please", "puppy"],
+    "cry" => "😢" ["sad", "tear"],
+    "sob" => "😭" ["sad", "crying"],
+    "scream" => "😱" ["scared", "horror"],
+    "sweat_smile" => "😅" ["relief", "phew"],
+    "sweat" => "😓",
+    "weary" => "😩" ["tired", "frustrated"],
+    "yawning_face" => "🥱" ["bored", "tired"],
+    "triumph" => "😤" ["proud"],
+    "rage" => "😡" ["angry", "mad"],
+    "angry" => "😠" ["mad"],
+    "skull" => "💀" ["dead"],
+    "poop" => "💩" ["poo"],
+    "clown_face" => "🤡" ["clown"],
+    "ghost" => "👻" ["halloween"],
+    "alien" => "👽" ["ufo"],
+    "robot" => "🤖" ["bot"],
+    "see_no_evil" => "🙈" ["monkey"],
+    "wave" => "👋" ["hello", "hi", "bye"],
+    "raised_hand" => "✋" ["stop", "high five"],
+    "ok_hand" => "👌" ["ok", "perfect"],
+    "v" => "✌️" ["peace", "victory"],
+    "crossed_fingers" => "🤞" ["luck"],
+    "point_up" => "☝️",
+    "point_right" => "👉",
+    "point_down" => "👇",
+    "+1" => "👍" ["thumbsup", "yes", "like"],
+    "thumbsup" => "👍" ["yes", "like"],
+    "-1" => "👎" ["thumbsdown", "no", "dislike"],
+    "thumbsdown" => "👎" ["no", "dislike"],
+    "fist" => "✊" ["power"],
+    "clap" => "👏" ["applause", "bravo"],
+    "raised_hands" => "🙌" ["hooray", "celebrate"],
+    "open_hands" => "👐",
+    "handshake" => "🤝" ["deal", "agree"],
+    "pray" => "🙏" ["please", "thanks"],
+    "muscle" => "💪" ["strong", "flex"],
+    "eyes" => "👀" ["look", "watching"],
+    "brain" => "🧠" ["smart"],
+    "heart" => "❤️" ["love"],
+    "orange_heart" => "🧡" ["love"],
+    "yellow_heart" => "💛" ["love"],
+    "green_heart" => "💚" ["love"],
+    "blue_heart" => "💙" ["love"],
+    "purple_heart" => "💜" ["love"],
+    "black_heart" => "🖤",
+    "broken_heart" => "💔" ["sad"],
+    "sparkling_heart" => "💖" ["love"],
+    "100" => "💯" ["perfect", "score"],
+    "boom" => "💥" ["explosion"],
+    "sparkles" => "✨" ["shiny", "new"],
+    "star" => "⭐" ["favorite"],
+    "fire" => "🔥" ["hot", "lit"],
+    "zap" => "⚡" ["lightning", "fast"],
+    "rainbow" => "🌈",
+    "sunny" => "☀️" ["sun", "weather"],
+    "cloud" => "☁️" ["weather"],
+    "snowflake" => "❄️" ["cold", "winter"],
+    "tada" => "🎉" ["party", "celebrate", "congrats"],
+    "confetti_ball" => "🎊" ["party"],
+    "balloon" => "🎈" ["party", "birthday"],
+    "gift" => "🎁" ["present", "birthday"],
+    "trophy" => "🏆" ["win", "award"],
+    "medal" => "🏅" ["award"],
+    "rocket" => "🚀" ["launch", "ship"],
+    "airplane" => "✈️" ["travel", "flight"],
+    "car" => "🚗",
+    "house" => "🏠" ["home"],
+    "coffee" => "☕" ["cafe", "tea"],
+    "tea" => "🍵",
+    "beer" => "🍺" ["drink", "cheers"],
+    "beers" => "🍻" ["drink", "cheers"],
+    "wine_glass" => "🍷" ["drink"],
+    "pizza" => "🍕" ["food"],
+    "hamburger" => "🍔" ["food", "burger"],
+    "taco" => "🌮" ["food"],
+    "cake" => "🍰" ["dessert"],
+    "birthday" => "🎂" ["cake"],
+    "cookie" => "🍪" ["dessert"],
+    "apple" => "🍎" ["fruit"],
+    "avocado" => "🥑" ["fruit"],
+    "popcorn" => "🍿" ["movie"],
+    "dog" => "🐶" ["pet", "puppy"],
+    "cat" => "🐱" ["pet", "kitten"],
+    "fox_face" => "🦊" ["fox"],
+    "bear" => "🐻",
+    "panda_face" => "🐼" ["panda"],
+    "unicorn" => "🦄",
+    "bee" => "🐝" ["insect"],
+    "bug" => "🐛" ["insect", "defect"],
+    "crab" => "🦀" ["rust", "ferris"],
+    "snake" => "🐍" ["python"],
+    "turtle" => "🐢" ["slow"],
+    "octopus" => "🐙",
+    "seedling" => "🌱" ["plant", "new"],
+    "evergreen_tree" => "🌲" ["tree"],
+    "cactus" => "🌵" ["plant"],
+    "sunflower" => "🌻" ["flower"],
+    "rose" => "🌹" ["flower"],
+    "earth_africa" => "🌍" ["world", "globe"],
+    "moon" => "🌙" ["night"],
+    "soccer" => "⚽" ["football", "sport"],
+    "basketball" => "🏀" ["sport"],
+    "video_game" => "🎮" ["game", "controller"],
+    "musical_note" => "🎵" ["music"],
+    "headphones" => "🎧" ["music"],
+    "art" => "🎨" ["paint", "design"],
+    "camera" => "📷" ["photo"],
+    "computer" => "💻" ["laptop"],
+    "keyboard" => "⌨️",
+    "iphone" => "📱" ["phone", "mobile"],
+    "bulb" => "💡" ["idea"],
+    "books" => "📚" ["read", "library"],
+    "memo" => "📝" ["note", "write"],
+    "pencil2" => "✏️" ["write"],
+    "pushpin" => "📌" ["pin"],
+    "paperclip" => "📎" ["attachment"],
+    "calendar" => "📆" ["date"],
+    "chart_with_upwards_trend" => "📈" ["graph", "up"],
+    "chart_with_downwards_trend" => "📉" ["graph", "down"],
+    "lock" => "🔒" ["secure", "private"],
+    "key" => "🔑" ["password"],
+    "hammer" => "🔨" ["tool", "build"],
+    "wrench" => "🔧" ["tool", "fix"],
+    "gear" => "⚙️" ["settings"],
+    "link" => "🔗" ["url"],
+    "mag" => "🔍" ["search"],
+    "bell" => "🔔" ["notification"],
+    "mega" => "📣" ["announcement"],
+    "email" => "📧" ["mail"],
+    "package" => "📦" ["box", "shipping"],
+    "hourglass" => "⌛" ["time", "wait"],
+    "alarm_clock" => "⏰" ["time"],
+    "warning" => "⚠️" ["caution"],
+    "no_entry" => "⛔" ["stop"],
+    "x" => "❌" ["no", "wrong"],
+    "white_check_mark" => "✅" ["done", "yes"],
+    "heavy_check_mark" => "✔️" ["done", "yes"],
+    "question" => "❓" ["what"],
+    "exclamation" => "❗" ["important"],
+    "bangbang" => "‼️",
+    "arrow_right" => "➡️",
+    "arrow_left" => "⬅️",
+    "arrow_up" => "⬆️",
+    "arrow_down" => "⬇️",
+    "recycle" => "♻️",
+    "checkered_flag" => "🏁" ["finish"],
+    "construction" => "🚧" ["wip"],
+    "rotating_light" => "🚨" ["alert", "incident"],
+];
+
+/// Most recently used emoji kept for the picker.
+pub const MAX_RECENTS: usize = 24;
+
+/// Shortcodes may use letters, digits, `_`, `+` and `-`.
+pub fn is_shortcode(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 32
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '+' || c == '-')
+}
+
+/// Normalize a custom emoji name typed by an admin, with or without colons.
+pub fn normalize_shortcode(input: &str) -> Option<String> {
+    let name = input.trim().trim_matches(':').to_lowercase();
+    is_shortcode(&name).then_some(name)
+}
+
+pub fn lookup(shortcode: &str) -> Option<&'static Emoji> {
+    EMOJI.iter().find(|e| e.shortcode == shortcode)
+}
+
+pub fn by_unicode(unicode: &str) -> Option<&'static Emoji> {
+    EMOJI.iter().find(|e| e.unicode == unicode)
+}
+
+/// Built-in emoji whose shortcode or keywords contain `query`, with
+/// shortcode prefix matches first. Duplicate aliases are shown once.
+pub fn search(query: &str) -> Vec<&'static Emoji> {
+    let query = query.trim().trim_matches(':').to_lowercase();
+    let mut seen: Vec<&str> = Vec::new();
+    let mut unique = EMOJI.iter().filter(|e| {
+        let new = !seen.contains(&e.unicode);
+        seen.push(e.unicode);
+        new
+    });
+    if query.is_empty() {
+        return unique.collect();
+    }
+    let (mut prefix, mut rest): (Vec<_>, Vec<_>) = (Vec::new(), Vec::new());
+    for e in unique.by_ref() {
+        if e.shortcode.starts_with(&query) {
+            prefix.push(e);
+        } else if e.shortcode.contains(&query) || e.keywords.iter().any(|k| k.contains(&query)) {
+            rest.push(e);
+        }
+    }
+    prefix.extend(rest);
+    prefix
+}
+
+/// Replace `:shortcode:` with unicode emoji, outside code spans and code
+/// blocks. Unknown shortcodes, including custom group emoji, and ones run
+/// into a word or number, as in `1:100:1`, are left alone.
+pub fn convert_shortcodes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_fence = false;
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            in_fence = !in_fence;
+            out.push_str(line);
+            continue;
+        }
+        if in_fence {
+            out.push_str(line);
+            continue;
+        }
+        // Odd-numbered backtick-separated pieces are inline code
+        for (j, piece) in line.split('`').enumerate() {
+            if j > 0 {
+                out.push('`');
+            }
+            if j % 2 == 1 {
+                out.push_str(piece);
+            } else {
+                out.push_str(&replace_codes(piece, |name| lookup(name).map(|e| e.unicode.to_string())));
+            }
+        }
+    }
+    out
+}
+
+/// The reactions to a message that share a key, shown as one chip.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReactionChip {
+    pub key: String,
+    pub unicode: Option<String>,
+    pub image: Option<String>,
+    pub count: usize,
+    /// The reader's own reaction with this key, which clicking the chip takes back.
+    pub own: Option<String>,
+}
+
+/// Group reactions by key, in the order each key was first used.
+pub fn reaction_chips(reactions: &[Reaction], is_own: impl Fn(&Reaction) -> bool) -> Vec<ReactionChip> {
+    let mut chips: Vec<ReactionChip> = Vec::new();
+    for reaction in reactions {
+        let own = is_own(reaction).then(|| reaction.id.clone());
+        match chips.iter_mut().find(|c| c.key == reaction.key) {
+            Some(chip) => {
+                chip.count += 1;
+                chip.own = chip.own.take().or(own);
+                chip.image = chip.image.take().or_else(|| reaction.image.clone());
+            }
+            None => chips.push(ReactionChip {
+                key: reaction.key.clone(),
+                unicode: reaction.unicode.clone(),
+                image: reaction.image.clone(),
+                count: 1,
+                own,
+            }),
+        }
+    }
+    chips
+}
+
+/// Plain text split around custom emoji shortcodes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    Text(String),
+    Emoji { shortcode: String, url: String },
+}
+
+/// Split text into runs of plain text and known custom emoji.
+pub fn custom_segments(text: &str, url_for: impl Fn(&str) -> Option<String>) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut plain = String::new();
+    for part in scan(text, url_for) {
+        match part {
+            Part::Text(t) => plain.push_str(t),
+            Part::Match(name, url) => {
+                if !plain.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut plain)));
+                }
+                segments.push(Segment::Emoji { shortcode: name.to_string(), url });
+            }
+        }
+    }
+    if !plain.is_empty() {
+        segments.push(Segment::Text(plain));
+    }
+    segments
+}
+
+fn replace_codes(text: &str, replace: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    for part in scan(text, replace) {
+        match part {
+            Part::Text(t) => out.push_str(t),
+            Part::Match(_, r) => out.push_str(&r),
+        }
+    }
+    out
+}
+
+enum Part<'a, T> {
+    Text(&'a str),
+    /// A `:name:` that `resolve` recognised, with what it resolved to.
+    Match(&'a str, T),
+}
+
+/// Split `text` around `:name:` shortcodes that `resolve` recognises. A
+/// shortcode touching a letter or digit is left as text, so `1:100:1` and
+/// `word:smile:` don't change. Anything else between colons stays text, so
+/// `12:30 :smile:` still finds the emoji.
+fn scan<T>(text: &str, resolve: impl Fn(&str) -> Option<T>) -> Vec<Part<'_, T>> {
+    let mut parts = Vec::new();
+    let mut plain_start = 0;
+    let mut pos = 0;
+    while let Some(found) = text[pos..].find(':') {
+        let start = pos + found;
+        let after = &text[start + 1..];
+        let hit = after
+            .find(':')
+            .map(|end| &after[..end])
+            .filter(|name| is_shortcode(name) && stands_alone(text, start, start + name.len() + 2))
+            .and_then(|name| resolve(name).map(|value| (name, value)));
+        match hit {
+            Some((name, value)) => {
+                parts.push(Part::Text(&text[plain_start..start]));
+                parts.push(Part::Match(name, value));
+                pos = start + name.len() + 2;
+                plain_start = pos;
+            }
+            None => pos = start + 1,
+        }
+    }
+    parts.push(Part::Text(&text[plain_start..]));
+    parts
+}
+
+/// Whether `text[start..end]` has no letter or digit right before or after it.
+fn stands_alone(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
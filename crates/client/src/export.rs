@@ -0,0 +1,395 @@
+//! Channel history export.
+//!
+//! The full history is paged in through `MessagesPage` cursors and written as
+//! a JSON dump, a Markdown transcript or a self-contained HTML file.
+//! Attachments can be downloaded into a folder next to the export, which the
+//! transcript then links to by relative path.
+
+use rorumall_shared::{Attachment, ChannelMessage, MessageType};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::api_client::ApiClient;
+
+const PAGE_SIZE: u32 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Markdown,
+    Html,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Json, ExportFormat::Markdown, ExportFormat::Html];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "JSON",
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Html => "HTML",
+        }
+    }
+
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.extension() == ext)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ExportOptions {
+    pub group_id: String,
+    pub channel_id: String,
+    pub channel_name: String,
+    pub format: ExportFormat,
+    pub path: PathBuf,
+    pub download_attachments: bool,
+}
+
+/// Progress reported while an export runs.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExportUpdate {
+    /// Messages fetched so far.
+    Fetched(usize),
+    /// Attachments downloaded so far, out of the total.
+    Downloaded(usize, usize),
+    Writing,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportSummary {
+    pub path: PathBuf,
+    pub messages: usize,
+    pub attachments: usize,
+    /// Attachments that could not be downloaded; the export links to their
+    /// server URL instead.
+    pub failed_attachments: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExportError {
+    Cancelled,
+    Failed(String),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::Cancelled => write!(f, "Export cancelled"),
+            ExportError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Downloaded attachments: attachment id to path relative to the export file.
+pub type LocalFiles = BTreeMap<String, String>;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelExport<'a> {
+    channel_id: &'a str,
+    channel_name: &'a str,
+    exported_at: String,
+    messages: &'a [ChannelMessage],
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    files: &'a LocalFiles,
+}
+
+/// Run an export. `cancel` is checked between pages and downloads; a
+/// cancelled export leaves nothing behind, removing the attachments it
+/// downloaded and their folder if it created it.
+pub async fn run(
+    client: ApiClient,
+    options: ExportOptions,
+    cancel: Arc<AtomicBool>,
+    report: impl Fn(ExportUpdate) + Send + Sync,
+) -> Result<ExportSummary, ExportError> {
+    let messages = fetch_history(&client, &options.group_id, &options.channel_id, &cancel, &report).await?;
+
+    let mut files = LocalFiles::new();
+    let mut written: Vec<PathBuf> = Vec::new();
+    let mut failed = 0;
+    let dir = attachments_dir(&options.path);
+    // Only remove the folder on cancel if this export created it
+    let created_dir = options.download_attachments && !dir.exists();
+    if options.download_attachments {
+        let attachments: Vec<&Attachment> = messages.iter().flat_map(|m| &m.attachments).collect();
+        if !attachments.is_empty() {
+            tokio::fs::create_dir_all(&dir)
+                .await
+                .map_err(|e| ExportError::Failed(format!("Failed to create {}: {}", dir.display(), e)))?;
+        }
+        let folder = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let mut used = HashSet::new();
+        for (i, att) in attachments.iter().enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                discard(&dir, created_dir, &written).await;
+                return Err(ExportError::Cancelled);
+            }
+            let name = unique_name(&local_name(att), &mut used);
+            // An existing file in the folder is kept; the download gets a
+            // numbered name beside it
+            match crate::download::download(&client, &att.url, &dir.join(&name), false, |_, _| {}).await {
+                Ok(path) => {
+                    let saved = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or(name);
+                    files.insert(att.id.clone(), format!("{}/{}", folder, saved));
+                    written.push(path);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to download attachment {}: {}", att.id, e);
+                    failed += 1;
+                }
+            }
+            report(ExportUpdate::Downloaded(i + 1, attachments.len()));
+        }
+    }
+
+    if cancel.load(Ordering::Relaxed) {
+        discard(&dir, created_dir, &written).await;
+        return Err(ExportError::Cancelled);
+    }
+    report(ExportUpdate::Writing);
+
+    let exported_at = chrono::Utc::now().to_rfc3339();
+    let content = match options.format {
+        ExportFormat::Json => to_json(&options.channel_id, &options.channel_name, &exported_at, &messages, &files),
+        ExportFormat::Markdown => to_markdown(&options.channel_name, &exported_at, &messages, &files),
+        ExportFormat::Html => to_html(&options.channel_name, &exported_at, &messages, &files),
+    };
+    tokio::fs::write(&options.path, content)
+        .await
+        .map_err(|e| ExportError::Failed(format!("Failed to write {}: {}", options.path.display(), e)))?;
+
+    Ok(ExportSummary {
+        path: options.path,
+        messages: messages.len(),
+        attachments: files.len(),
+        failed_attachments: failed,
+    })
+}
+
+/// Remove what a cancelled export downloaded: the whole folder if the export
+/// created it, otherwise only the files it added.
+async fn discard(dir: &Path, created: bool, written: &[PathBuf]) {
+    if created {
+        let _ = tokio::fs::remove_dir_all(dir).await;
+        return;
+    }
+    for path in written {
+        let _ = tokio::fs::remove_file(path).await;
+    }
+}
+
+/// Every message in the channel, oldest first.
+async fn fetch_history(
+    client: &ApiClient,
+    group_id: &str,
+    channel_id: &str,
+    cancel: &AtomicBool,
+    report: &(impl Fn(ExportUpdate) + Send + Sync),
+) -> Result<Vec<ChannelMessage>, ExportError> {
+    let mut messages = Vec::new();
+    let mut seen_ids = HashSet::new();
+    let mut seen_cursors = HashSet::new();
+    let mut cursor: Option<String> = None;
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(ExportError::Cancelled);
+        }
+        let page = client
+            .list_messages(group_id, channel_id, cursor.as_deref(), PAGE_SIZE)
+            .await
+            .map_err(|e| ExportError::Failed(format!("Failed to load messages: {}", e)))?;
+        let before = messages.len();
+        messages.extend(page.items.into_iter().filter(|m| seen_ids.insert(m.id.clone())));
+        report(ExportUpdate::Fetched(messages.len()));
+
+        // Stop at the end, and on a server that hands back a cursor it
+        // already gave us or a page with nothing new.
+        match page.page.next_cursor {
+            Some(next) if messages.len() > before && seen_cursors.insert(next.clone()) => cursor = Some(next),
+            _ => break,
+        }
+    }
+
+    messages.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(messages)
+}
+
+/// Folder next to `path` that holds downloaded attachments, e.g.
+/// `general.html` keeps them in `general_files/`.
+pub fn attachments_dir(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "export".to_string());
+    path.with_file_name(format!("{}_files", stem))
+}
+
+/// Default export file name for a channel.
+pub fn default_file_name(channel_name: &str, format: ExportFormat) -> String {
+    let name = sanitize(channel_name);
+    let name = if name.is_empty() { "channel".to_string() } else { name };
+    format!("{}-{}.{}", name, chrono::Local::now().format("%Y-%m-%d"), format.extension())
+}
+
+fn local_name(att: &Attachment) -> String {
+    let name = sanitize(&crate::search::attachment_name(att));
+    if name.is_empty() { att.id.clone() } else { name }
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect::<String>()
+        .trim_matches('.')
+        .to_string()
+}
+
+/// `name`, or `name_n.ext` if it has already been used.
+fn unique_name(name: &str, used: &mut HashSet<String>) -> String {
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (name, String::new()),
+    };
+    let mut candidate = name.to_string();
+    let mut n = 1;
+    while !used.insert(candidate.to_lowercase()) {
+        candidate = format!("{}_{}{}", stem, n, ext);
+        n += 1;
+    }
+    candidate
+}
+
+pub fn to_json(channel_id: &str, channel_name: &str, exported_at: &str, messages: &[ChannelMessage], files: &LocalFiles) -> String {
+    let export = ChannelExport {
+        channel_id,
+        channel_name,
+        exported_at: exported_at.to_string(),
+        messages,
+        files,
+    };
+    serde_json::to_string_pretty(&export).unwrap_or_default()
+}
+
+pub fn to_markdown(channel_name: &str, exported_at: &str, messages: &[ChannelMessage], files: &LocalFiles) -> String {
+    let mut out = format!("# #{}\n\nExported {}, {} messages.\n", channel_name, exported_at, messages.len());
+
+    for msg in messages {
+        out.push_str("\n---\n\n");
+        if let Some(title) = msg.title.as_deref().filter(|_| msg.message_type == Some(MessageType::Article)) {
+            out.push_str(&format!("## {}\n\n", title));
+        }
+        out.push_str(&format!("**{}** · {}", msg.sender_user_id, msg.created_at));
+        if let Some(parent) = &msg.parent_id {
+            out.push_str(&format!(" · reply to `{}`", parent));
+        }
+        out.push_str("\n\n");
+
+        if let Some(excerpt) = msg.reference.as_ref().and_then(|r| r.excerpt.as_deref()) {
+            for line in excerpt.lines() {
+                out.push_str(&format!("> {}\n", line));
+            }
+            out.push('\n');
+        }
+
+        if !msg.body.is_empty() {
+            out.push_str(msg.body.trim_end());
+            out.push_str("\n\n");
+        }
+
+        for att in &msg.attachments {
+            let name = crate::search::attachment_name(att);
+            match attachment_href(att, files) {
+                Some(href) => out.push_str(&format!("- [{}]({})\n", name, href)),
+                None => out.push_str(&format!("- {}\n", name)),
+            }
+        }
+    }
+    out
+}
+
+pub fn to_html(channel_name: &str, exported_at: &str, messages: &[ChannelMessage], files: &LocalFiles) -> String {
+    let title = ammonia::clean_text(&format!("#{}", channel_name));
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n<p class=\"meta\">Exported {}, {} messages.</p>\n",
+        title,
+        HTML_STYLE,
+        title,
+        ammonia::clean_text(exported_at),
+        messages.len(),
+    );
+
+    for msg in messages {
+        out.push_str(&format!("<article class=\"message\" id=\"m-{}\">\n", ammonia::clean_text(&msg.id)));
+        out.push_str(&format!(
+            "<header><strong>{}</strong> <span class=\"meta\">{}</span>",
+            ammonia::clean_text(&msg.sender_user_id),
+            ammonia::clean_text(&msg.created_at),
+        ));
+        if let Some(parent) = &msg.parent_id {
+            let parent = ammonia::clean_text(parent);
+            out.push_str(&format!(" <a class=\"meta\" href=\"#m-{}\">in reply</a>", parent));
+        }
+        out.push_str("</header>\n");
+
+        if let Some(title) = msg.title.as_deref().filter(|_| msg.message_type == Some(MessageType::Article)) {
+            out.push_str(&format!("<h2>{}</h2>\n", ammonia::clean_text(title)));
+        }
+        if let Some(excerpt) = msg.reference.as_ref().and_then(|r| r.excerpt.as_deref()) {
+            out.push_str(&format!("<blockquote>{}</blockquote>\n", ammonia::clean_text(excerpt)));
+        }
+
+        if crate::markdown::is_markdown(msg.mime.as_deref()) {
+            out.push_str(&format!("<div class=\"body\">{}</div>\n", crate::markdown::render_markdown(&msg.body)));
+        } else {
+            out.push_str(&format!("<pre class=\"body\">{}</pre>\n", ammonia::clean_text(&msg.body)));
+        }
+
+        for att in &msg.attachments {
+            let name = ammonia::clean_text(&crate::search::attachment_name(att));
+            match attachment_href(att, files).map(|href| ammonia::clean_text(&href)) {
+                Some(href) if att.mime.starts_with("image/") && files.contains_key(&att.id) => {
+                    out.push_str(&format!("<a href=\"{}\"><img src=\"{}\" alt=\"{}\"></a>\n", href, href, name));
+                }
+                Some(href) => out.push_str(&format!("<p class=\"attachment\"><a href=\"{}\">{}</a></p>\n", href, name)),
+                None => out.push_str(&format!("<p class=\"attachment\">{}</p>\n", name)),
+            }
+        }
+        out.push_str("</article>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// The downloaded copy, or else the server URL when it is http(s). Other
+/// schemes, such as `javascript:`, are never linked.
+fn attachment_href(att: &Attachment, files: &LocalFiles) -> Option<String> {
+    if let Some(local) = files.get(&att.id) {
+        return Some(local.clone());
+    }
+    url::Url::parse(&att.url)
+        .ok()
+        .filter(|u| matches!(u.scheme(), "http" | "https"))
+        .map(|u| u.to_string())
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:860px;margin:2em auto;padding:0 1em;color:#1a1b1e;line-height:1.5}\
+.message{border-top:1px solid #dee2e6;padding:.75em 0}\
+.meta{color:#868e96;font-size:.85em}\
+blockquote{border-left:3px solid #4c6ef5;margin:.5em 0;padding-left:.75em;color:#495057}\
+pre{background:#f1f3f5;padding:.75em;border-radius:4px;overflow-x:auto;white-space:pre-wrap}\
+img{max-width:100%;border-radius:4px}";
@@ -0,0 +1,68 @@
+//! Syntax highlighting for fenced code blocks.
+//!
+//! Uses syntect's bundled grammars with the pure-Rust regex engine. Output is
+//! class-based (`hl-keyword`, `hl-string`, ...) so colors come from the app
+//! stylesheet in `theme.rs` rather than a syntect theme.
+
+use std::sync::OnceLock;
+use syntect::html::{ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+/// Blocks larger than this are shown without highlighting.
+const MAX_HIGHLIGHT_BYTES: usize = 64 * 1024;
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+/// Language name from a fence info string, e.g. `rust,ignore` -> `rust`.
+pub fn fence_language(info: &str) -> Option<String> {
+    info.split(|c: char| c == ',' || c.is_whitespace())
+        .next()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_lowercase())
+}
+
+/// Whether a grammar exists for the language token (`rust`, `rs`, `sql`, `sh`, ...).
+pub fn is_supported(language: &str) -> bool {
+    syntax_set().find_syntax_by_token(language).is_some()
+}
+
+/// Highlight `code` as HTML spans, or `None` if the language is unknown or the
+/// block is too large. Code text is escaped by the generator.
+pub fn highlight(code: &str, language: &str) -> Option<String> {
+    if code.len() > MAX_HIGHLIGHT_BYTES {
+        return None;
+    }
+    let syntaxes = syntax_set();
+    let syntax = syntaxes.find_syntax_by_token(language)?;
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, syntaxes, CLASS_STYLE);
+    for line in LinesWithEndings::from(code) {
+        if let Err(e) = generator.parse_html_for_line_which_includes_newline(line) {
+            tracing::warn!("Failed to highlight {} block: {}", language, e);
+            return None;
+        }
+    }
+    Some(generator.finalize())
+}
+
+/// Escape code for display when it can't be highlighted.
+pub fn escape(code: &str) -> String {
+    let mut out = String::with_capacity(code.len());
+    for c in code.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
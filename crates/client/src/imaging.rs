@@ -0,0 +1,232 @@
+//! Image preparation before upload.
+//!
+//! Pictures are decoded, turned upright, scaled down to the user's maximum
+//! dimension and re-encoded, which also drops EXIF and other metadata such
+//! as GPS positions. A small JPEG thumbnail is made either way, and the
+//! final width and height are recorded so receivers can reserve space before
+//! the image loads. "Send original" keeps the file untouched.
+//!
+//! The image crate only writes lossless WebP, which is usually larger than
+//! the lossy original, so a WebP that needs no scaling or turning keeps its
+//! encoded bytes and only loses its metadata chunks.
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Longest side of thumbnails.
+const THUMBNAIL_SIZE: u32 = 320;
+const THUMBNAIL_QUALITY: u8 = 75;
+
+/// Format images are re-encoded to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormatChoice {
+    /// JPEG stays JPEG, WebP stays WebP, anything else becomes PNG.
+    #[default]
+    Auto,
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl ImageFormatChoice {
+    pub const ALL: [ImageFormatChoice; 4] = [Self::Auto, Self::Png, Self::Jpeg, Self::Webp];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+            Self::Webp => "webp",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Self {
+        Self::ALL.into_iter().find(|f| f.key() == key).unwrap_or_default()
+    }
+}
+
+/// How pictures are prepared before upload.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageSettings {
+    /// Longest side after scaling down; 0 keeps the full resolution.
+    pub max_dimension: u32,
+    pub format: ImageFormatChoice,
+    /// JPEG quality, 1-100.
+    pub jpeg_quality: u8,
+}
+
+impl Default for ImageSettings {
+    fn default() -> Self {
+        Self { max_dimension: 2048, format: ImageFormatChoice::Auto, jpeg_quality: 85 }
+    }
+}
+
+/// An image ready to upload.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreparedImage {
+    /// The re-encoded file in the temp directory, or the original file.
+    pub path: PathBuf,
+    pub filename: String,
+    pub mime: String,
+    pub width: u32,
+    pub height: u32,
+    /// JPEG, at most `THUMBNAIL_SIZE` on its longest side.
+    pub thumbnail: Vec<u8>,
+    /// Whether `path` is a temp file the caller should remove after upload.
+    pub temporary: bool,
+}
+
+/// Whether `mime` is a still image this module can decode. GIFs are left
+/// alone so animations survive.
+pub fn is_processable(mime: &str) -> bool {
+    matches!(mime, "image/png" | "image/jpeg" | "image/webp")
+}
+
+/// Prepare the image at `path` for upload. With `original` the file is sent
+/// as-is and only measured and thumbnailed.
+pub fn prepare(path: &Path, filename: &str, original: bool, settings: &ImageSettings) -> Result<PreparedImage, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let (image, source_format, orientation) = decode(&bytes)?;
+    let thumbnail = encode_jpeg(&image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE), THUMBNAIL_QUALITY)?;
+
+    if original {
+        return Ok(PreparedImage {
+            path: path.to_path_buf(),
+            filename: filename.to_string(),
+            mime: crate::components::messages::message_input::mime_from_extension(filename),
+            width: image.width(),
+            height: image.height(),
+            thumbnail,
+            temporary: false,
+        });
+    }
+
+    let max = settings.max_dimension;
+    let resize = max > 0 && (image.width() > max || image.height() > max);
+    let image = if resize {
+        image.resize(max, max, FilterType::Lanczos3)
+    } else {
+        image
+    };
+
+    let format = match settings.format {
+        ImageFormatChoice::Auto => match source_format {
+            ImageFormat::Jpeg => ImageFormatChoice::Jpeg,
+            ImageFormat::WebP => ImageFormatChoice::Webp,
+            _ => ImageFormatChoice::Png,
+        },
+        other => other,
+    };
+    let (data, extension, mime) = match format {
+        ImageFormatChoice::Jpeg => (encode_jpeg(&image, settings.jpeg_quality.clamp(1, 100))?, "jpg", "image/jpeg"),
+        ImageFormatChoice::Webp => {
+            let unchanged = source_format == ImageFormat::WebP && !resize && orientation == Orientation::NoTransforms;
+            let stripped = if unchanged { strip_webp_metadata(&bytes) } else { None };
+            let buf = match stripped {
+                Some(buf) => buf,
+                None => {
+                    let mut buf = Vec::new();
+                    image
+                        .to_rgba8()
+                        .write_with_encoder(WebPEncoder::new_lossless(&mut buf))
+                        .map_err(|e| e.to_string())?;
+                    buf
+                }
+            };
+            (buf, "webp", "image/webp")
+        }
+        _ => {
+            let mut buf = Cursor::new(Vec::new());
+            image.write_to(&mut buf, ImageFormat::Png).map_err(|e| e.to_string())?;
+            (buf.into_inner(), "png", "image/png")
+        }
+    };
+
+    let dir = std::env::temp_dir().join("rorumall-uploads");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let out = dir.join(format!("{}.{}", uuid::Uuid::new_v4().simple(), extension));
+    std::fs::write(&out, &data).map_err(|e| e.to_string())?;
+
+    Ok(PreparedImage {
+        path: out,
+        filename: with_extension(filename, extension),
+        mime: mime.to_string(),
+        width: image.width(),
+        height: image.height(),
+        thumbnail,
+        temporary: true,
+    })
+}
+
+/// Decode `bytes` and apply the EXIF orientation, since re-encoding drops
+/// the tag that would otherwise rotate it. The orientation is returned too.
+fn decode(bytes: &[u8]) -> Result<(DynamicImage, ImageFormat, Orientation), String> {
+    let reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format().map_err(|e| e.to_string())?;
+    let format = reader.format().ok_or("unrecognised image format")?;
+    let mut decoder = reader.into_decoder().map_err(|e| e.to_string())?;
+    let orientation = decoder.orientation().map_err(|e| e.to_string())?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| e.to_string())?;
+    image.apply_orientation(orientation);
+    Ok((image, format, orientation))
+}
+
+/// A WebP file without its `EXIF` and `XMP ` chunks, with the `VP8X`
+/// header flags for them cleared. `None` if `bytes` isn't a well-formed
+/// RIFF WebP container.
+fn strip_webp_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WEBP" {
+        return None;
+    }
+    let mut out = bytes[..12].to_vec();
+    let mut pos = 12;
+    while pos < bytes.len() {
+        let header = bytes.get(pos..pos + 8)?;
+        let size = u32::from_le_bytes(header[4..8].try_into().ok()?) as usize;
+        let data_end = pos.checked_add(8)?.checked_add(size)?;
+        if data_end > bytes.len() {
+            return None;
+        }
+        // Chunks are padded to an even size
+        let end = (data_end + size % 2).min(bytes.len());
+        match &header[..4] {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" if size > 0 => {
+                let start = out.len();
+                out.extend_from_slice(&bytes[pos..end]);
+                out[start + 8] &= !(0x08 | 0x04);
+            }
+            _ => out.extend_from_slice(&bytes[pos..end]),
+        }
+        pos = end;
+    }
+    let riff_size = u32::try_from(out.len() - 8).ok()?;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Some(out)
+}
+
+/// JPEG has no alpha channel, so it is dropped first.
+fn encode_jpeg(image: &DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
+    let mut buf = Vec::new();
+    image
+        .to_rgb8()
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut buf, quality))
+        .map_err(|e| e.to_string())?;
+    Ok(buf)
+}
+
+/// Swap the last extension, so `photo.png` becomes `photo.jpg`.
+fn with_extension(filename: &str, extension: &str) -> String {
+    let stem = match filename.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => filename,
+    };
+    format!("{}.{}", stem, extension)
+}
@@ -0,0 +1,129 @@
+//! Invite links: building and recognising them, and who may hand them out.
+
+use chrono::{DateTime, Duration, Utc};
+use rorumall_shared::{GroupInvite, GroupInvitePermission};
+
+/// Path segment invite links are served under.
+const INVITE_PATH: &str = "invite";
+
+/// Link expiry choices for the invite form, as (key, label).
+pub const EXPIRY_CHOICES: [(&str, &str); 5] = [
+    ("30m", "30 minutes"),
+    ("1d", "1 day"),
+    ("7d", "7 days"),
+    ("30d", "30 days"),
+    ("never", "Never"),
+];
+
+/// Use limits for the invite form; 0 is unlimited.
+pub const MAX_USES_CHOICES: [u32; 6] = [0, 1, 5, 10, 25, 100];
+
+/// An invite code and the provider that issued it. An empty `host` is the
+/// home provider.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InviteLink {
+    pub host: String,
+    pub code: String,
+}
+
+impl InviteLink {
+    /// The first invite link in `text`, e.g. `https://chat.example.org/invite/Ab3xY9`.
+    /// Links to `home` come back with an empty host.
+    pub fn find(text: &str, home: &str) -> Option<Self> {
+        text.split_whitespace().find_map(|word| Self::parse(word, home))
+    }
+
+    fn parse(word: &str, home: &str) -> Option<Self> {
+        let word = word.trim_matches(|c: char| matches!(c, '<' | '>' | '(' | ')' | '"' | '\'' | ',' | '.'));
+        let rest = word
+            .strip_prefix("https://")
+            .or_else(|| word.strip_prefix("http://"))?;
+        let mut parts = rest.split('/');
+        let domain = parts.next()?;
+        if parts.next()? != INVITE_PATH {
+            return None;
+        }
+        let code = parts.next()?.split(['?', '#']).next()?;
+        if parts.next().is_some_and(|p| !p.is_empty()) || !is_code(code) {
+            return None;
+        }
+        Some(Self {
+            host: crate::discovery::host_for(domain, home)?,
+            code: code.to_string(),
+        })
+    }
+
+    /// The shareable link; `home` stands in for an empty host.
+    pub fn url(&self, home: &str) -> String {
+        let domain = if self.host.is_empty() { home } else { &self.host };
+        crate::auth_session::api_url(domain, &format!("/{}/{}", INVITE_PATH, self.code))
+    }
+}
+
+fn is_code(code: &str) -> bool {
+    !code.is_empty() && code.len() <= 64 && code.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+}
+
+/// `@handle@domain`, `handle@domain` or a local `handle` in the
+/// `handle@domain` form the API takes; local handles stay bare.
+pub fn normalize_handle(input: &str) -> Option<String> {
+    let input = input.trim().trim_start_matches('@');
+    let (handle, domain) = match input.split_once('@') {
+        Some((handle, domain)) => (handle, Some(crate::discovery::normalize_domain(domain)?)),
+        None => (input, None),
+    };
+    let valid = !handle.is_empty()
+        && handle.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if !valid {
+        return None;
+    }
+    Some(match domain {
+        Some(domain) => format!("{}@{}", handle.to_lowercase(), domain),
+        None => handle.to_lowercase(),
+    })
+}
+
+/// Whether a member holding `roles` may invite people under `permission`.
+/// Someone without roles isn't a member (or hasn't loaded yet) and may not.
+pub fn can_invite(permission: &GroupInvitePermission, roles: &[String]) -> bool {
+    !roles.is_empty() && permission.allows(roles)
+}
+
+/// When a link made now with expiry choice `key` stops working.
+pub fn expires_at(key: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let duration = match key {
+        "30m" => Duration::minutes(30),
+        "1d" => Duration::days(1),
+        "7d" => Duration::days(7),
+        "30d" => Duration::days(30),
+        _ => return None,
+    };
+    Some(now + duration)
+}
+
+/// Uses and expiry of `invite`, e.g. "3 of 10 uses · expires in 2 days".
+pub fn describe(invite: &GroupInvite, now: DateTime<Utc>) -> String {
+    let uses = match invite.max_uses {
+        Some(max) => format!("{} of {} uses", invite.uses, max),
+        None if invite.uses == 1 => "1 use".to_string(),
+        None => format!("{} uses", invite.uses),
+    };
+    let expiry = match invite.expires_at {
+        _ if invite.is_used_up() => "used up".to_string(),
+        Some(at) if at <= now => "expired".to_string(),
+        Some(at) => format!("expires in {}", format_remaining(at - now)),
+        None => "never expires".to_string(),
+    };
+    format!("{} · {}", uses, expiry)
+}
+
+fn format_remaining(left: Duration) -> String {
+    let plural = |n: i64, unit: &str| if n == 1 { format!("1 {}", unit) } else { format!("{} {}s", n, unit) };
+    if left.num_days() >= 1 {
+        plural(left.num_days(), "day")
+    } else if left.num_hours() >= 1 {
+        plural(left.num_hours(), "hour")
+    } else {
+        plural(left.num_minutes().max(1), "minute")
+    }
+}
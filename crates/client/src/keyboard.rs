@@ -0,0 +1,46 @@
+//! Global keyboard handling.
+//!
+//! rinch has a single keyboard interceptor, so the app installs one that
+//! routes keys here. An open modal view, such as the lightbox, gets every key
+//! first and the composer none; otherwise the composer mounted last handles
+//! them. A handler returns whether it used the key.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type KeyHandler = Rc<dyn Fn(&str, bool) -> bool>;
+
+thread_local! {
+    static COMPOSER_HANDLER: RefCell<Option<KeyHandler>> = const { RefCell::new(None) };
+    static MODAL_HANDLER: RefCell<Option<KeyHandler>> = const { RefCell::new(None) };
+}
+
+/// Route rinch's keyboard interceptor through this module. Call once at startup.
+pub fn install() {
+    rinch_core::set_keyboard_interceptor(|key_data| dispatch(&key_data.key, key_data.ctrl));
+}
+
+fn dispatch(key: &str, ctrl: bool) -> bool {
+    // Cloned out so a handler can replace or clear itself
+    if let Some(modal) = MODAL_HANDLER.with(|h| h.borrow().clone()) {
+        return modal(key, ctrl);
+    }
+    COMPOSER_HANDLER
+        .with(|h| h.borrow().clone())
+        .is_some_and(|composer| composer(key, ctrl))
+}
+
+/// Handle keys for the composer, replacing the previous composer's handler.
+pub fn set_composer_handler(handler: impl Fn(&str, bool) -> bool + 'static) {
+    COMPOSER_HANDLER.with(|h| *h.borrow_mut() = Some(Rc::new(handler)));
+}
+
+/// Give a modal view the keyboard until [`clear_modal_handler`]. Keys it
+/// doesn't use keep their default behaviour but don't reach the composer.
+pub fn set_modal_handler(handler: impl Fn(&str, bool) -> bool + 'static) {
+    MODAL_HANDLER.with(|h| *h.borrow_mut() = Some(Rc::new(handler)));
+}
+
+pub fn clear_modal_handler() {
+    MODAL_HANDLER.with(|h| *h.borrow_mut() = None);
+}
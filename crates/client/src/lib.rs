@@ -4,11 +4,30 @@ pub mod api_client;
 pub mod app;
 pub mod auth_session;
 pub mod client_keys;
+pub mod commands;
 pub mod components;
+pub mod deep_link;
+pub mod discovery;
+pub mod download;
+pub mod emoji;
+pub mod export;
+pub mod highlight;
+pub mod imaging;
+pub mod invites;
+pub mod keyboard;
+pub mod link_preview;
+pub mod markdown;
+pub mod media;
+pub mod message_cache;
+pub mod multipart;
 pub mod navigation;
+pub mod paste;
 pub mod runtime;
+pub mod search;
 pub mod storage;
 pub mod stores;
+pub mod tags;
 pub mod theme;
+pub mod upload;
 pub mod views;
 pub mod ws;
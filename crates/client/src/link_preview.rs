@@ -0,0 +1,575 @@
+//! Link previews for URLs found in message bodies.
+//!
+//! Fetching a preview contacts the linked site directly, which reveals the
+//! user's IP address to it, so fetches only happen when the user's
+//! preferences allow the domain (see [`LinkPreviewPolicy`]). Requests go
+//! through [`PreviewClient`], which never signs requests, keeps no cookies,
+//! only connects to public addresses and caps both response size and time.
+//! Results are cached on disk.
+
+use chrono::{DateTime, Duration, Utc};
+use rorumall_shared::ApiError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+const CACHE_KEY_PREFIX: &str = "link_preview_";
+const CACHE_TTL_HOURS: i64 = 24;
+const MAX_URLS_PER_MESSAGE: usize = 3;
+const MAX_BODY_BYTES: usize = 512 * 1024;
+const MAX_IMAGE_BYTES: usize = 2 * 1024 * 1024;
+const REQUEST_TIMEOUT_SECS: u64 = 5;
+const MAX_REDIRECTS: usize = 3;
+const MAX_TEXT_CHARS: usize = 300;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LinkPreview {
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub site_name: Option<String>,
+}
+
+impl LinkPreview {
+    /// A preview with nothing to show beyond the URL itself.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_none() && self.image.is_none()
+    }
+
+    pub fn domain(&self) -> String {
+        url::Url::parse(&self.url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+            .unwrap_or_default()
+    }
+}
+
+/// Which domains previews may be fetched for. A deny entry always wins; when
+/// the allow list is non-empty only those domains are fetched.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinkPreviewPolicy {
+    pub enabled: bool,
+    pub allow_domains: Vec<String>,
+    pub deny_domains: Vec<String>,
+}
+
+impl LinkPreviewPolicy {
+    pub fn allows(&self, url: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let Some(host) = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+        else {
+            return false;
+        };
+        if self.deny_domains.iter().any(|d| domain_matches(&host, d)) {
+            return false;
+        }
+        self.allow_domains.is_empty() || self.allow_domains.iter().any(|d| domain_matches(&host, d))
+    }
+}
+
+/// `host` equals `domain` or is a subdomain of it.
+fn domain_matches(host: &str, domain: &str) -> bool {
+    let domain = domain.trim().trim_start_matches("*.").trim_matches('.').to_lowercase();
+    !domain.is_empty() && (host == domain || host.ends_with(&format!(".{}", domain)))
+}
+
+/// Parse a comma- or whitespace-separated domain list from a settings field.
+pub fn parse_domain_list(input: &str) -> Vec<String> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|d| d.trim().trim_start_matches("*.").to_lowercase())
+        .filter(|d| !d.is_empty())
+        .collect()
+}
+
+/// http(s) URLs in `text`, in order of appearance, without duplicates.
+pub fn extract_urls(text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for word in text.split(|c: char| c.is_whitespace() || c == '<' || c == '>') {
+        let Some(start) = word.find("https://").or_else(|| word.find("http://")) else {
+            continue;
+        };
+        let candidate = trim_url(&word[start..]);
+        if url::Url::parse(candidate).is_ok_and(|u| u.host_str().is_some())
+            && !urls.iter().any(|u| u == candidate)
+        {
+            urls.push(candidate.to_string());
+            if urls.len() == MAX_URLS_PER_MESSAGE {
+                break;
+            }
+        }
+    }
+    urls
+}
+
+/// Drop trailing punctuation that is usually part of the sentence, and a
+/// closing paren unless the URL contains the matching open paren.
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '"', '*', '_', ']']);
+        let trimmed = if trimmed.ends_with(')') && trimmed.matches('(').count() < trimmed.matches(')').count() {
+            &trimmed[..trimmed.len() - 1]
+        } else {
+            trimmed
+        };
+        if trimmed.len() == url.len() {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
+/// Refuse loopback, private and link-local hosts so a message can't make the
+/// client probe the local network. Names are only checked for the obvious
+/// local ones here; what they resolve to is checked by [`PublicResolver`].
+fn is_public_host(url: &url::Url) -> bool {
+    match url.host() {
+        Some(url::Host::Domain(d)) => {
+            let d = d.to_lowercase();
+            d != "localhost" && !d.ends_with(".localhost") && !d.ends_with(".local")
+        }
+        Some(url::Host::Ipv4(ip)) => is_public_ip(IpAddr::V4(ip)),
+        Some(url::Host::Ipv6(ip)) => is_public_ip(IpAddr::V6(ip)),
+        None => false,
+    }
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_public_ipv4(v4),
+        IpAddr::V6(v6) => {
+            // IPv4-mapped (::ffff:a.b.c.d) and NAT64 (64:ff9b::a.b.c.d)
+            // addresses reach the embedded IPv4 address
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_ipv4(v4);
+            }
+            let segments = v6.segments();
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                let [a, b] = segments[6].to_be_bytes();
+                let [c, d] = segments[7].to_be_bytes();
+                return is_public_ipv4(Ipv4Addr::new(a, b, c, d));
+            }
+            let unique_local = (segments[0] & 0xfe00) == 0xfc00;
+            let link_local = (segments[0] & 0xffc0) == 0xfe80;
+            !(v6.is_loopback() || v6.is_unspecified() || v6.is_multicast() || unique_local || link_local)
+        }
+    }
+}
+
+fn is_public_ipv4(v4: Ipv4Addr) -> bool {
+    let [a, b, ..] = v4.octets();
+    let this_network = a == 0;
+    let shared = a == 100 && (b & 0xc0) == 64;
+    let reserved = a >= 240;
+    !(this_network
+        || shared
+        || reserved
+        || v4.is_loopback()
+        || v4.is_private()
+        || v4.is_link_local()
+        || v4.is_multicast()
+        || v4.is_broadcast())
+}
+
+/// Why a preview couldn't be fetched.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PreviewError {
+    /// The URL, its host or the response can't be previewed.
+    Refused(String),
+    Api(ApiError),
+}
+
+impl PreviewError {
+    /// Whether fetching again would give the same result. Timeouts, network
+    /// errors, 5xx, 408 and 429 may go away on their own.
+    pub fn is_definitive(&self) -> bool {
+        match self {
+            PreviewError::Refused(_) => true,
+            PreviewError::Api(ApiError::Http { status, .. }) => *status < 500 && *status != 408 && *status != 429,
+            PreviewError::Api(_) => false,
+        }
+    }
+
+    fn from_request(e: reqwest::Error) -> Self {
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(&e);
+        while let Some(err) = source {
+            if let Some(not_public) = err.downcast_ref::<NotPublic>() {
+                return PreviewError::Refused(not_public.to_string());
+            }
+            source = err.source();
+        }
+        PreviewError::Api(ApiError::Network(e.to_string()))
+    }
+}
+
+impl std::fmt::Display for PreviewError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreviewError::Refused(e) => write!(f, "{}", e),
+            PreviewError::Api(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// A name that only resolves to addresses [`PublicResolver`] won't connect to.
+#[derive(Debug)]
+struct NotPublic(String);
+
+impl std::fmt::Display for NotPublic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} has no public address", self.0)
+    }
+}
+
+impl std::error::Error for NotPublic {}
+
+/// DNS resolver for [`PreviewClient`] that drops non-public addresses. The
+/// client connects to exactly the addresses checked here, so a name that
+/// points at the local network, or rebinds to it after a first lookup,
+/// can't be reached.
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(Box::new(NotPublic(host)) as Box<dyn std::error::Error + Send + Sync>);
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// HTTP client for fetching link metadata. Unlike `ApiClient` it never
+/// signs requests, keeps no cookies and bounds every fetch.
+#[derive(Debug, Clone)]
+pub struct PreviewClient {
+    client: reqwest::Client,
+}
+
+impl PreviewClient {
+    /// Fails if the sandboxed client can't be built; previews are then off
+    /// rather than fetched by an unrestricted client.
+    pub fn new() -> Result<Self, ApiError> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .redirect(reqwest::redirect::Policy::custom(|attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS || !is_public_host(attempt.url()) {
+                    attempt.stop()
+                } else {
+                    attempt.follow()
+                }
+            }))
+            // A proxy would resolve names itself, past the resolver's check
+            .no_proxy()
+            .dns_resolver(Arc::new(PublicResolver))
+            .user_agent(concat!("rorumall-link-preview/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| ApiError::Network(format!("failed to build link preview client: {e}")))?;
+        Ok(Self { client })
+    }
+
+    pub async fn fetch(&self, url: &str) -> Result<LinkPreview, PreviewError> {
+        let parsed = url::Url::parse(url).map_err(|e| PreviewError::Refused(e.to_string()))?;
+        if !matches!(parsed.scheme(), "http" | "https") || !is_public_host(&parsed) {
+            return Err(PreviewError::Refused(format!("refusing to fetch {}", url)));
+        }
+
+        let mut resp = self
+            .client
+            .get(parsed.clone())
+            .header("Accept", "text/html,application/xhtml+xml")
+            .send()
+            .await
+            .map_err(PreviewError::from_request)?;
+
+        let status = resp.status().as_u16();
+        if !resp.status().is_success() {
+            return Err(PreviewError::Api(ApiError::Http { status, body: String::new() }));
+        }
+        let is_html = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.contains("text/html") || ct.contains("application/xhtml"));
+        if !is_html {
+            return Ok(LinkPreview {
+                url: url.to_string(),
+                title: None,
+                description: None,
+                image: None,
+                site_name: None,
+            });
+        }
+
+        // Metadata lives in <head>, so stop reading once the limit is hit
+        let body = read_capped(&mut resp, MAX_BODY_BYTES).await?;
+
+        let final_url = resp.url().clone();
+        Ok(parse_metadata(url, &final_url, &String::from_utf8_lossy(&body)))
+    }
+
+    /// Fetch a preview's image as a `data:` URI so the card never makes the
+    /// renderer contact the image host itself. Oversized images are refused
+    /// rather than shown cut off.
+    pub async fn fetch_image(&self, url: &str) -> Result<String, PreviewError> {
+        use base64::Engine;
+
+        let parsed = url::Url::parse(url).map_err(|e| PreviewError::Refused(e.to_string()))?;
+        if !matches!(parsed.scheme(), "http" | "https") || !is_public_host(&parsed) {
+            return Err(PreviewError::Refused(format!("refusing to fetch {}", url)));
+        }
+
+        let mut resp = self
+            .client
+            .get(parsed)
+            .header("Accept", "image/*")
+            .send()
+            .await
+            .map_err(PreviewError::from_request)?;
+
+        let status = resp.status().as_u16();
+        if !resp.status().is_success() {
+            return Err(PreviewError::Api(ApiError::Http { status, body: String::new() }));
+        }
+        let mime = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|ct| ct.split(';').next().unwrap_or_default().trim().to_lowercase())
+            .filter(|ct| ct.starts_with("image/") && ct != "image/svg+xml")
+            .ok_or_else(|| PreviewError::Refused(format!("{} isn't an image", url)))?;
+
+        let body = read_capped(&mut resp, MAX_IMAGE_BYTES + 1).await?;
+        if body.len() > MAX_IMAGE_BYTES {
+            return Err(PreviewError::Refused(format!("image at {} is too large", url)));
+        }
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&body);
+        Ok(format!("data:{};base64,{}", mime, encoded))
+    }
+}
+
+/// Read at most `limit` bytes of the body.
+async fn read_capped(resp: &mut reqwest::Response, limit: usize) -> Result<Vec<u8>, PreviewError> {
+    let mut body: Vec<u8> = Vec::new();
+    while let Some(chunk) = resp
+        .chunk()
+        .await
+        .map_err(|e| PreviewError::Api(ApiError::Network(format!("failed to read body: {e}"))))?
+    {
+        body.extend_from_slice(&chunk);
+        if body.len() >= limit {
+            body.truncate(limit);
+            break;
+        }
+    }
+    Ok(body)
+}
+
+/// Extract OpenGraph metadata, falling back to `<title>` and the meta description.
+pub fn parse_metadata(url: &str, base: &url::Url, html: &str) -> LinkPreview {
+    let mut og_title = None;
+    let mut og_description = None;
+    let mut og_image = None;
+    let mut og_site = None;
+    let mut meta_description = None;
+
+    // ASCII-only lowering keeps byte offsets valid for slicing `html`
+    let lower = html.to_ascii_lowercase();
+    let mut pos = 0;
+    while let Some(offset) = lower[pos..].find("<meta") {
+        let start = pos + offset;
+        let Some(len) = lower[start..].find('>') else {
+            break;
+        };
+        let tag = &html[start..start + len];
+        pos = start + len;
+
+        let key = attribute(tag, "property").or_else(|| attribute(tag, "name"));
+        let Some(content) = attribute(tag, "content") else {
+            continue;
+        };
+        match key.map(|k| k.to_lowercase()).as_deref() {
+            Some("og:title") | Some("twitter:title") => { og_title.get_or_insert(content); }
+            Some("og:description") | Some("twitter:description") => { og_description.get_or_insert(content); }
+            Some("og:image") | Some("og:image:url") | Some("twitter:image") => { og_image.get_or_insert(content); }
+            Some("og:site_name") => { og_site.get_or_insert(content); }
+            Some("description") => { meta_description.get_or_insert(content); }
+            _ => {}
+        }
+    }
+
+    let title_tag = lower.find("<title").and_then(|start| {
+        let open_end = start + lower[start..].find('>')? + 1;
+        let close = open_end + lower[open_end..].find("</title")?;
+        Some(decode_entities(&html[open_end..close]))
+    });
+
+    LinkPreview {
+        url: url.to_string(),
+        title: clean_text(og_title.or(title_tag)),
+        description: clean_text(og_description.or(meta_description)),
+        image: og_image
+            .and_then(|src| base.join(src.trim()).ok())
+            .filter(|u| matches!(u.scheme(), "http" | "https"))
+            .map(|u| u.to_string()),
+        site_name: clean_text(og_site),
+    }
+}
+
+/// Value of `name="..."` (or single-quoted / unquoted) inside a tag.
+pub(crate) fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let mut search = 0;
+    while let Some(offset) = lower[search..].find(name) {
+        let start = search + offset;
+        search = start + name.len();
+        let preceded_ok = lower[..start].ends_with(|c: char| c.is_whitespace());
+        let rest = lower[search..].trim_start();
+        if !preceded_ok || !rest.starts_with('=') {
+            continue;
+        }
+        let value_start = tag.len() - rest.len() + 1;
+        let value = tag[value_start..].trim_start();
+        let parsed = match value.chars().next() {
+            Some(q @ ('"' | '\'')) => value[1..].split(q).next().unwrap_or_default(),
+            _ => value.split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or_default(),
+        };
+        return Some(decode_entities(parsed));
+    }
+    None
+}
+
+pub(crate) fn decode_entities(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn clean_text(text: Option<String>) -> Option<String> {
+    let text = text?.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    if text.chars().count() > MAX_TEXT_CHARS {
+        let truncated: String = text.chars().take(MAX_TEXT_CHARS).collect();
+        return Some(format!("{}…", truncated.trim_end()));
+    }
+    Some(text)
+}
+
+/// A fetched preview, or a remembered failure so broken links aren't retried
+/// on every render.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CachedPreview {
+    preview: Option<LinkPreview>,
+    fetched_at: DateTime<Utc>,
+}
+
+fn cache_key(url: &str) -> String {
+    let digest = Sha256::digest(url.as_bytes());
+    format!("{}{}", CACHE_KEY_PREFIX, hex::encode(&digest[..16]))
+}
+
+/// Cached result for `url`: `Some(None)` is a cached failure, `None` means
+/// nothing usable is cached.
+pub fn load_cached(url: &str) -> Option<Option<LinkPreview>> {
+    let cached: CachedPreview = crate::storage::load(&cache_key(url))?;
+    if Utc::now() - cached.fetched_at > Duration::hours(CACHE_TTL_HOURS) {
+        return None;
+    }
+    Some(cached.preview)
+}
+
+pub fn save_cached(url: &str, preview: Option<&LinkPreview>) {
+    let cached = CachedPreview {
+        preview: preview.cloned(),
+        fetched_at: Utc::now(),
+    };
+    if !crate::storage::save(&cache_key(url), &cached) {
+        tracing::warn!("Failed to cache link preview for {}", url);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attribute_with_non_ascii_values() {
+        assert_eq!(attribute("img alt=\"日\u{212A}\" src=\"/a.png\"", "alt").as_deref(), Some("日\u{212A}"));
+        assert_eq!(attribute("img alt=\"日\u{212A}\" src=\"/a.png\"", "src").as_deref(), Some("/a.png"));
+        assert_eq!(
+            attribute("a href=\"https://example.com/\u{2126}hm\"", "href").as_deref(),
+            Some("https://example.com/\u{2126}hm")
+        );
+        assert_eq!(attribute("A HREF='x' İ=1", "href").as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn metadata_after_non_ascii_text() {
+        let base = url::Url::parse("https://example.com/").unwrap();
+        let html = "<html><head><TITLE>\u{212A}elvin \u{2126}hm</TITLE>\
+                    <meta property=\"og:image\" content=\"/\u{212A}.png\"></head>";
+        let preview = parse_metadata("https://example.com/", &base, html);
+        assert_eq!(preview.title.as_deref(), Some("\u{212A}elvin \u{2126}hm"));
+        assert_eq!(preview.image.as_deref(), Some("https://example.com/%E2%84%AA.png"));
+    }
+
+    #[test]
+    fn non_public_addresses() {
+        for ip in [
+            "127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1",
+            "100.127.255.254", "0.0.0.0", "240.0.0.1", "::1", "fe80::1", "fc00::1",
+            "::ffff:127.0.0.1", "::ffff:169.254.169.254", "::ffff:100.64.0.1", "64:ff9b::a9fe:a9fe",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{ip} should be refused");
+        }
+        for ip in ["93.184.216.34", "100.128.0.1", "2606:4700::1111", "::ffff:93.184.216.34"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{ip} should be allowed");
+        }
+    }
+
+    #[tokio::test]
+    async fn resolver_drops_non_public_addresses() {
+        use reqwest::dns::Resolve;
+
+        for name in ["127.0.0.1", "10.0.0.1", "100.64.0.1", "::ffff:127.0.0.1"] {
+            let err = match PublicResolver.resolve(name.parse().unwrap()).await {
+                Ok(_) => panic!("{name} should not resolve"),
+                Err(e) => e,
+            };
+            assert!(err.downcast_ref::<NotPublic>().is_some(), "{name}: {err}");
+        }
+        let addrs: Vec<_> = PublicResolver.resolve("93.184.216.34".parse().unwrap()).await.unwrap().collect();
+        assert_eq!(addrs, vec!["93.184.216.34:0".parse().unwrap()]);
+    }
+
+    #[tokio::test]
+    async fn client_refuses_local_hosts() {
+        let client = PreviewClient::new().unwrap();
+        for url in ["http://127.0.0.1:1/", "http://[::ffff:127.0.0.1]:1/", "http://100.64.0.1/", "http://localhost/"] {
+            let err = client.fetch(url).await.unwrap_err();
+            assert!(matches!(err, PreviewError::Refused(_)), "{url}: {err}");
+            assert!(err.is_definitive());
+        }
+    }
+}
@@ -15,12 +15,23 @@ fn main() {
         )
         .init();
 
-    // Single-instance: if another instance is running, tell it to show its window and exit.
-    if single_instance::signal_existing_instance() {
+    // A link to open, e.g. from a browser or the file manager
+    let link = rorumall::deep_link::link_in_args(std::env::args());
+
+    // Single-instance: if another instance is running, hand it the link (or
+    // just ask it to show its window) and exit.
+    if single_instance::signal_existing_instance(link.as_deref()) {
         std::process::exit(0);
     }
     single_instance::start_listener();
 
+    if let Some(link) = link {
+        rorumall::deep_link::set_pending(link);
+    }
+
+    // Make rorumall:// and ofscp:// links open here
+    std::thread::spawn(rorumall::deep_link::register_handler);
+
     tracing::info!("Starting Rorumall...");
 
     // Initialise the global tokio runtime for background async work.
@@ -0,0 +1,307 @@
+//! Markdown rendering for message content.
+//!
+//! Articles get full CommonMark; chat messages and memos get a smaller set of
+//! block and inline elements so a message can't masquerade as page structure.
+//! Both paths go through ammonia before being displayed. Top-level fenced
+//! code blocks are split out so they can be highlighted and given their own
+//! controls.
+
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use std::collections::{HashMap, HashSet};
+
+pub const MIME_MARKDOWN: &str = "text/markdown";
+pub const MIME_PLAIN: &str = "text/plain";
+
+/// Tags allowed in rendered chat messages and memos. Headings, images and
+/// tables are stripped down to their text.
+const MESSAGE_TAGS: &[&str] = &[
+    "p", "br", "em", "strong", "del", "code", "pre", "a", "blockquote", "ul", "ol", "li",
+];
+
+const URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+const HEADING_TAGS: &[&str] = &["h1", "h2", "h3", "h4", "h5", "h6"];
+
+/// Average silent reading speed used for reading time estimates.
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Whether content with the given mime type should be rendered as Markdown.
+/// Content without a mime type is treated as Markdown.
+pub fn is_markdown(mime: Option<&str>) -> bool {
+    match mime {
+        Some(mime) => {
+            let essence = mime.split(';').next().unwrap_or_default().trim();
+            !essence.eq_ignore_ascii_case(MIME_PLAIN)
+        }
+        None => true,
+    }
+}
+
+const ARTICLE_OPTIONS: Options = Options::ENABLE_STRIKETHROUGH.union(Options::ENABLE_TABLES);
+const MESSAGE_OPTIONS: Options = Options::ENABLE_STRIKETHROUGH;
+
+/// A run of rendered Markdown, or a top-level code block kept as raw text.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MarkdownBlock {
+    Html(String),
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+}
+
+/// A heading in an article, with the anchor id its rendered element carries.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+    pub anchor: String,
+}
+
+/// Render an article body to sanitized HTML.
+pub fn render_markdown(text: &str) -> String {
+    clean_article(&to_html(with_heading_ids(text, Parser::new_ext(text, ARTICLE_OPTIONS))))
+}
+
+/// Article body split into sanitized HTML and code blocks.
+pub fn article_blocks(text: &str) -> Vec<MarkdownBlock> {
+    split_blocks(with_heading_ids(text, Parser::new_ext(text, ARTICLE_OPTIONS)), clean_article)
+}
+
+/// Message or memo body split into sanitized HTML and code blocks, keeping
+/// only inline formatting, code, links, block quotes and lists.
+pub fn message_blocks(text: &str) -> Vec<MarkdownBlock> {
+    split_blocks(Parser::new_ext(text, MESSAGE_OPTIONS), clean_message)
+}
+
+/// Like `message_blocks`, with custom emoji (shortcode to image URL) drawn
+/// inline. Only those URLs survive sanitizing; other images are still dropped.
+pub fn message_blocks_with_emoji(text: &str, emoji: &HashMap<String, String>) -> Vec<MarkdownBlock> {
+    if emoji.is_empty() {
+        return message_blocks(text);
+    }
+    let urls: HashSet<String> = emoji.values().cloned().collect();
+    let mut in_code_block = false;
+    let events = Parser::new_ext(text, MESSAGE_OPTIONS).flat_map(|event| match event {
+        Event::Start(Tag::CodeBlock(_)) => {
+            in_code_block = true;
+            vec![event]
+        }
+        Event::End(TagEnd::CodeBlock) => {
+            in_code_block = false;
+            vec![event]
+        }
+        Event::Text(t) if !in_code_block => emoji_events(&t, emoji),
+        // Now that img is allowed, keep Markdown images down to their alt text
+        Event::Start(Tag::Image { .. }) | Event::End(TagEnd::Image) => Vec::new(),
+        other => vec![other],
+    });
+    split_blocks(events, |html| clean_message_with_emoji(html, urls.clone()))
+}
+
+fn emoji_events<'a>(text: &str, emoji: &HashMap<String, String>) -> Vec<Event<'a>> {
+    crate::emoji::custom_segments(text, |name| emoji.get(name).cloned())
+        .into_iter()
+        .map(|segment| match segment {
+            crate::emoji::Segment::Text(t) => Event::Text(CowStr::from(t)),
+            crate::emoji::Segment::Emoji { shortcode, url } => Event::InlineHtml(CowStr::from(format!(
+                "<img class=\"custom-emoji\" src=\"{}\" alt=\":{}:\" title=\":{}:\">",
+                crate::highlight::escape(&url),
+                shortcode,
+                shortcode,
+            ))),
+        })
+        .collect()
+}
+
+fn to_html<'a>(events: impl Iterator<Item = Event<'a>>) -> String {
+    let mut html_output = String::new();
+    pulldown_cmark::html::push_html(&mut html_output, events);
+    html_output
+}
+
+/// Headings in document order, for a table of contents.
+pub fn headings(text: &str) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut used = HashSet::new();
+    let mut current: Option<(u8, String)> = None;
+    for event in Parser::new_ext(text, ARTICLE_OPTIONS) {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => current = Some((level as u8, String::new())),
+            Event::Text(t) | Event::Code(t) => {
+                if let Some((_, heading)) = current.as_mut() {
+                    heading.push_str(&t);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((level, text)) = current.take() {
+                    let anchor = unique_anchor(&text, &mut used);
+                    headings.push(Heading { level, text, anchor });
+                }
+            }
+            _ => {}
+        }
+    }
+    headings
+}
+
+/// The text of a Markdown document without any markup, on one line.
+pub fn plain_text(text: &str) -> String {
+    let mut out = String::new();
+    for event in Parser::new_ext(text, ARTICLE_OPTIONS) {
+        match event {
+            Event::Text(t) | Event::Code(t) => out.push_str(&t),
+            Event::SoftBreak | Event::HardBreak => out.push(' '),
+            Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item | TagEnd::CodeBlock) => {
+                out.push(' ')
+            }
+            _ => {}
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A plain-text preview of at most `max_chars` characters, cut at a word
+/// boundary where possible.
+pub fn preview(text: &str, max_chars: usize) -> String {
+    let plain = plain_text(text);
+    if plain.chars().count() <= max_chars {
+        return plain;
+    }
+    let cut: String = plain.chars().take(max_chars).collect();
+    let cut = match cut.rfind(' ') {
+        Some(idx) if idx > cut.len() / 2 => &cut[..idx],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation()))
+}
+
+/// Estimated reading time in whole minutes, at least one.
+pub fn reading_time_minutes(text: &str) -> usize {
+    let words = plain_text(text).split_whitespace().count();
+    words.div_ceil(WORDS_PER_MINUTE).max(1)
+}
+
+fn unique_anchor(text: &str, used: &mut HashSet<String>) -> String {
+    let slug = text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let base = if slug.is_empty() { "section".to_string() } else { format!("section-{}", slug) };
+    let mut anchor = base.clone();
+    let mut n = 1;
+    while !used.insert(anchor.clone()) {
+        anchor = format!("{}-{}", base, n);
+        n += 1;
+    }
+    anchor
+}
+
+/// Give each heading the anchor id `headings` reports for it.
+fn with_heading_ids<'a>(text: &str, parser: Parser<'a>) -> impl Iterator<Item = Event<'a>> {
+    let mut anchors = headings(text).into_iter().map(|h| h.anchor);
+    parser.map(move |event| match event {
+        Event::Start(Tag::Heading { level, id: _, classes, attrs }) => Event::Start(Tag::Heading {
+            level,
+            id: anchors.next().map(CowStr::from),
+            classes,
+            attrs,
+        }),
+        other => other,
+    })
+}
+
+fn split_blocks<'a>(
+    parser: impl Iterator<Item = Event<'a>>,
+    clean: impl Fn(&str) -> String,
+) -> Vec<MarkdownBlock> {
+    let mut blocks = Vec::new();
+    let mut pending: Vec<Event<'a>> = Vec::new();
+    let mut depth = 0usize;
+    // Set while inside a top-level code block: (language, code so far)
+    let mut code: Option<(Option<String>, String)> = None;
+
+    let flush = |pending: &mut Vec<Event<'a>>, blocks: &mut Vec<MarkdownBlock>| {
+        if !pending.is_empty() {
+            let html = clean(&to_html(pending.drain(..)));
+            if !html.trim().is_empty() {
+                blocks.push(MarkdownBlock::Html(html));
+            }
+        }
+    };
+
+    for event in parser {
+        if let Some((_, text)) = code.as_mut() {
+            match event {
+                Event::Text(t) => text.push_str(&t),
+                Event::End(TagEnd::CodeBlock) => {
+                    let (language, mut text) = code.take().unwrap_or_default();
+                    if text.ends_with('\n') {
+                        text.pop();
+                    }
+                    blocks.push(MarkdownBlock::Code { language, code: text });
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match &event {
+            Event::Start(Tag::CodeBlock(kind)) if depth == 0 => {
+                flush(&mut pending, &mut blocks);
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => crate::highlight::fence_language(info),
+                    CodeBlockKind::Indented => None,
+                };
+                code = Some((language, String::new()));
+                continue;
+            }
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        pending.push(event);
+    }
+    flush(&mut pending, &mut blocks);
+    blocks
+}
+
+fn clean_article(html: &str) -> String {
+    let mut builder = ammonia::Builder::default();
+    for tag in HEADING_TAGS {
+        builder.add_tag_attributes(tag, &["id"]);
+    }
+    builder.clean(html).to_string()
+}
+
+fn clean_message(html: &str) -> String {
+    message_builder().clean(html).to_string()
+}
+
+fn clean_message_with_emoji(html: &str, urls: HashSet<String>) -> String {
+    let mut builder = message_builder();
+    builder
+        .add_tags(["img"])
+        .add_tag_attributes("img", &["src", "alt", "title"])
+        .add_allowed_classes("img", &["custom-emoji"])
+        .attribute_filter(move |element, attribute, value| {
+            if element == "img" && attribute == "src" && !urls.contains(value) {
+                return None;
+            }
+            Some(value.into())
+        });
+    builder.clean(html).to_string()
+}
+
+fn message_builder<'a>() -> ammonia::Builder<'a> {
+    let mut builder = ammonia::Builder::empty();
+    builder
+        .add_tags(MESSAGE_TAGS)
+        .add_tag_attributes("a", &["href"])
+        .add_tag_attributes("ol", &["start"])
+        .url_schemes(URL_SCHEMES.iter().copied().collect::<HashSet<_>>())
+        .link_rel(Some("noopener noreferrer"));
+    builder
+}
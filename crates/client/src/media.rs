@@ -0,0 +1,67 @@
+//! Images and files posted in a channel, for the media tab and the lightbox.
+
+use chrono::{DateTime, Utc};
+use rorumall_shared::Attachment;
+use std::collections::HashSet;
+
+use crate::stores::{get_media_store, get_messages_store, StoredMessage};
+
+/// An attachment and the message it came with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaItem {
+    pub attachment: Attachment,
+    pub message_id: String,
+    pub user_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MediaItem {
+    pub fn is_image(&self) -> bool {
+        is_image(&self.attachment)
+    }
+}
+
+pub fn is_image(att: &Attachment) -> bool {
+    att.mime.starts_with("image/")
+}
+
+/// Every attachment in `messages`, oldest first. An attachment forwarded
+/// into several messages is listed once, at its first appearance.
+pub fn collect(messages: &[StoredMessage]) -> Vec<MediaItem> {
+    let mut sorted: Vec<&StoredMessage> = messages.iter().collect();
+    sorted.sort_by_key(|m| m.created_at);
+
+    let mut seen = HashSet::new();
+    sorted
+        .into_iter()
+        .flat_map(|m| {
+            m.attachments.iter().map(move |att| MediaItem {
+                attachment: att.clone(),
+                message_id: m.id.clone(),
+                user_id: m.user_id.clone(),
+                created_at: m.created_at,
+            })
+        })
+        .filter(|item| seen.insert(item.attachment.id.clone()))
+        .collect()
+}
+
+/// Attachments of a channel from the loaded history and, where it reaches
+/// further back, the on-disk cache as read by `MediaStore::load`. Loaded
+/// copies are current, so cached ones are only used for messages older
+/// than anything loaded; a cached message inside the loaded stretch but
+/// missing from it was deleted.
+pub fn channel_media(channel_id: &str) -> Vec<MediaItem> {
+    let loaded = get_messages_store()
+        .get_channel_messages(channel_id)
+        .map(|ch| ch.messages)
+        .unwrap_or_default();
+    let oldest = loaded.iter().map(|m| m.created_at).min();
+    let mut messages: Vec<StoredMessage> = get_media_store()
+        .get_cached(channel_id)
+        .into_iter()
+        .filter(|m| oldest.is_none_or(|oldest| m.created_at < oldest))
+        .collect();
+    messages.extend(loaded);
+    collect(&messages)
+}
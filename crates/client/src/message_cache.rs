@@ -0,0 +1,45 @@
+//! On-disk cache of recently seen channel messages.
+//!
+//! Each channel is written through [`crate::storage`] under its own key so the
+//! search index can be rebuilt on startup without touching the network.
+
+use serde::{Deserialize, Serialize};
+
+use crate::stores::{ChannelLocation, StoredMessage};
+
+const KEY_PREFIX: &str = "message_cache_";
+
+/// Only the newest messages of each channel are kept on disk.
+const MAX_MESSAGES_PER_CHANNEL: usize = 500;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CachedChannel {
+    pub location: ChannelLocation,
+    pub messages: Vec<StoredMessage>,
+}
+
+fn cache_key(host: &str, channel_id: &str) -> String {
+    format!("{}{}_{}", KEY_PREFIX, host, channel_id)
+}
+
+pub fn save_channel(location: &ChannelLocation, messages: &[StoredMessage]) {
+    let start = messages.len().saturating_sub(MAX_MESSAGES_PER_CHANNEL);
+    let cached = CachedChannel {
+        location: location.clone(),
+        messages: messages[start..].to_vec(),
+    };
+    if !crate::storage::save(&cache_key(&location.host, &location.channel_id), &cached) {
+        tracing::warn!("Failed to cache messages for channel {}", location.channel_id);
+    }
+}
+
+pub fn load_channel(host: &str, channel_id: &str) -> Option<CachedChannel> {
+    crate::storage::load(&cache_key(host, channel_id))
+}
+
+pub fn load_all() -> Vec<CachedChannel> {
+    crate::storage::keys_with_prefix(KEY_PREFIX)
+        .iter()
+        .filter_map(|key| crate::storage::load::<CachedChannel>(key))
+        .collect()
+}
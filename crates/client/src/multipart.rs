@@ -0,0 +1,77 @@
+//! Deterministic `multipart/form-data` bodies for signed uploads.
+//!
+//! Request signatures cover a hash of the exact body bytes, so the body has
+//! to be known before the request is sent. reqwest's own forms are only
+//! produced while streaming; these are built up front instead, as the part
+//! header, the file bytes and a closing delimiter around a boundary picked
+//! for each request.
+
+/// Form field the provider reads the file from.
+pub const FILE_FIELD: &str = "file";
+
+/// The delimiter between the parts of one multipart body.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Boundary(String);
+
+impl Boundary {
+    /// A fresh random boundary. File content can't be expected to avoid any
+    /// fixed string, so every request gets its own.
+    pub fn random() -> Self {
+        Self(format!("rorumall-upload-{}", uuid::Uuid::new_v4().simple()))
+    }
+
+    /// A random boundary that doesn't occur in `data`.
+    pub fn for_data(data: &[u8]) -> Self {
+        loop {
+            let boundary = Self::random();
+            if !data.windows(boundary.0.len()).any(|w| w == boundary.0.as_bytes()) {
+                return boundary;
+            }
+        }
+    }
+
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.0)
+    }
+
+    /// Everything before the file bytes.
+    pub fn head(&self, filename: &str, mime: &str) -> Vec<u8> {
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+            self.0,
+            FILE_FIELD,
+            escape_filename(filename),
+            mime,
+        )
+        .into_bytes()
+    }
+
+    /// Everything after the file bytes.
+    pub fn tail(&self) -> Vec<u8> {
+        format!("\r\n--{}--\r\n", self.0).into_bytes()
+    }
+
+    /// A complete body for a file held in memory.
+    pub fn encode(&self, filename: &str, mime: &str, data: &[u8]) -> Vec<u8> {
+        let head = self.head(filename, mime);
+        let tail = self.tail();
+        let mut body = Vec::with_capacity(head.len() + data.len() + tail.len());
+        body.extend_from_slice(&head);
+        body.extend_from_slice(data);
+        body.extend_from_slice(&tail);
+        body
+    }
+}
+
+/// Quote a filename for the `Content-Disposition` header. Line breaks
+/// would end the header early, so they are dropped.
+fn escape_filename(filename: &str) -> String {
+    filename
+        .chars()
+        .filter(|c| *c != '\r' && *c != '\n')
+        .flat_map(|c| match c {
+            '"' | '\\' => vec!['\\', c],
+            c => vec![c],
+        })
+        .collect()
+}
@@ -9,7 +9,14 @@ pub enum AppRoute {
     Group { host: String, group_id: String },
     Channel { host: String, group_id: String, channel_id: String },
     ComposeArticle { host: String, group_id: String, channel_id: String },
+    EditArticle { host: String, group_id: String, channel_id: String, message_id: String },
+    Article { host: String, group_id: String, channel_id: String, message_id: String },
+    Tag { host: String, group_id: String, tag: String },
     Profile,
+    Search,
+    Scheduled,
+    Discover,
+    UserProfile { host: String, handle: String },
 }
 
 thread_local! {
@@ -59,3 +66,10 @@ pub fn navigate_to_channel(host: String, group_id: String, channel_id: String) {
 pub fn navigate_to_group(host: String, group_id: String) {
     navigate(AppRoute::Group { host, group_id });
 }
+
+pub fn navigate_to_message(host: String, group_id: String, channel_id: String, message_id: String) {
+    crate::stores::get_messages_store()
+        .focus_message
+        .set(Some(message_id));
+    navigate_to_channel(host, group_id, channel_id);
+}
@@ -0,0 +1,450 @@
+//! Clipboard content other than images, turned into composer input: file
+//! lists copied in a file manager, and rich text converted to Markdown.
+
+use std::path::PathBuf;
+
+use crate::link_preview::{attribute, decode_entities};
+
+/// Local files in a `text/uri-list` (or GNOME's `x-special/gnome-copied-files`,
+/// which adds a leading `copy` or `cut` line).
+pub fn parse_uri_list(text: &str) -> Vec<PathBuf> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && *line != "copy" && *line != "cut")
+        .filter_map(|line| url::Url::parse(line).ok())
+        .filter(|url| url.scheme() == "file")
+        .filter_map(|url| url.to_file_path().ok())
+        .collect()
+}
+
+/// Markdown for pasted HTML. Covers what text copied from a browser or an
+/// office suite usually contains: paragraphs, headings, emphasis, links,
+/// lists, quotes, code and images. Anything else keeps only its text.
+pub fn html_to_markdown(html: &str) -> String {
+    convert(html).0
+}
+
+/// Markdown for pasted HTML that has any formatting. `None` for HTML that
+/// is just text, which pastes as well as plain text does.
+pub fn rich_text_markdown(html: &str) -> Option<String> {
+    let (markdown, formatted) = convert(html);
+    (formatted && !markdown.is_empty()).then_some(markdown)
+}
+
+/// `after` with the text a native paste inserted into `before` replaced by
+/// `replacement`. The inserted part is whatever lies between the longest
+/// common prefix and suffix of the two, so it sits where the cursor was.
+pub fn splice_paste(before: &str, after: &str, replacement: &str) -> String {
+    let prefix = before
+        .char_indices()
+        .zip(after.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((i, _), _)| i)
+        .unwrap_or_else(|| before.len().min(after.len()));
+    let max_suffix = before.len().min(after.len()) - prefix;
+    let suffix = before[prefix..]
+        .chars()
+        .rev()
+        .zip(after[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .scan(0, |len, n| {
+            *len += n;
+            Some(*len)
+        })
+        .take_while(|len| *len <= max_suffix)
+        .last()
+        .unwrap_or(0);
+    format!("{}{}{}", &after[..prefix], replacement, &after[after.len() - suffix..])
+}
+
+fn convert(html: &str) -> (String, bool) {
+    // Browsers on some platforms wrap the copied part in fragment markers
+    let html = match (html.find("<!--StartFragment-->"), html.find("<!--EndFragment-->")) {
+        (Some(start), Some(end)) if start < end => &html[start + "<!--StartFragment-->".len()..end],
+        _ => html,
+    };
+    let mut writer = Writer::default();
+    let mut pos = 0;
+    while pos < html.len() {
+        let rest = &html[pos..];
+        if rest.starts_with("<!--") {
+            pos += rest.find("-->").map(|i| i + 3).unwrap_or(rest.len());
+            continue;
+        }
+        if rest.starts_with('<') {
+            let Some(end) = rest.find('>') else {
+                writer.text(&decode_entities(rest));
+                break;
+            };
+            writer.tag(&rest[1..end]);
+            pos += end + 1;
+            continue;
+        }
+        let end = rest.find('<').unwrap_or(rest.len());
+        writer.text(&decode_entities(&rest[..end]));
+        pos += end;
+    }
+    let formatted = writer.formatted;
+    (writer.finish(), formatted)
+}
+
+/// Elements whose content is never shown.
+const HIDDEN: &[&str] = &["head", "script", "style", "title", "template"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum FrameKind {
+    Root,
+    Link,
+    Quote,
+    ListItem,
+}
+
+/// Output collected for an element that wraps its content once closed.
+struct Frame {
+    kind: FrameKind,
+    out: String,
+    href: String,
+    /// How many lists enclose a list item.
+    depth: usize,
+}
+
+impl Frame {
+    fn new(kind: FrameKind) -> Self {
+        Self { kind, out: String::new(), href: String::new(), depth: 0 }
+    }
+}
+
+struct Writer {
+    frames: Vec<Frame>,
+    /// `None` for a bulleted list, the next number for a numbered one.
+    lists: Vec<Option<u32>>,
+    hidden: usize,
+    pre: usize,
+    code: usize,
+    /// Whether any element produced Markdown syntax.
+    formatted: bool,
+}
+
+impl Default for Writer {
+    fn default() -> Self {
+        Self { frames: vec![Frame::new(FrameKind::Root)], lists: Vec::new(), hidden: 0, pre: 0, code: 0, formatted: false }
+    }
+}
+
+impl Writer {
+    fn out(&mut self) -> &mut String {
+        &mut self.frames.last_mut().expect("root frame").out
+    }
+
+    fn push(&mut self, s: &str) {
+        self.out().push_str(s);
+    }
+
+    /// End the current paragraph.
+    fn block(&mut self) {
+        let out = self.out();
+        let trimmed = out.trim_end_matches(' ').len();
+        out.truncate(trimmed);
+        if out.is_empty() || out.ends_with("\n\n") {
+            return;
+        }
+        out.push_str(if out.ends_with('\n') { "\n" } else { "\n\n" });
+    }
+
+    fn line(&mut self) {
+        let out = self.out();
+        let trimmed = out.trim_end_matches(' ').len();
+        out.truncate(trimmed);
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.hidden > 0 {
+            return;
+        }
+        if self.pre > 0 {
+            self.push(text);
+            return;
+        }
+        let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let leading = text.starts_with(char::is_whitespace);
+        let trailing = text.ends_with(char::is_whitespace) && !collapsed.is_empty();
+        let escape = self.code == 0;
+        let out = self.out();
+        if leading && !out.is_empty() && !out.ends_with([' ', '\n']) {
+            out.push(' ');
+        }
+        if escape {
+            out.push_str(&escape_markdown(&collapsed));
+        } else {
+            out.push_str(&collapsed);
+        }
+        if trailing {
+            out.push(' ');
+        }
+    }
+
+    fn tag(&mut self, tag: &str) {
+        let closing = tag.starts_with('/');
+        let body = tag.trim_start_matches('/').trim_end_matches('/');
+        let name = body
+            .split(|c: char| c.is_whitespace())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if HIDDEN.contains(&name.as_str()) {
+            if closing {
+                self.hidden = self.hidden.saturating_sub(1);
+            } else if !tag.ends_with('/') {
+                self.hidden += 1;
+            }
+            return;
+        }
+        if self.hidden > 0 {
+            return;
+        }
+
+        if matches!(
+            name.as_str(),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "strong" | "b" | "em" | "i" | "del" | "s" | "strike"
+                | "code" | "pre" | "img" | "a" | "blockquote" | "ul" | "ol" | "hr"
+        ) {
+            self.formatted = true;
+        }
+
+        match (name.as_str(), closing) {
+            ("p" | "div" | "section" | "article" | "header" | "footer" | "table", _) => self.block(),
+            ("tr", _) => self.line(),
+            ("td" | "th", false) if !self.out().is_empty() && !self.out().ends_with('\n') => self.push(" "),
+            ("br", _) => {
+                if self.pre > 0 {
+                    self.push("\n");
+                } else {
+                    self.push("  \n");
+                }
+            }
+            ("hr", _) => {
+                self.block();
+                self.push("---");
+                self.block();
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                self.block();
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                self.push(&format!("{} ", "#".repeat(level)));
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => self.block(),
+            ("strong" | "b", _) => self.push("**"),
+            ("em" | "i", _) => self.push("*"),
+            ("del" | "s" | "strike", _) => self.push("~~"),
+            ("code", _) if self.pre > 0 => {}
+            ("code", false) => {
+                self.code += 1;
+                self.push("`");
+            }
+            ("code", true) => {
+                self.code = self.code.saturating_sub(1);
+                self.push("`");
+            }
+            ("pre", false) => {
+                self.block();
+                let language = attribute(body, "class")
+                    .and_then(|c| c.split_whitespace().find_map(|c| c.strip_prefix("language-").map(str::to_string)))
+                    .unwrap_or_default();
+                self.push(&format!("```{}\n", language));
+                self.pre += 1;
+            }
+            ("pre", true) => {
+                self.pre = self.pre.saturating_sub(1);
+                if !self.out().ends_with('\n') {
+                    self.push("\n");
+                }
+                self.push("```");
+                self.block();
+            }
+            ("img", false) => {
+                let alt = attribute(body, "alt").unwrap_or_default();
+                if let Some(src) = attribute(body, "src").filter(|s| s.starts_with("http")) {
+                    self.push(&format!("![{}]({})", escape_markdown(&alt), src));
+                }
+            }
+            ("a", false) => {
+                let mut frame = Frame::new(FrameKind::Link);
+                frame.href = attribute(body, "href").unwrap_or_default();
+                self.frames.push(frame);
+            }
+            ("a", true) => {
+                if let Some(frame) = self.pop(FrameKind::Link) {
+                    let text = frame.out.trim().to_string();
+                    let link = if frame.href.is_empty() || frame.href.starts_with('#') || frame.href.starts_with("javascript:") {
+                        text
+                    } else if text.is_empty() || text == frame.href {
+                        format!("<{}>", frame.href)
+                    } else {
+                        format!("[{}]({})", text, frame.href)
+                    };
+                    self.push(&link);
+                }
+            }
+            ("blockquote", false) => {
+                self.block();
+                self.frames.push(Frame::new(FrameKind::Quote));
+            }
+            ("blockquote", true) => {
+                if let Some(frame) = self.pop(FrameKind::Quote) {
+                    let quoted = frame
+                        .out
+                        .trim()
+                        .lines()
+                        .map(|l| if l.is_empty() { ">".to_string() } else { format!("> {}", l) })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    self.push(&quoted);
+                    self.block();
+                }
+            }
+            ("ul", false) => {
+                self.line();
+                self.lists.push(None);
+            }
+            ("ol", false) => {
+                self.line();
+                let start = attribute(body, "start").and_then(|s| s.parse().ok()).unwrap_or(1);
+                self.lists.push(Some(start));
+            }
+            ("ul" | "ol", true) => {
+                self.close_open_item();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.block();
+                }
+            }
+            ("li", false) => {
+                // `</li>` is optional, so a new item ends the one before
+                self.close_open_item();
+                self.line();
+                let mut frame = Frame::new(FrameKind::ListItem);
+                frame.depth = self.lists.len();
+                self.frames.push(frame);
+            }
+            ("li", true) => {
+                if let Some(frame) = self.pop(FrameKind::ListItem) {
+                    self.list_item(frame.out.trim());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Close frames up to and including the innermost one of `kind`,
+    /// folding any left open (unclosed tags are common in pasted HTML).
+    fn pop(&mut self, kind: FrameKind) -> Option<Frame> {
+        let index = self.frames.iter().rposition(|f| f.kind == kind)?;
+        while self.frames.len() > index + 1 {
+            let inner = self.frames.pop().expect("frame above index");
+            self.out().push_str(&inner.out);
+        }
+        self.frames.pop()
+    }
+
+    /// Emit a list item of the innermost list whose end tag was left out.
+    fn close_open_item(&mut self) {
+        let open = self
+            .frames
+            .last()
+            .is_some_and(|f| f.kind == FrameKind::ListItem && f.depth == self.lists.len());
+        if open {
+            let frame = self.frames.pop().expect("checked above");
+            self.list_item(frame.out.trim());
+        }
+    }
+
+    fn list_item(&mut self, content: &str) {
+        let depth = self.lists.len().saturating_sub(1);
+        let marker = match self.lists.last_mut() {
+            Some(Some(n)) => {
+                let marker = format!("{}. ", n);
+                *n += 1;
+                marker
+            }
+            _ => "- ".to_string(),
+        };
+        let indent = "  ".repeat(depth);
+        let continuation = " ".repeat(indent.len() + marker.len());
+        let mut lines = content.lines();
+        let first = lines.next().unwrap_or_default();
+        let mut item = format!("{}{}{}", indent, marker, first);
+        for line in lines {
+            item.push('\n');
+            // Nested list items carry their own indentation
+            if line.trim_start().starts_with(['-', '*']) || line.starts_with(' ') {
+                item.push_str(&format!("{}{}", "  ".repeat(depth), line));
+            } else if !line.is_empty() {
+                item.push_str(&continuation);
+                item.push_str(line);
+            }
+        }
+        self.line();
+        self.push(&item);
+        self.push("\n");
+    }
+
+    fn finish(mut self) -> String {
+        while self.frames.len() > 1 {
+            let inner = self.frames.pop().expect("more than root");
+            self.out().push_str(&inner.out);
+        }
+        let out = std::mem::take(self.out());
+        let mut text = String::new();
+        let mut blank = 0;
+        for line in out.lines() {
+            // Two trailing spaces are a hard line break
+            let line = if line.ends_with("  ") { line } else { line.trim_end() };
+            if line.trim().is_empty() {
+                blank += 1;
+                continue;
+            }
+            if !text.is_empty() {
+                text.push_str(if blank > 0 { "\n\n" } else { "\n" });
+            }
+            blank = 0;
+            text.push_str(line);
+        }
+        text
+    }
+}
+
+/// Backslash characters Markdown would otherwise read as formatting.
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_with_non_ascii_alt() {
+        let html = r#"<p><b>İİ</b> <img alt="Straße" src="https://example.com/a.png"></p>"#;
+        assert_eq!(html_to_markdown(html), "**İİ** ![Straße](https://example.com/a.png)");
+    }
+
+    #[test]
+    fn splice_at_cursor() {
+        assert_eq!(splice_paste("hello world", "hello big world", "**big** "), "hello **big** world");
+        assert_eq!(splice_paste("", "pasted", "*pasted*"), "*pasted*");
+        assert_eq!(splice_paste("aa", "aaaa", "**aa**"), "aa**aa**");
+        assert_eq!(splice_paste("é!", "éé!", "*é*"), "é*é*!");
+    }
+}
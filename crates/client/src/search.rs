@@ -0,0 +1,282 @@
+//! Incremental full-text index over locally known messages.
+//!
+//! Message bodies, article titles and attachment names are tokenized into an
+//! inverted index keyed by lowercase word. Queries match every term, with the
+//! last term treated as a prefix so results update while typing.
+
+use chrono::{DateTime, Utc};
+use rorumall_shared::MessageType;
+use std::collections::{HashMap, HashSet};
+
+use crate::stores::{ChannelLocation, StoredMessage};
+
+const MAX_RESULTS: usize = 100;
+const SNIPPET_CHARS: usize = 140;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchFilters {
+    pub author: Option<String>,
+    pub group_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub message_type: Option<MessageType>,
+    pub has_attachment: Option<bool>,
+    pub tag: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl SearchFilters {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn matches(&self, doc: &IndexedDoc) -> bool {
+        let msg = &doc.message;
+        if let Some(author) = &self.author {
+            let author = author.trim_start_matches('@').to_lowercase();
+            if !msg.user_id.to_lowercase().starts_with(&author) {
+                return false;
+            }
+        }
+        if let Some(gid) = &self.group_id {
+            if &doc.location.group_id != gid {
+                return false;
+            }
+        }
+        if let Some(cid) = &self.channel_id {
+            if &doc.location.channel_id != cid {
+                return false;
+            }
+        }
+        if let Some(mt) = &self.message_type {
+            if &msg.message_type != mt {
+                return false;
+            }
+        }
+        if let Some(has) = self.has_attachment {
+            if msg.attachments.is_empty() == has {
+                return false;
+            }
+        }
+        if let Some(tag) = &self.tag {
+            if !msg.tags.contains(tag) {
+                return false;
+            }
+        }
+        if let Some(from) = self.from {
+            if msg.created_at < from {
+                return false;
+            }
+        }
+        if let Some(to) = self.to {
+            if msg.created_at > to {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchHit {
+    pub message: StoredMessage,
+    pub location: ChannelLocation,
+    pub snippet: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct IndexedDoc {
+    message: StoredMessage,
+    location: ChannelLocation,
+    tokens: HashSet<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchIndex {
+    docs: HashMap<String, IndexedDoc>,
+    postings: HashMap<String, HashSet<String>>,
+    channels: HashMap<String, ChannelLocation>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// Channels that have at least one indexed message.
+    pub fn channels(&self) -> Vec<ChannelLocation> {
+        let mut channels: Vec<ChannelLocation> = self.channels.values().cloned().collect();
+        channels.sort_by(|a, b| a.channel_name.cmp(&b.channel_name));
+        channels
+    }
+
+    /// Add every message indexed in `other`, replacing copies indexed here.
+    pub fn extend(&mut self, other: SearchIndex) {
+        for doc in other.docs.into_values() {
+            self.insert(&doc.location, doc.message);
+        }
+    }
+
+    /// Add a message, replacing any previously indexed copy with the same id.
+    pub fn insert(&mut self, location: &ChannelLocation, message: StoredMessage) {
+        self.remove(&message.id);
+
+        let tokens: HashSet<String> = tokenize(&indexable_text(&message)).collect();
+        for token in &tokens {
+            self.postings
+                .entry(token.clone())
+                .or_default()
+                .insert(message.id.clone());
+        }
+        self.channels
+            .insert(location.channel_id.clone(), location.clone());
+        self.docs.insert(
+            message.id.clone(),
+            IndexedDoc {
+                message,
+                location: location.clone(),
+                tokens,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, message_id: &str) {
+        let Some(doc) = self.docs.remove(message_id) else {
+            return;
+        };
+        for token in &doc.tokens {
+            if let Some(ids) = self.postings.get_mut(token) {
+                ids.remove(message_id);
+                if ids.is_empty() {
+                    self.postings.remove(token);
+                }
+            }
+        }
+    }
+
+    pub fn search(&self, query: &str, filters: &SearchFilters) -> Vec<SearchHit> {
+        let terms: Vec<String> = tokenize(query).collect();
+        if terms.is_empty() && filters.is_empty() {
+            return Vec::new();
+        }
+
+        let candidates: Vec<&IndexedDoc> = if terms.is_empty() {
+            self.docs.values().collect()
+        } else {
+            let mut ids: Option<HashSet<&String>> = None;
+            let last = terms.len() - 1;
+            for (i, term) in terms.iter().enumerate() {
+                let matching: HashSet<&String> = if i == last {
+                    self.postings
+                        .iter()
+                        .filter(|(token, _)| token.starts_with(term.as_str()))
+                        .flat_map(|(_, ids)| ids.iter())
+                        .collect()
+                } else {
+                    self.postings
+                        .get(term)
+                        .map(|ids| ids.iter().collect())
+                        .unwrap_or_default()
+                };
+                ids = Some(match ids {
+                    Some(acc) => acc.intersection(&matching).copied().collect(),
+                    None => matching,
+                });
+            }
+            ids.unwrap_or_default()
+                .into_iter()
+                .filter_map(|id| self.docs.get(id))
+                .collect()
+        };
+
+        let mut hits: Vec<SearchHit> = candidates
+            .into_iter()
+            .filter(|doc| filters.matches(doc))
+            .map(|doc| SearchHit {
+                message: doc.message.clone(),
+                location: doc.location.clone(),
+                snippet: snippet(&doc.message, &terms),
+            })
+            .collect();
+        hits.sort_by_key(|h| std::cmp::Reverse(h.message.created_at));
+        hits.truncate(MAX_RESULTS);
+        hits
+    }
+}
+
+/// Display name for an attachment: its original file name, or the last path
+/// segment of its URL for providers that don't send one.
+pub fn attachment_name(att: &rorumall_shared::Attachment) -> String {
+    if !att.filename.is_empty() {
+        return att.filename.clone();
+    }
+    att.url
+        .split('?')
+        .next()
+        .and_then(|u| u.rsplit('/').next())
+        .filter(|s| !s.is_empty())
+        .map(|s| urlencoding::decode(s).map(|d| d.into_owned()).unwrap_or_else(|_| s.to_string()))
+        .unwrap_or_else(|| att.id.clone())
+}
+
+fn indexable_text(message: &StoredMessage) -> String {
+    let mut text = String::new();
+    if let Some(title) = &message.title {
+        text.push_str(title);
+        text.push(' ');
+    }
+    text.push_str(&message.content);
+    for att in &message.attachments {
+        text.push(' ');
+        text.push_str(&attachment_name(att));
+    }
+    text
+}
+
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+}
+
+/// A short excerpt of the message centred on the first matching term.
+fn snippet(message: &StoredMessage, terms: &[String]) -> String {
+    let text = if message.content.trim().is_empty() {
+        message.title.clone().unwrap_or_default()
+    } else {
+        message.content.replace('\n', " ")
+    };
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= SNIPPET_CHARS {
+        return text;
+    }
+
+    let lower = text.to_lowercase();
+    let start = terms
+        .iter()
+        .filter_map(|t| lower.find(t.as_str()))
+        .min()
+        .map(|byte_idx| lower[..byte_idx].chars().count())
+        .unwrap_or(0)
+        .saturating_sub(SNIPPET_CHARS / 4)
+        .min(chars.len());
+    let end = (start + SNIPPET_CHARS).min(chars.len());
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    out.extend(&chars[start..end]);
+    if end < chars.len() {
+        out.push('…');
+    }
+    out
+}
@@ -7,10 +7,14 @@
 //!
 //! When a second instance starts, it tries to connect to that socket. If it
 //! succeeds, the existing instance is already running — so the second instance
-//! exits after signalling.
+//! writes the link it was launched with, if any, and exits. The running
+//! instance reads up to end of stream and follows the link; a connection
+//! that sends nothing just shows the window.
 
+use std::io::{Read, Write};
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::PathBuf;
+use std::time::Duration;
 
 use rinch::windows::show_current_window;
 
@@ -23,13 +27,25 @@ fn socket_path() -> PathBuf {
     }
 }
 
+/// Longest link a second instance may forward.
+const MAX_MESSAGE: u64 = 8 * 1024;
+
+/// How long the listener waits for a second instance to finish writing.
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Try to connect to an already-running instance. Returns `true` if one was
-/// found (and signalled to show), meaning this process should exit.
-pub fn signal_existing_instance() -> bool {
+/// found (and handed `link`, or signalled to show), meaning this process
+/// should exit.
+pub fn signal_existing_instance(link: Option<&str>) -> bool {
     let path = socket_path();
-    if UnixStream::connect(&path).is_ok() {
-        // Connection succeeded — another instance is listening.
-        // Just connecting is enough; the listener treats any connection as "show".
+    if let Ok(mut stream) = UnixStream::connect(&path) {
+        // Connection succeeded — another instance is listening. Closing the
+        // stream marks the end of the link.
+        if let Some(link) = link {
+            if let Err(e) = stream.write_all(link.as_bytes()) {
+                tracing::warn!("Could not forward link to running instance: {}", e);
+            }
+        }
         true
     } else {
         // No listener — clean up stale socket file if present.
@@ -40,7 +56,7 @@ pub fn signal_existing_instance() -> bool {
 
 /// Bind the socket and spawn a background thread that listens for incoming
 /// connections. Each connection triggers `show_current_window()` on the main
-/// thread.
+/// thread, then follows the link it carried.
 pub fn start_listener() {
     let path = socket_path();
     let listener = match UnixListener::bind(&path) {
@@ -54,9 +70,13 @@ pub fn start_listener() {
     std::thread::spawn(move || {
         for stream in listener.incoming() {
             match stream {
-                Ok(_) => {
-                    rinch::run_on_main_thread(|| {
+                Ok(stream) => {
+                    let link = read_link(stream);
+                    rinch::run_on_main_thread(move || {
                         show_current_window();
+                        if let Some(link) = link {
+                            rorumall::deep_link::handle(&link);
+                        }
                     });
                 }
                 Err(e) => {
@@ -66,3 +86,17 @@ pub fn start_listener() {
         }
     });
 }
+
+/// The link a second instance wrote, if it sent one.
+fn read_link(stream: UnixStream) -> Option<String> {
+    if let Err(e) = stream.set_read_timeout(Some(READ_TIMEOUT)) {
+        tracing::warn!("Single-instance listener error: {}", e);
+    }
+    let mut message = String::new();
+    if let Err(e) = stream.take(MAX_MESSAGE).read_to_string(&mut message) {
+        tracing::warn!("Could not read forwarded link: {}", e);
+        return None;
+    }
+    let link = message.trim();
+    (!link.is_empty()).then(|| link.to_string())
+}
@@ -20,6 +20,26 @@ pub fn exists(key: &str) -> bool {
     load_raw(key).is_some()
 }
 
+/// List stored keys starting with `prefix`. Keys come back in their on-disk
+/// (sanitized) form, which `load` accepts unchanged.
+pub fn keys_with_prefix(prefix: &str) -> Vec<String> {
+    let Some(config_dir) = get_config_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(config_dir) else {
+        return Vec::new();
+    };
+    let safe_prefix = sanitize_key(prefix);
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            name.strip_suffix(".json").map(|k| k.to_string())
+        })
+        .filter(|k| k.starts_with(&safe_prefix))
+        .collect()
+}
+
 fn get_config_dir() -> Option<std::path::PathBuf> {
     let config_dir = dirs::config_dir()?;
     let app_dir = config_dir.join("rorumall");
@@ -31,8 +51,11 @@ fn get_config_dir() -> Option<std::path::PathBuf> {
 
 fn get_file_path(key: &str) -> Option<std::path::PathBuf> {
     let config_dir = get_config_dir()?;
-    let safe_key = key.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_");
-    Some(config_dir.join(format!("{}.json", safe_key)))
+    Some(config_dir.join(format!("{}.json", sanitize_key(key))))
+}
+
+fn sanitize_key(key: &str) -> String {
+    key.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_")
 }
 
 fn save_raw(key: &str, value: &str) -> bool {
@@ -51,11 +51,13 @@ impl AuthStore {
     pub fn set_session(&self, session: AuthSession) {
         crate::auth_session::save_session(&session);
         self.session.set(Some(session));
+        crate::stores::get_settings_store().reload();
     }
 
     pub fn clear_session(&self) {
         crate::auth_session::clear_session();
         self.session.set(None);
+        crate::stores::get_settings_store().reload();
     }
 
     pub fn set_server_url(&self, url: String) {
@@ -86,6 +88,16 @@ impl AuthStore {
         let domain = self.server_url.get();
         crate::auth_session::make_client(session.as_ref(), &domain)
     }
+
+    /// Client for a group's host, signed with our keys. An empty host is the
+    /// home server.
+    pub fn make_client_for(&self, host: &str) -> crate::api_client::ApiClient {
+        if host.is_empty() {
+            return self.make_client();
+        }
+        let session = self.session.get();
+        crate::auth_session::make_client(session.as_ref(), host)
+    }
 }
 
 pub fn get_auth_store() -> AuthStore {
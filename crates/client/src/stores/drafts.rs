@@ -0,0 +1,225 @@
+use chrono::{DateTime, Utc};
+use rinch::prelude::*;
+use rorumall_shared::{Attachment, MessageReference};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+const STORAGE_KEY: &str = "composer_drafts";
+const ARTICLE_STORAGE_KEY: &str = "article_drafts";
+/// How long after the first change drafts are written, so typing doesn't
+/// rewrite the file on every keystroke.
+const PERSIST_DELAY_MS: u64 = 1000;
+
+/// An unsent message kept per channel. Only attachments that finished
+/// uploading are kept, since in-flight uploads can't be resumed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Draft {
+    pub text: String,
+    pub reply_to: Option<String>,
+    pub attachments: Vec<Attachment>,
+    /// Message being quoted.
+    #[serde(default)]
+    pub quote: Option<MessageReference>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Draft {
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty()
+            && self.reply_to.is_none()
+            && self.attachments.is_empty()
+            && self.quote.is_none()
+    }
+
+    fn same_content(&self, other: &Draft) -> bool {
+        self.text == other.text
+            && self.reply_to == other.reply_to
+            && self.attachments == other.attachments
+            && self.quote == other.quote
+    }
+}
+
+pub fn draft_key(host: &str, group_id: &str, channel_id: &str) -> String {
+    format!("{}/{}/{}", crate::ws::normalize_host(host), group_id, channel_id)
+}
+
+/// An article being written, or an edit to a published article.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ArticleDraft {
+    pub title: String,
+    pub body: String,
+    pub attachments: Vec<Attachment>,
+    /// Explicitly entered tags; `#tags` in the body are added on publish.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ArticleDraft {
+    pub fn is_empty(&self) -> bool {
+        self.title.trim().is_empty()
+            && self.body.trim().is_empty()
+            && self.attachments.is_empty()
+            && self.tags.is_empty()
+    }
+
+    fn same_content(&self, other: &ArticleDraft) -> bool {
+        self.title == other.title
+            && self.body == other.body
+            && self.attachments == other.attachments
+            && self.tags == other.tags
+    }
+}
+
+/// Key for the new-article draft in a channel, or for edits to `editing`.
+pub fn article_draft_key(host: &str, group_id: &str, channel_id: &str, editing: Option<&str>) -> String {
+    let base = draft_key(host, group_id, channel_id);
+    match editing {
+        Some(message_id) => format!("{}/edit/{}", base, message_id),
+        None => format!("{}/new", base),
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct DraftsStore {
+    pub drafts: Signal<HashMap<String, Draft>>,
+    pub article_drafts: Signal<HashMap<String, ArticleDraft>>,
+}
+
+thread_local! {
+    static DRAFTS_STORE: RefCell<Option<DraftsStore>> = const { RefCell::new(None) };
+    /// Storage keys changed since the last write.
+    static DIRTY_KEYS: RefCell<HashSet<&'static str>> = RefCell::new(HashSet::new());
+}
+
+impl DraftsStore {
+    pub fn init() -> Self {
+        let drafts = Signal::new(
+            crate::storage::load::<HashMap<String, Draft>>(STORAGE_KEY).unwrap_or_default(),
+        );
+
+        let article_drafts = Signal::new(
+            crate::storage::load::<HashMap<String, ArticleDraft>>(ARTICLE_STORAGE_KEY).unwrap_or_default(),
+        );
+
+        let store = Self { drafts, article_drafts };
+
+        DRAFTS_STORE.with(|s| {
+            *s.borrow_mut() = Some(store);
+        });
+
+        store
+    }
+
+    pub fn get_draft(&self, key: &str) -> Option<Draft> {
+        self.drafts.get().get(key).cloned()
+    }
+
+    pub fn has_draft(&self, key: &str) -> bool {
+        self.drafts.get().contains_key(key)
+    }
+
+    /// Store a draft, dropping it when empty. Unchanged drafts are not rewritten.
+    pub fn set_draft(&self, key: &str, draft: Draft) {
+        let existing = self.get_draft(key);
+        if draft.is_empty() {
+            if existing.is_some() {
+                self.clear_draft(key);
+            }
+            return;
+        }
+        if existing.is_some_and(|d| d.same_content(&draft)) {
+            return;
+        }
+        self.drafts.update(|m| { m.insert(key.to_string(), draft); });
+        self.persist();
+    }
+
+    pub fn clear_draft(&self, key: &str) {
+        self.drafts.update(|m| { m.remove(key); });
+        self.persist();
+    }
+
+    fn persist(&self) {
+        self.schedule_persist(STORAGE_KEY);
+    }
+
+    /// Mark a drafts file as stale. Writes are batched: the first change
+    /// starts a short timer and the drafts as they are by then are written
+    /// off the UI thread.
+    fn schedule_persist(&self, key: &'static str) {
+        let first = DIRTY_KEYS.with(|dirty| {
+            let mut dirty = dirty.borrow_mut();
+            let first = dirty.is_empty();
+            dirty.insert(key);
+            first
+        });
+        if first {
+            crate::runtime::spawn(
+                async { tokio::time::sleep(tokio::time::Duration::from_millis(PERSIST_DELAY_MS)).await },
+                |()| get_drafts_store().flush_persist(),
+            );
+        }
+    }
+
+    fn flush_persist(&self) {
+        let keys = DIRTY_KEYS.with(|dirty| std::mem::take(&mut *dirty.borrow_mut()));
+        let drafts = keys.contains(STORAGE_KEY).then(|| self.drafts.get().clone());
+        let article_drafts = keys.contains(ARTICLE_STORAGE_KEY).then(|| self.article_drafts.get().clone());
+        crate::runtime::spawn(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    if let Some(drafts) = drafts {
+                        crate::storage::save(STORAGE_KEY, &drafts);
+                    }
+                    if let Some(article_drafts) = article_drafts {
+                        crate::storage::save(ARTICLE_STORAGE_KEY, &article_drafts);
+                    }
+                })
+                .await
+            },
+            |result| {
+                if let Err(e) = result {
+                    tracing::warn!("Draft writer failed: {}", e);
+                }
+            },
+        );
+    }
+
+    pub fn get_article_draft(&self, key: &str) -> Option<ArticleDraft> {
+        self.article_drafts.get().get(key).cloned()
+    }
+
+    /// Store an article draft, dropping it when empty. Unchanged drafts are not rewritten.
+    pub fn set_article_draft(&self, key: &str, draft: ArticleDraft) {
+        let existing = self.get_article_draft(key);
+        if draft.is_empty() {
+            if existing.is_some() {
+                self.clear_article_draft(key);
+            }
+            return;
+        }
+        if existing.is_some_and(|d| d.same_content(&draft)) {
+            return;
+        }
+        self.article_drafts.update(|m| { m.insert(key.to_string(), draft); });
+        self.persist_articles();
+    }
+
+    pub fn clear_article_draft(&self, key: &str) {
+        self.article_drafts.update(|m| { m.remove(key); });
+        self.persist_articles();
+    }
+
+    fn persist_articles(&self) {
+        self.schedule_persist(ARTICLE_STORAGE_KEY);
+    }
+}
+
+pub fn get_drafts_store() -> DraftsStore {
+    DRAFTS_STORE.with(|s| {
+        s.borrow()
+            .expect("DraftsStore not initialized")
+    })
+}
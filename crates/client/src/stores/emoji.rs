@@ -0,0 +1,144 @@
+use rinch::prelude::*;
+use rorumall_shared::{CreateGroupEmojiRequest, GroupEmoji};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::stores::get_auth_store;
+use crate::upload::{UploadControl, UploadError, UploadSource};
+
+const RECENTS_KEY: &str = "emoji_recents";
+
+#[derive(Clone, Copy)]
+pub struct EmojiStore {
+    /// Recently used emoji, newest first: unicode for built-in emoji and
+    /// `:shortcode:` for custom ones.
+    pub recents: Signal<Vec<String>>,
+    /// Custom emoji per group id.
+    pub group_emoji: Signal<HashMap<String, Vec<GroupEmoji>>>,
+}
+
+thread_local! {
+    static EMOJI_STORE: RefCell<Option<EmojiStore>> = const { RefCell::new(None) };
+}
+
+impl EmojiStore {
+    pub fn init() -> Self {
+        let recents = Signal::new(crate::storage::load::<Vec<String>>(RECENTS_KEY).unwrap_or_default());
+        let group_emoji = Signal::new(HashMap::<String, Vec<GroupEmoji>>::new());
+
+        let store = Self { recents, group_emoji };
+
+        EMOJI_STORE.with(|s| {
+            *s.borrow_mut() = Some(store);
+        });
+
+        store
+    }
+
+    pub fn get_group_emoji(&self, group_id: &str) -> Vec<GroupEmoji> {
+        self.group_emoji.get().get(group_id).cloned().unwrap_or_default()
+    }
+
+    /// Shortcode to image URL for a group's custom emoji.
+    pub fn custom_map(&self, group_id: &str) -> HashMap<String, String> {
+        self.get_group_emoji(group_id)
+            .into_iter()
+            .map(|e| (e.shortcode, e.url))
+            .collect()
+    }
+
+    pub fn set_group_emoji(&self, group_id: &str, mut emoji: Vec<GroupEmoji>) {
+        emoji.sort_by(|a, b| a.shortcode.cmp(&b.shortcode));
+        self.group_emoji.update(|m| { m.insert(group_id.to_string(), emoji); });
+    }
+
+    /// Move an emoji to the front of the recents.
+    pub fn use_emoji(&self, emoji: &str) {
+        self.recents.update(|r| {
+            r.retain(|e| e != emoji);
+            r.insert(0, emoji.to_string());
+            r.truncate(crate::emoji::MAX_RECENTS);
+        });
+        crate::storage::save(RECENTS_KEY, &self.recents.get());
+    }
+
+    pub fn load(&self, host: &str, group_id: &str) {
+        let client = get_auth_store().make_client_for(host);
+        let gid = group_id.to_string();
+        crate::runtime::spawn(
+            async move {
+                let result = client.list_group_emoji(&gid).await;
+                (gid, result)
+            },
+            move |(gid, result)| match result {
+                Ok(resp) => get_emoji_store().set_group_emoji(&gid, resp.emoji),
+                Err(e) => tracing::warn!("Failed to load group emoji: {}", e),
+            },
+        );
+    }
+
+    /// Upload an image and register it as a group emoji. `on_done` gets the
+    /// error message on failure.
+    pub fn add(
+        &self,
+        host: &str,
+        group_id: &str,
+        shortcode: String,
+        path: std::path::PathBuf,
+        on_done: impl FnOnce(Result<(), String>) + Send + 'static,
+    ) {
+        let client = get_auth_store().make_client_for(host);
+        let gid = group_id.to_string();
+        crate::runtime::spawn(
+            async move {
+                let source = UploadSource::from_path(path).map_err(UploadError::from)?;
+                let attachment = crate::upload::upload(&client, &source, &UploadControl::default(), |_| {}).await?;
+                let req = CreateGroupEmojiRequest { shortcode, url: attachment.url };
+                client
+                    .create_group_emoji(&gid, &req)
+                    .await
+                    .map(|emoji| (gid, emoji))
+                    .map_err(UploadError::Api)
+            },
+            move |result| match result {
+                Ok((gid, emoji)) => {
+                    get_emoji_store().group_emoji.update(|m| {
+                        let list = m.entry(gid).or_default();
+                        list.retain(|e| e.shortcode != emoji.shortcode);
+                        list.push(emoji);
+                        list.sort_by(|a, b| a.shortcode.cmp(&b.shortcode));
+                    });
+                    on_done(Ok(()));
+                }
+                Err(e) => on_done(Err(e.to_string())),
+            },
+        );
+    }
+
+    pub fn remove(&self, host: &str, group_id: &str, shortcode: &str) {
+        let client = get_auth_store().make_client_for(host);
+        let gid = group_id.to_string();
+        let code = shortcode.to_string();
+        crate::runtime::spawn(
+            async move {
+                let result = client.delete_group_emoji(&gid, &code).await;
+                (gid, code, result)
+            },
+            move |(gid, code, result)| match result {
+                Ok(()) => get_emoji_store().group_emoji.update(|m| {
+                    if let Some(list) = m.get_mut(&gid) {
+                        list.retain(|e| e.shortcode != code);
+                    }
+                }),
+                Err(e) => tracing::error!("Failed to delete emoji: {}", e),
+            },
+        );
+    }
+}
+
+pub fn get_emoji_store() -> EmojiStore {
+    EMOJI_STORE.with(|s| {
+        s.borrow()
+            .expect("EmojiStore not initialized")
+    })
+}
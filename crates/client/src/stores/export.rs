@@ -0,0 +1,116 @@
+use rinch::prelude::*;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::export::{ExportError, ExportOptions, ExportSummary, ExportUpdate};
+use crate::stores::get_auth_store;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExportStatus {
+    Running(Option<ExportUpdate>),
+    Done(ExportSummary),
+    Failed(String),
+    Cancelled,
+}
+
+/// The current (or last finished) channel export.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportJob {
+    pub channel_id: String,
+    pub channel_name: String,
+    pub status: ExportStatus,
+}
+
+#[derive(Clone, Copy)]
+pub struct ExportStore {
+    pub job: Signal<Option<ExportJob>>,
+}
+
+thread_local! {
+    static EXPORT_STORE: RefCell<Option<ExportStore>> = const { RefCell::new(None) };
+    static CANCEL: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+impl ExportStore {
+    pub fn init() -> Self {
+        let job = Signal::new(None::<ExportJob>);
+
+        let store = Self { job };
+
+        EXPORT_STORE.with(|s| {
+            *s.borrow_mut() = Some(store);
+        });
+
+        store
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(self.job.get().as_ref().map(|j| &j.status), Some(ExportStatus::Running(_)))
+    }
+
+    /// Start exporting a channel. Only one export runs at a time.
+    pub fn start(&self, host: &str, options: ExportOptions) {
+        if self.is_running() {
+            return;
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        CANCEL.with(|c| *c.borrow_mut() = Some(cancel.clone()));
+        self.job.set(Some(ExportJob {
+            channel_id: options.channel_id.clone(),
+            channel_name: options.channel_name.clone(),
+            status: ExportStatus::Running(None),
+        }));
+
+        let client = get_auth_store().make_client_for(host);
+        crate::runtime::spawn(
+            crate::export::run(client, options, cancel, |update| {
+                rinch::run_on_main_thread(move || get_export_store().set_status(ExportStatus::Running(Some(update))));
+            }),
+            move |result| {
+                CANCEL.with(|c| *c.borrow_mut() = None);
+                get_export_store().set_status(match result {
+                    Ok(summary) => ExportStatus::Done(summary),
+                    Err(ExportError::Cancelled) => ExportStatus::Cancelled,
+                    Err(ExportError::Failed(e)) => {
+                        tracing::error!("Export failed: {}", e);
+                        ExportStatus::Failed(e)
+                    }
+                });
+            },
+        );
+    }
+
+    pub fn cancel(&self) {
+        CANCEL.with(|c| {
+            if let Some(flag) = c.borrow().as_ref() {
+                flag.store(true, Ordering::Relaxed);
+            }
+        });
+    }
+
+    /// Forget a finished export.
+    pub fn dismiss(&self) {
+        if !self.is_running() {
+            self.job.set(None);
+        }
+    }
+
+    fn set_status(&self, status: ExportStatus) {
+        self.job.update(|job| {
+            if let Some(job) = job {
+                // Late progress from a finished run must not revive it
+                if matches!(job.status, ExportStatus::Running(_)) {
+                    job.status = status;
+                }
+            }
+        });
+    }
+}
+
+pub fn get_export_store() -> ExportStore {
+    EXPORT_STORE.with(|s| {
+        s.borrow()
+            .expect("ExportStore not initialized")
+    })
+}
@@ -1,6 +1,10 @@
 use rinch::prelude::*;
 use rorumall_shared::{Channel, Group, UserJoinedGroup};
 use std::cell::RefCell;
+use std::collections::HashSet;
+
+use crate::discovery::{JoinOutcome, JoinRequest};
+use crate::stores::get_auth_store;
 
 #[derive(Clone, Copy)]
 pub struct GroupsStore {
@@ -8,10 +12,14 @@ pub struct GroupsStore {
     pub current_group: Signal<Option<Group>>,
     pub channels: Signal<Vec<Channel>>,
     pub current_channel_id: Signal<Option<String>>,
+    /// Requests to join that wait for a group's admins.
+    pub join_requests: Signal<Vec<JoinRequest>>,
 }
 
 thread_local! {
     static GROUPS_STORE: RefCell<Option<GroupsStore>> = const { RefCell::new(None) };
+    /// Join requests being looked up, by key.
+    static CHECKING: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
 }
 
 impl GroupsStore {
@@ -20,12 +28,14 @@ impl GroupsStore {
         let current_group = Signal::new(None::<Group>);
         let channels = Signal::new(Vec::<Channel>::new());
         let current_channel_id = Signal::new(None::<String>);
+        let join_requests = Signal::new(crate::discovery::saved_requests());
 
         let store = Self {
             joined_groups,
             current_group,
             channels,
             current_channel_id,
+            join_requests,
         };
 
         GROUPS_STORE.with(|s| {
@@ -63,6 +73,58 @@ impl GroupsStore {
         self.joined_groups
             .update(|groups| groups.retain(|g| g.group_id != group_id));
     }
+
+    /// Whether a request to join the group with this key is waiting.
+    pub fn is_requested(&self, key: &str) -> bool {
+        self.join_requests.get().iter().any(|r| r.key() == key)
+    }
+
+    pub fn add_join_request(&self, request: JoinRequest) {
+        self.join_requests.update(|requests| {
+            requests.retain(|r| r.key() != request.key());
+            requests.push(request);
+        });
+        crate::discovery::save_requests(&self.join_requests.get());
+    }
+
+    pub fn remove_join_request(&self, key: &str) {
+        self.join_requests.update(|requests| requests.retain(|r| r.key() != key));
+        crate::discovery::save_requests(&self.join_requests.get());
+    }
+
+    /// Ask each group with a waiting join request where it stands. Approved
+    /// groups join the group list; turned down ones can be asked again.
+    pub fn check_join_requests(&self) {
+        let auth = get_auth_store();
+        let user_id = auth.user_id().unwrap_or_default();
+        for request in self.join_requests.get().clone() {
+            let key = request.key();
+            if !CHECKING.with(|c| c.borrow_mut().insert(key.clone())) {
+                continue;
+            }
+            let group_client = auth.make_client_for(&request.host);
+            let home_client = auth.make_client();
+            let user_id = user_id.clone();
+            crate::runtime::spawn(
+                async move {
+                    crate::discovery::check_request(&group_client, &home_client, &user_id, &request).await
+                },
+                move |result| {
+                    CHECKING.with(|c| c.borrow_mut().remove(&key));
+                    let store = get_groups_store();
+                    match result {
+                        Ok(Some(JoinOutcome::Pending)) => {}
+                        Ok(Some(JoinOutcome::Joined(joined))) => {
+                            store.add_joined_group(joined);
+                            store.remove_join_request(&key);
+                        }
+                        Ok(None) => store.remove_join_request(&key),
+                        Err(e) => tracing::warn!("Failed to check join request for {}: {}", key, e),
+                    }
+                },
+            );
+        }
+    }
 }
 
 pub fn get_groups_store() -> GroupsStore {
@@ -0,0 +1,43 @@
+use rinch::prelude::*;
+use std::cell::RefCell;
+
+use crate::invites::InviteLink;
+
+#[derive(Clone, Copy)]
+pub struct InvitesStore {
+    /// Invite link the accept-invite dialog is showing.
+    pub pending: Signal<Option<InviteLink>>,
+}
+
+thread_local! {
+    static INVITES_STORE: RefCell<Option<InvitesStore>> = const { RefCell::new(None) };
+}
+
+impl InvitesStore {
+    pub fn init() -> Self {
+        let pending = Signal::new(None::<InviteLink>);
+
+        let store = Self { pending };
+
+        INVITES_STORE.with(|s| {
+            *s.borrow_mut() = Some(store);
+        });
+
+        store
+    }
+
+    pub fn open(&self, link: InviteLink) {
+        self.pending.set(Some(link));
+    }
+
+    pub fn close(&self) {
+        self.pending.set(None);
+    }
+}
+
+pub fn get_invites_store() -> InvitesStore {
+    INVITES_STORE.with(|s| {
+        s.borrow()
+            .expect("InvitesStore not initialized")
+    })
+}
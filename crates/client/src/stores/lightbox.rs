@@ -0,0 +1,188 @@
+use rinch::prelude::*;
+use rorumall_shared::Attachment;
+use std::cell::RefCell;
+
+use crate::navigation::{get_nav, AppRoute};
+use crate::stores::get_media_store;
+
+/// Zoom steps in percent; the first is the fitted size.
+const ZOOM_STEPS: [u32; 7] = [100, 150, 200, 300, 400, 600, 800];
+/// Distance one key press pans a zoomed image, in pixels.
+const PAN_STEP: i32 = 80;
+
+/// The image shown full size over the app, with the ones around it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightboxView {
+    pub images: Vec<Attachment>,
+    pub index: usize,
+    /// Index into `ZOOM_STEPS`.
+    zoom_step: usize,
+    pub pan_x: i32,
+    pub pan_y: i32,
+}
+
+impl LightboxView {
+    pub fn current(&self) -> &Attachment {
+        &self.images[self.index]
+    }
+
+    pub fn zoom(&self) -> u32 {
+        ZOOM_STEPS[self.zoom_step]
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.zoom_step > 0
+    }
+
+    fn reset_zoom(&mut self) {
+        self.zoom_step = 0;
+        self.pan_x = 0;
+        self.pan_y = 0;
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct LightboxStore {
+    pub view: Signal<Option<LightboxView>>,
+}
+
+thread_local! {
+    static LIGHTBOX_STORE: RefCell<Option<LightboxStore>> = const { RefCell::new(None) };
+}
+
+impl LightboxStore {
+    pub fn init() -> Self {
+        let view = Signal::new(None::<LightboxView>);
+
+        let store = Self { view };
+
+        LIGHTBOX_STORE.with(|s| {
+            *s.borrow_mut() = Some(store);
+        });
+
+        store
+    }
+
+    /// Show `images` starting at `index`.
+    pub fn open(&self, images: Vec<Attachment>, index: usize) {
+        if images.is_empty() {
+            return;
+        }
+        let index = index.min(images.len() - 1);
+        self.view.set(Some(LightboxView { images, index, zoom_step: 0, pan_x: 0, pan_y: 0 }));
+        crate::keyboard::set_modal_handler(|key, ctrl| get_lightbox_store().handle_key(key, ctrl));
+    }
+
+    /// Show `att` with every other image of the open channel to step through.
+    pub fn open_attachment(&self, att: &Attachment) {
+        let images: Vec<Attachment> = match get_nav().get().clone() {
+            AppRoute::Channel { host, channel_id, .. } => {
+                // Older images come from the disk cache, read in the background
+                get_media_store().load(&host, &channel_id);
+                crate::media::channel_media(&channel_id)
+                    .into_iter()
+                    .filter(|item| item.is_image())
+                    .map(|item| item.attachment)
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        match images.iter().position(|a| a.id == att.id) {
+            Some(index) => self.open(images, index),
+            None => self.open(vec![att.clone()], 0),
+        }
+    }
+
+    pub fn close(&self) {
+        self.view.set(None);
+        crate::keyboard::clear_modal_handler();
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.view.get().is_some()
+    }
+
+    /// Step `delta` images, wrapping around at either end.
+    pub fn step(&self, delta: isize) {
+        self.update(|v| {
+            let len = v.images.len() as isize;
+            v.index = (v.index as isize + delta).rem_euclid(len) as usize;
+            v.reset_zoom();
+        });
+    }
+
+    pub fn zoom_in(&self) {
+        self.update(|v| v.zoom_step = (v.zoom_step + 1).min(ZOOM_STEPS.len() - 1));
+    }
+
+    pub fn zoom_out(&self) {
+        self.update(|v| {
+            v.zoom_step = v.zoom_step.saturating_sub(1);
+            if v.zoom_step == 0 {
+                v.reset_zoom();
+            }
+        });
+    }
+
+    pub fn reset_zoom(&self) {
+        self.update(|v| v.reset_zoom());
+    }
+
+    pub fn pan(&self, dx: i32, dy: i32) {
+        self.update(|v| {
+            if v.is_zoomed() {
+                v.pan_x += dx;
+                v.pan_y += dy;
+            }
+        });
+    }
+
+    /// Keyboard control while the lightbox is open. Returns whether the key
+    /// was used.
+    pub fn handle_key(&self, key: &str, ctrl: bool) -> bool {
+        let Some(view) = self.view.get().clone() else {
+            return false;
+        };
+        if ctrl {
+            return false;
+        }
+        match key {
+            "Escape" => self.close(),
+            // Arrows pan a zoomed image and otherwise move between images
+            "ArrowLeft" if view.is_zoomed() => self.pan(PAN_STEP, 0),
+            "ArrowRight" if view.is_zoomed() => self.pan(-PAN_STEP, 0),
+            "ArrowUp" if view.is_zoomed() => self.pan(0, PAN_STEP),
+            "ArrowDown" if view.is_zoomed() => self.pan(0, -PAN_STEP),
+            "ArrowLeft" | "PageUp" => self.step(-1),
+            "ArrowRight" | "PageDown" | " " => self.step(1),
+            "Home" => self.update(|v| {
+                v.index = 0;
+                v.reset_zoom();
+            }),
+            "End" => self.update(|v| {
+                v.index = v.images.len() - 1;
+                v.reset_zoom();
+            }),
+            "+" | "=" => self.zoom_in(),
+            "-" => self.zoom_out(),
+            "0" => self.reset_zoom(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn update(&self, f: impl FnOnce(&mut LightboxView)) {
+        self.view.update(|view| {
+            if let Some(view) = view {
+                f(view);
+            }
+        });
+    }
+}
+
+pub fn get_lightbox_store() -> LightboxStore {
+    LIGHTBOX_STORE.with(|s| {
+        s.borrow()
+            .expect("LightboxStore not initialized")
+    })
+}
@@ -0,0 +1,167 @@
+use rinch::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+
+use crate::link_preview::{LinkPreview, PreviewClient, PreviewError};
+
+/// Attempts after the first for timeouts, network errors and 5xx, waiting
+/// `RETRY_DELAY_MS` and then twice as long each time.
+const MAX_RETRIES: u32 = 2;
+const RETRY_DELAY_MS: u64 = 1000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PreviewState {
+    Loading,
+    Ready(LinkPreview),
+    Failed,
+}
+
+#[derive(Clone, Copy)]
+pub struct LinkPreviewsStore {
+    pub previews: Signal<HashMap<String, PreviewState>>,
+    /// Preview images as `data:` URIs by image URL; `None` while loading or
+    /// after a failure.
+    pub images: Signal<HashMap<String, Option<String>>>,
+}
+
+thread_local! {
+    static LINK_PREVIEWS_STORE: RefCell<Option<LinkPreviewsStore>> = const { RefCell::new(None) };
+    /// `None` if the sandboxed client couldn't be built, which turns
+    /// previews off.
+    static PREVIEW_CLIENT: Option<PreviewClient> = PreviewClient::new()
+        .inspect_err(|e| tracing::error!("Link previews disabled: {}", e))
+        .ok();
+}
+
+impl LinkPreviewsStore {
+    pub fn init() -> Self {
+        let previews = Signal::new(HashMap::new());
+        let images = Signal::new(HashMap::new());
+
+        let store = Self { previews, images };
+
+        LINK_PREVIEWS_STORE.with(|s| {
+            *s.borrow_mut() = Some(store);
+        });
+
+        store
+    }
+
+    pub fn get(&self, url: &str) -> Option<PreviewState> {
+        self.previews.get().get(url).cloned()
+    }
+
+    /// Load a preview from the disk cache or start fetching it. Callers must
+    /// check the user's link preview policy first.
+    pub fn request(&self, url: &str) {
+        if self.previews.get().contains_key(url) {
+            return;
+        }
+
+        if let Some(cached) = crate::link_preview::load_cached(url) {
+            let state = match cached {
+                Some(preview) => PreviewState::Ready(preview),
+                None => PreviewState::Failed,
+            };
+            self.previews.update(|m| { m.insert(url.to_string(), state); });
+            return;
+        }
+
+        let Some(client) = PREVIEW_CLIENT.with(|c| c.clone()) else {
+            self.previews.update(|m| { m.insert(url.to_string(), PreviewState::Failed); });
+            return;
+        };
+        self.previews.update(|m| { m.insert(url.to_string(), PreviewState::Loading); });
+
+        let url = url.to_string();
+        crate::runtime::spawn(
+            async move {
+                let result = with_retries(|| client.fetch(&url)).await;
+                (url, result)
+            },
+            move |(url, result)| {
+                let state = match result {
+                    Ok(preview) if !preview.is_empty() => {
+                        crate::link_preview::save_cached(&url, Some(&preview));
+                        PreviewState::Ready(preview)
+                    }
+                    Ok(_) => {
+                        crate::link_preview::save_cached(&url, None);
+                        PreviewState::Failed
+                    }
+                    Err(e) => {
+                        tracing::debug!("Link preview failed for {}: {}", url, e);
+                        // Transient failures are only remembered for this
+                        // session, so the link is tried again next time
+                        if e.is_definitive() {
+                            crate::link_preview::save_cached(&url, None);
+                        }
+                        PreviewState::Failed
+                    }
+                };
+                get_link_previews_store().previews.update(|m| { m.insert(url, state); });
+            },
+        );
+    }
+
+    pub fn image(&self, url: &str) -> Option<String> {
+        self.images.get().get(url).cloned().flatten()
+    }
+
+    /// Fetch a preview image through the preview client. Like [`Self::request`],
+    /// callers must check the policy allows the image's own domain.
+    pub fn request_image(&self, url: &str) {
+        if self.images.get().contains_key(url) {
+            return;
+        }
+        self.images.update(|m| { m.insert(url.to_string(), None); });
+
+        let Some(client) = PREVIEW_CLIENT.with(|c| c.clone()) else {
+            return;
+        };
+        let url = url.to_string();
+        crate::runtime::spawn(
+            async move {
+                let result = with_retries(|| client.fetch_image(&url)).await;
+                (url, result)
+            },
+            move |(url, result)| {
+                let data = match result {
+                    Ok(data) => Some(data),
+                    Err(e) => {
+                        tracing::debug!("Link preview image failed for {}: {}", url, e);
+                        None
+                    }
+                };
+                get_link_previews_store().images.update(|m| { m.insert(url, data); });
+            },
+        );
+    }
+}
+
+/// Run `fetch`, retrying with a growing delay while it fails in a way that
+/// may go away on its own.
+async fn with_retries<T, F, Fut>(mut fetch: F) -> Result<T, PreviewError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, PreviewError>>,
+{
+    let mut retries = 0;
+    loop {
+        match fetch().await {
+            Err(e) if !e.is_definitive() && retries < MAX_RETRIES => {
+                tokio::time::sleep(tokio::time::Duration::from_millis(RETRY_DELAY_MS << retries)).await;
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+pub fn get_link_previews_store() -> LinkPreviewsStore {
+    LINK_PREVIEWS_STORE.with(|s| {
+        s.borrow()
+            .expect("LinkPreviewsStore not initialized")
+    })
+}
@@ -0,0 +1,67 @@
+use rinch::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::stores::StoredMessage;
+
+#[derive(Clone, Copy)]
+pub struct MediaStore {
+    /// Messages read from each channel's disk cache, by channel id, for
+    /// attachments older than the loaded history.
+    pub cached: Signal<HashMap<String, Vec<StoredMessage>>>,
+}
+
+thread_local! {
+    static MEDIA_STORE: RefCell<Option<MediaStore>> = const { RefCell::new(None) };
+}
+
+impl MediaStore {
+    pub fn init() -> Self {
+        let cached = Signal::new(HashMap::<String, Vec<StoredMessage>>::new());
+
+        let store = Self { cached };
+
+        MEDIA_STORE.with(|s| {
+            *s.borrow_mut() = Some(store);
+        });
+
+        store
+    }
+
+    pub fn get_cached(&self, channel_id: &str) -> Vec<StoredMessage> {
+        self.cached.get().get(channel_id).cloned().unwrap_or_default()
+    }
+
+    /// Read a channel's disk cache in the background, once per session.
+    pub fn load(&self, host: &str, channel_id: &str) {
+        if self.cached.get().contains_key(channel_id) {
+            return;
+        }
+        self.cached.update(|m| { m.insert(channel_id.to_string(), Vec::new()); });
+        let host = host.to_string();
+        let cid = channel_id.to_string();
+        crate::runtime::spawn(
+            async move {
+                let messages = tokio::task::spawn_blocking({
+                    let cid = cid.clone();
+                    move || crate::message_cache::load_channel(&host, &cid).map(|c| c.messages)
+                })
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+                (cid, messages)
+            },
+            move |(cid, messages)| {
+                get_media_store().cached.update(|m| { m.insert(cid, messages); });
+            },
+        );
+    }
+}
+
+pub fn get_media_store() -> MediaStore {
+    MEDIA_STORE.with(|s| {
+        s.borrow()
+            .expect("MediaStore not initialized")
+    })
+}
@@ -1,5 +1,5 @@
 use rinch::prelude::*;
-use rorumall_shared::{GroupMember, GroupRole};
+use rorumall_shared::{ChannelAccess, GroupMember, GroupRole};
 use std::cell::RefCell;
 use std::collections::HashMap;
 
@@ -47,6 +47,24 @@ impl MembersStore {
             .update(|m| { m.insert(group_id.to_string(), roles); });
     }
 
+    pub fn get_my_roles(&self, group_id: &str) -> Vec<String> {
+        self.my_roles.get().get(group_id).cloned().unwrap_or_default()
+    }
+
+    /// What the signed-in user may do in a channel of this group. Channels
+    /// that haven't loaded yet evaluate with default settings.
+    pub fn channel_access(&self, group_id: &str, channel_id: &str) -> ChannelAccess {
+        let settings = crate::stores::get_groups_store()
+            .channels
+            .get()
+            .iter()
+            .find(|c| c.id == channel_id)
+            .map(|c| c.settings.clone())
+            .unwrap_or_default();
+        let group_roles = self.get_group_roles(group_id).unwrap_or_default();
+        ChannelAccess::evaluate(&settings, &self.get_my_roles(group_id), &group_roles)
+    }
+
     pub fn get_my_base_role(&self, group_id: &str) -> Option<String> {
         self.my_roles.get().get(group_id).map(|roles| {
             roles
@@ -57,6 +75,15 @@ impl MembersStore {
         })
     }
 
+    pub fn add_member(&self, group_id: &str, member: GroupMember) {
+        self.members.update(|m| {
+            let members = m.entry(group_id.to_string()).or_default();
+            if !members.iter().any(|existing| existing.user_id == member.user_id) {
+                members.push(member);
+            }
+        });
+    }
+
     pub fn remove_member(&self, group_id: &str, user_id: &str) {
         self.members.update(|m| {
             if let Some(members) = m.get_mut(group_id) {
@@ -1,33 +1,115 @@
 use chrono::{DateTime, Utc};
 use rinch::prelude::*;
-use rorumall_shared::{Attachment, MessageType};
+use rorumall_shared::{AddReactionRequest, Attachment, ChannelMessage, MessageReference, MessageType, Reaction};
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
-#[derive(Clone, PartialEq, Debug)]
+use crate::stores::get_auth_store;
+
+/// Newer messages still listed below a focused one.
+const FOCUS_CONTEXT: usize = 2;
+
+/// How long changes sit in memory before the channel cache is rewritten.
+const PERSIST_DELAY_MS: u64 = 2000;
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct StoredMessage {
     pub id: String,
     pub user_id: String,
     pub title: Option<String>,
     pub content: String,
+    /// Mime type of `content`; `None` for messages cached before it was tracked.
+    #[serde(default)]
+    pub mime: Option<String>,
     pub message_type: MessageType,
     pub created_at: DateTime<Utc>,
     pub parent_id: Option<String>,
     pub parent_message_type: Option<MessageType>,
     pub attachments: Vec<Attachment>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Quoted or forwarded message.
+    #[serde(default)]
+    pub reference: Option<MessageReference>,
+    #[serde(default)]
+    pub reactions: Vec<Reaction>,
+}
+
+impl StoredMessage {
+    pub fn from_channel_message(m: ChannelMessage) -> Self {
+        Self {
+            id: m.id,
+            user_id: m.sender_user_id,
+            title: m.title,
+            content: m.body,
+            mime: m.mime,
+            message_type: m.message_type.unwrap_or(MessageType::Message),
+            created_at: DateTime::parse_from_rfc3339(&m.created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            parent_id: m.parent_id,
+            parent_message_type: m.parent_message_type,
+            attachments: m.attachments,
+            tags: m.tags,
+            reference: m.reference,
+            reactions: m.reactions,
+        }
+    }
+}
+
+/// Where a channel lives, so cached messages and search hits can navigate back to it.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct ChannelLocation {
+    pub host: String,
+    pub group_id: String,
+    pub channel_id: String,
+    pub channel_name: String,
+}
+
+/// A message with its nested replies.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ThreadNode {
+    pub message: StoredMessage,
+    pub replies: Vec<ThreadNode>,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ThreadSummary {
+    pub reply_count: usize,
+    pub last_reply: Option<StoredMessage>,
 }
 
 #[derive(Default, Clone, PartialEq)]
 pub struct ChannelMessages {
     pub messages: Vec<StoredMessage>,
     pub is_loaded: bool,
+    pub location: Option<ChannelLocation>,
+    /// Reply summaries by parent id, rebuilt by [`Self::index_threads`].
+    threads: HashMap<String, ThreadSummary>,
+    /// Parent id of every loaded message, to walk up a thread without
+    /// scanning `messages`.
+    parents: HashMap<String, Option<String>>,
 }
 
 impl ChannelMessages {
+    /// Tags used in this channel, most used first.
+    pub fn tags(&self) -> Vec<String> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for tag in self.messages.iter().flat_map(|m| m.tags.iter()) {
+            *counts.entry(tag.as_str()).or_default() += 1;
+        }
+        let mut tags: Vec<(&str, usize)> = counts.into_iter().collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        tags.into_iter().map(|(t, _)| t.to_string()).collect()
+    }
+
     pub fn add_message(&mut self, msg: StoredMessage) -> bool {
-        if self.messages.iter().any(|m| m.id == msg.id) {
+        if self.parents.contains_key(&msg.id) {
             return false;
         }
+        self.parents.insert(msg.id.clone(), msg.parent_id.clone());
         let pos = self
             .messages
             .binary_search_by(|m| m.created_at.cmp(&msg.created_at))
@@ -36,26 +118,216 @@ impl ChannelMessages {
         true
     }
 
+    /// Replace a message with an edited copy, adding it if it isn't loaded.
+    pub fn replace_message(&mut self, msg: StoredMessage) {
+        match self.messages.iter_mut().find(|m| m.id == msg.id) {
+            Some(existing) => {
+                // Edits arrive without reactions, which have their own events
+                let reactions = std::mem::take(&mut existing.reactions);
+                *existing = msg;
+                if existing.reactions.is_empty() {
+                    existing.reactions = reactions;
+                }
+            }
+            None => {
+                self.add_message(msg);
+            }
+        }
+    }
+
     pub fn set_history(&mut self, mut messages: Vec<StoredMessage>) {
         messages.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        self.parents = messages.iter().map(|m| (m.id.clone(), m.parent_id.clone())).collect();
         self.messages = messages;
         self.is_loaded = true;
     }
+
+    /// Messages to list, oldest first, limited to `tag` when set. With a
+    /// `focus` the list ends a few messages after it, so the bottom-anchored
+    /// view opens on the focused message.
+    pub fn visible(&self, focus: Option<&str>, tag: Option<&String>) -> Vec<StoredMessage> {
+        self.messages[..self.visible_end(focus)]
+            .iter()
+            .filter(|m| tag.is_none_or(|t| m.tags.contains(t)))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether focusing `focus` leaves newer messages out of the list.
+    pub fn hides_newer(&self, focus: Option<&str>) -> bool {
+        self.visible_end(focus) < self.messages.len()
+    }
+
+    fn visible_end(&self, focus: Option<&str>) -> usize {
+        focus
+            .and_then(|id| self.messages.iter().position(|m| m.id == id))
+            .map(|pos| (pos + 1 + FOCUS_CONTEXT).min(self.messages.len()))
+            .unwrap_or(self.messages.len())
+    }
+
+    pub fn find(&self, message_id: &str) -> Option<&StoredMessage> {
+        self.messages.iter().find(|m| m.id == message_id)
+    }
+
+    /// Follow parent links as far up as the loaded messages allow.
+    pub fn thread_root(&self, message_id: &str) -> String {
+        let mut current = message_id.to_string();
+        let mut seen = HashSet::new();
+        while seen.insert(current.clone()) {
+            match self.find(&current).and_then(|m| m.parent_id.clone()) {
+                Some(parent) if self.find(&parent).is_some() => current = parent,
+                _ => break,
+            }
+        }
+        current
+    }
+
+    /// Build the nested reply tree below `root_id`, oldest replies first.
+    pub fn reply_tree(&self, root_id: &str) -> Option<ThreadNode> {
+        let root = self.find(root_id)?.clone();
+        let mut children: HashMap<&str, Vec<&StoredMessage>> = HashMap::new();
+        for m in &self.messages {
+            if let Some(parent) = m.parent_id.as_deref() {
+                children.entry(parent).or_default().push(m);
+            }
+        }
+        let mut seen = HashSet::new();
+        Some(build_node(root, &children, &mut seen))
+    }
+
+    /// Every descendant of `parent_id` counted, with the most recent one.
+    pub fn thread_summary(&self, parent_id: &str) -> ThreadSummary {
+        self.threads.get(parent_id).cloned().unwrap_or_default()
+    }
+
+    /// Rebuild the reply summaries in one pass: each message counts towards
+    /// every loaded ancestor. Call after changing `messages`.
+    pub fn index_threads(&mut self) {
+        let by_id: HashMap<&str, &StoredMessage> = self.messages.iter().map(|m| (m.id.as_str(), m)).collect();
+        let mut threads: HashMap<String, ThreadSummary> = HashMap::new();
+        for reply in &self.messages {
+            let mut seen = HashSet::from([reply.id.as_str()]);
+            let mut parent = reply.parent_id.as_deref();
+            while let Some(parent_id) = parent {
+                if !seen.insert(parent_id) {
+                    break;
+                }
+                let summary = threads.entry(parent_id.to_string()).or_default();
+                summary.reply_count += 1;
+                if summary.last_reply.as_ref().is_none_or(|last| reply.created_at > last.created_at) {
+                    summary.last_reply = Some(reply.clone());
+                }
+                parent = by_id.get(parent_id).and_then(|m| m.parent_id.as_deref());
+            }
+        }
+        self.threads = threads;
+    }
+
+    /// Count a newly added message towards the summaries of its ancestors.
+    /// A message that already has loaded replies brings them into its
+    /// ancestors' threads too, so that case rebuilds everything.
+    pub fn index_added(&mut self, reply: &StoredMessage) {
+        if self.threads.contains_key(&reply.id) {
+            self.index_threads();
+            return;
+        }
+        for parent_id in self.ancestors(reply) {
+            let summary = self.threads.entry(parent_id).or_default();
+            summary.reply_count += 1;
+            if summary.last_reply.as_ref().is_none_or(|last| reply.created_at > last.created_at) {
+                summary.last_reply = Some(reply.clone());
+            }
+        }
+    }
+
+    /// Refresh the copy of an edited message kept as an ancestor's last reply.
+    pub fn index_edited(&mut self, msg: &StoredMessage) {
+        for parent_id in self.ancestors(msg) {
+            if let Some(last) = self
+                .threads
+                .get_mut(&parent_id)
+                .and_then(|summary| summary.last_reply.as_mut())
+                .filter(|last| last.id == msg.id)
+            {
+                *last = msg.clone();
+            }
+        }
+    }
+
+    /// Ids above `msg`, nearest first, as far as loaded messages allow.
+    fn ancestors(&self, msg: &StoredMessage) -> Vec<String> {
+        let mut ancestors = Vec::new();
+        let mut seen = HashSet::from([msg.id.as_str()]);
+        let mut parent = msg.parent_id.as_deref();
+        while let Some(parent_id) = parent {
+            if !seen.insert(parent_id) {
+                break;
+            }
+            ancestors.push(parent_id.to_string());
+            parent = self.parents.get(parent_id).and_then(|p| p.as_deref());
+        }
+        ancestors
+    }
+}
+
+fn build_node(
+    message: StoredMessage,
+    children: &HashMap<&str, Vec<&StoredMessage>>,
+    seen: &mut HashSet<String>,
+) -> ThreadNode {
+    seen.insert(message.id.clone());
+    let replies = children
+        .get(message.id.as_str())
+        .map(|kids| {
+            kids.iter()
+                .filter(|k| !seen.contains(&k.id))
+                .map(|k| (*k).clone())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .map(|k| build_node(k, children, seen))
+        .collect();
+    ThreadNode { message, replies }
 }
 
 #[derive(Clone, Copy)]
 pub struct MessagesStore {
     pub messages: Signal<HashMap<String, ChannelMessages>>,
+    /// Message the channel view should highlight, e.g. after picking a search result.
+    pub focus_message: Signal<Option<String>>,
+    /// Message whose thread is shown in the side panel.
+    pub open_thread: Signal<Option<String>>,
+    /// Tag the channel view is filtered to.
+    pub tag_filter: Signal<Option<String>>,
+    /// Quote handed to the channel composer by a message's "Quote" action.
+    pub compose_quote: Signal<Option<MessageReference>>,
+    /// Files dropped onto the channel view, for the channel composer to attach.
+    pub compose_files: Signal<Vec<PathBuf>>,
 }
 
 thread_local! {
     static MESSAGES_STORE: RefCell<Option<MessagesStore>> = const { RefCell::new(None) };
+    /// Channels changed since the cache was last written.
+    static DIRTY_CHANNELS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
 }
 
 impl MessagesStore {
     pub fn init() -> Self {
         let messages = Signal::new(HashMap::<String, ChannelMessages>::new());
-        let store = Self { messages };
+        let focus_message = Signal::new(None::<String>);
+        let open_thread = Signal::new(None::<String>);
+        let tag_filter = Signal::new(None::<String>);
+        let compose_quote = Signal::new(None::<MessageReference>);
+        let compose_files = Signal::new(Vec::<PathBuf>::new());
+        let store = Self {
+            messages,
+            focus_message,
+            open_thread,
+            tag_filter,
+            compose_quote,
+            compose_files,
+        };
         MESSAGES_STORE.with(|s| {
             *s.borrow_mut() = Some(store);
         });
@@ -66,20 +338,170 @@ impl MessagesStore {
         self.messages.get().get(channel_id).cloned()
     }
 
-    pub fn add_message(&self, channel_id: &str, msg: StoredMessage) {
+    pub fn set_channel_location(&self, location: ChannelLocation) {
         self.messages.update(|map| {
-            map.entry(channel_id.to_string())
+            map.entry(location.channel_id.clone())
                 .or_default()
-                .add_message(msg);
+                .location = Some(location);
         });
     }
 
+    pub fn add_message(&self, channel_id: &str, msg: StoredMessage) {
+        let mut added = false;
+        self.messages.update(|map| {
+            let ch = map.entry(channel_id.to_string()).or_default();
+            added = ch.add_message(msg.clone());
+            if added {
+                ch.index_added(&msg);
+            }
+        });
+        if added {
+            if let Some(location) = self.location(channel_id) {
+                crate::stores::get_search_store().index_message(&location, msg);
+            }
+            self.persist_channel(channel_id);
+        }
+    }
+
+    /// Apply an edit to a message, e.g. an updated article.
+    pub fn update_message(&self, channel_id: &str, msg: StoredMessage) {
+        self.messages.update(|map| {
+            let ch = map.entry(channel_id.to_string()).or_default();
+            let existed = ch.parents.contains_key(&msg.id);
+            ch.replace_message(msg.clone());
+            if existed {
+                ch.index_edited(&msg);
+            } else {
+                ch.index_added(&msg);
+            }
+        });
+        if let Some(location) = self.location(channel_id) {
+            crate::stores::get_search_store().index_message(&location, msg);
+        }
+        self.persist_channel(channel_id);
+    }
+
+    /// Add a batch of messages (e.g. a fetched thread) without replacing history.
+    pub fn merge_messages(&self, channel_id: &str, messages: Vec<StoredMessage>) {
+        let mut added = Vec::new();
+        self.messages.update(|map| {
+            let ch = map.entry(channel_id.to_string()).or_default();
+            for msg in messages {
+                if ch.add_message(msg.clone()) {
+                    added.push(msg);
+                }
+            }
+            if !added.is_empty() {
+                ch.index_threads();
+            }
+        });
+        if added.is_empty() {
+            return;
+        }
+        if let Some(location) = self.location(channel_id) {
+            crate::stores::get_search_store().index_channel(&location, &added);
+        }
+        self.persist_channel(channel_id);
+    }
+
+    /// Record a reaction on the message it references.
+    pub fn add_reaction(&self, channel_id: &str, reaction: Reaction) {
+        let mut added = false;
+        self.messages.update(|map| {
+            let Some(msg) = map
+                .get_mut(channel_id)
+                .and_then(|ch| ch.messages.iter_mut().find(|m| m.id == reaction.reference.id))
+            else {
+                return;
+            };
+            if !msg.reactions.iter().any(|r| r.id == reaction.id) {
+                msg.reactions.push(reaction);
+                added = true;
+            }
+        });
+        if added {
+            self.persist_channel(channel_id);
+        }
+    }
+
+    /// React to a message as the signed-in user.
+    pub fn react(&self, group_id: &str, channel_id: &str, message_id: &str, req: AddReactionRequest) {
+        let host = self.location(channel_id).map(|l| l.host).unwrap_or_default();
+        let client = get_auth_store().make_client_for(&host);
+        let gid = group_id.to_string();
+        let cid = channel_id.to_string();
+        let mid = message_id.to_string();
+        crate::runtime::spawn(
+            async move {
+                let result = client.add_reaction(&gid, &cid, &mid, &req).await;
+                (cid, result)
+            },
+            move |(cid, result)| match result {
+                Ok(reaction) => get_messages_store().add_reaction(&cid, reaction),
+                Err(e) => tracing::error!("Failed to add reaction: {}", e),
+            },
+        );
+    }
+
+    /// Take back one of the signed-in user's reactions.
+    pub fn unreact(&self, group_id: &str, channel_id: &str, message_id: &str, reaction_id: &str) {
+        let host = self.location(channel_id).map(|l| l.host).unwrap_or_default();
+        let client = get_auth_store().make_client_for(&host);
+        let gid = group_id.to_string();
+        let cid = channel_id.to_string();
+        let mid = message_id.to_string();
+        let rid = reaction_id.to_string();
+        crate::runtime::spawn(
+            async move {
+                let result = client.remove_reaction(&gid, &cid, &mid, &rid).await;
+                (cid, mid, rid, result)
+            },
+            move |(cid, mid, rid, result)| match result {
+                Ok(()) => get_messages_store().remove_reaction(&cid, &mid, &rid),
+                Err(e) => tracing::error!("Failed to remove reaction: {}", e),
+            },
+        );
+    }
+
+    pub fn remove_reaction(&self, channel_id: &str, message_id: &str, reaction_id: &str) {
+        self.messages.update(|map| {
+            if let Some(msg) = map
+                .get_mut(channel_id)
+                .and_then(|ch| ch.messages.iter_mut().find(|m| m.id == message_id))
+            {
+                msg.reactions.retain(|r| r.id != reaction_id);
+            }
+        });
+        self.persist_channel(channel_id);
+    }
+
+    pub fn find_message(&self, channel_id: &str, message_id: &str) -> Option<StoredMessage> {
+        self.messages
+            .get()
+            .get(channel_id)
+            .and_then(|ch| ch.find(message_id).cloned())
+    }
+
+    pub fn thread_summary(&self, channel_id: &str, parent_id: &str) -> ThreadSummary {
+        self.messages
+            .get()
+            .get(channel_id)
+            .map(|ch| ch.thread_summary(parent_id))
+            .unwrap_or_default()
+    }
+
     pub fn set_channel_history(&self, channel_id: &str, messages: Vec<StoredMessage>) {
         self.messages.update(|map| {
-            map.entry(channel_id.to_string())
-                .or_default()
-                .set_history(messages);
+            let ch = map.entry(channel_id.to_string()).or_default();
+            ch.set_history(messages);
+            ch.index_threads();
         });
+        if let Some(ch) = self.get_channel_messages(channel_id) {
+            if let Some(location) = &ch.location {
+                crate::stores::get_search_store().index_channel(location, &ch.messages);
+            }
+        }
+        self.persist_channel(channel_id);
     }
 
     pub fn is_channel_loaded(&self, channel_id: &str) -> bool {
@@ -89,6 +511,55 @@ impl MessagesStore {
             .map(|ch| ch.is_loaded)
             .unwrap_or(false)
     }
+
+    pub fn location(&self, channel_id: &str) -> Option<ChannelLocation> {
+        self.messages
+            .get()
+            .get(channel_id)
+            .and_then(|ch| ch.location.clone())
+    }
+
+    /// Mark a channel's cache as stale. Writes are batched: the first change
+    /// starts a short timer and everything changed by then is written
+    /// together, off the UI thread.
+    fn persist_channel(&self, channel_id: &str) {
+        let first = DIRTY_CHANNELS.with(|dirty| {
+            let mut dirty = dirty.borrow_mut();
+            let first = dirty.is_empty();
+            dirty.insert(channel_id.to_string());
+            first
+        });
+        if first {
+            crate::runtime::spawn(
+                async { tokio::time::sleep(tokio::time::Duration::from_millis(PERSIST_DELAY_MS)).await },
+                |()| get_messages_store().flush_persist(),
+            );
+        }
+    }
+
+    fn flush_persist(&self) {
+        let channel_ids = DIRTY_CHANNELS.with(|dirty| std::mem::take(&mut *dirty.borrow_mut()));
+        let snapshots: Vec<(ChannelLocation, Vec<StoredMessage>)> = channel_ids
+            .iter()
+            .filter_map(|id| self.get_channel_messages(id))
+            .filter_map(|ch| Some((ch.location?, ch.messages)))
+            .collect();
+        crate::runtime::spawn(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    for (location, messages) in snapshots {
+                        crate::message_cache::save_channel(&location, &messages);
+                    }
+                })
+                .await
+            },
+            |result| {
+                if let Err(e) = result {
+                    tracing::warn!("Message cache writer failed: {}", e);
+                }
+            },
+        );
+    }
 }
 
 pub fn get_messages_store() -> MessagesStore {
@@ -1,13 +1,35 @@
 pub mod auth;
+pub mod drafts;
+pub mod emoji;
+pub mod export;
 pub mod groups;
+pub mod invites;
+pub mod lightbox;
+pub mod link_previews;
+pub mod media;
 pub mod members;
 pub mod messages;
+pub mod pins;
 pub mod presence;
 pub mod profile;
+pub mod scheduled;
+pub mod search;
+pub mod settings;
 
 pub use auth::*;
+pub use drafts::*;
+pub use emoji::*;
+pub use export::*;
 pub use groups::*;
+pub use invites::*;
+pub use lightbox::*;
+pub use link_previews::*;
+pub use media::*;
 pub use members::*;
 pub use messages::*;
+pub use pins::*;
 pub use presence::*;
 pub use profile::*;
+pub use scheduled::*;
+pub use search::*;
+pub use settings::*;
@@ -0,0 +1,160 @@
+use rinch::prelude::*;
+use rorumall_shared::ChannelPin;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use crate::stores::{get_auth_store, get_members_store, get_messages_store, StoredMessage};
+
+/// Pinned messages fetched per request.
+const PINNED_BATCH: usize = 50;
+
+#[derive(Clone, Copy)]
+pub struct PinsStore {
+    /// Pins per channel id, most recently pinned first.
+    pub pins: Signal<HashMap<String, Vec<ChannelPin>>>,
+}
+
+thread_local! {
+    static PINS_STORE: RefCell<Option<PinsStore>> = const { RefCell::new(None) };
+    /// Pinned messages already fetched (or tried) this session.
+    static REQUESTED: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+impl PinsStore {
+    pub fn init() -> Self {
+        let pins = Signal::new(HashMap::<String, Vec<ChannelPin>>::new());
+
+        let store = Self { pins };
+
+        PINS_STORE.with(|s| {
+            *s.borrow_mut() = Some(store);
+        });
+
+        store
+    }
+
+    pub fn get_pins(&self, channel_id: &str) -> Vec<ChannelPin> {
+        self.pins.get().get(channel_id).cloned().unwrap_or_default()
+    }
+
+    pub fn is_pinned(&self, channel_id: &str, message_id: &str) -> bool {
+        self.pins
+            .get()
+            .get(channel_id)
+            .is_some_and(|pins| pins.iter().any(|p| p.message_id == message_id))
+    }
+
+    pub fn set_pins(&self, channel_id: &str, mut pins: Vec<ChannelPin>) {
+        pins.sort_by(|a, b| b.pinned_at.cmp(&a.pinned_at));
+        self.pins.update(|m| { m.insert(channel_id.to_string(), pins); });
+    }
+
+    pub fn add_pin(&self, channel_id: &str, pin: ChannelPin) {
+        self.pins.update(|m| {
+            let pins = m.entry(channel_id.to_string()).or_default();
+            pins.retain(|p| p.message_id != pin.message_id);
+            pins.insert(0, pin);
+        });
+    }
+
+    pub fn remove_pin(&self, channel_id: &str, message_id: &str) {
+        self.pins.update(|m| {
+            if let Some(pins) = m.get_mut(channel_id) {
+                pins.retain(|p| p.message_id != message_id);
+            }
+        });
+    }
+
+    /// Fetch a channel's pins, then any pinned messages that aren't loaded
+    /// (pins are often older than the current page of history).
+    pub fn load(&self, group_id: &str, channel_id: &str) {
+        let client = get_auth_store().make_client();
+        let gid = group_id.to_string();
+        let cid = channel_id.to_string();
+        crate::runtime::spawn(
+            async move {
+                let result = client.list_pins(&gid, &cid).await;
+                (gid, cid, result)
+            },
+            move |(gid, cid, result)| match result {
+                Ok(resp) => {
+                    let missing: Vec<String> = resp
+                        .pins
+                        .iter()
+                        .filter(|p| get_messages_store().find_message(&cid, &p.message_id).is_none())
+                        .filter(|p| REQUESTED.with(|r| r.borrow_mut().insert(p.message_id.clone())))
+                        .map(|p| p.message_id.clone())
+                        .collect();
+                    get_pins_store().set_pins(&cid, resp.pins);
+                    if !missing.is_empty() {
+                        load_pinned_messages(gid, cid, missing);
+                    }
+                }
+                Err(e) => tracing::error!("Failed to load pins: {}", e),
+            },
+        );
+    }
+
+    /// Pin or unpin a message. The WS pin event confirms the change for other
+    /// clients; ours applies the response directly.
+    pub fn toggle(&self, group_id: &str, channel_id: &str, message_id: &str) {
+        let pinned = self.is_pinned(channel_id, message_id);
+        let client = get_auth_store().make_client();
+        let gid = group_id.to_string();
+        let cid = channel_id.to_string();
+        let mid = message_id.to_string();
+        crate::runtime::spawn(
+            async move {
+                let result = if pinned {
+                    client.unpin_message(&gid, &cid, &mid).await.map(|()| None)
+                } else {
+                    client.pin_message(&gid, &cid, &mid).await.map(Some)
+                };
+                (cid, mid, result)
+            },
+            move |(cid, mid, result)| match result {
+                Ok(Some(pin)) => get_pins_store().add_pin(&cid, pin),
+                Ok(None) => get_pins_store().remove_pin(&cid, &mid),
+                Err(e) => tracing::error!("Failed to update pin: {}", e),
+            },
+        );
+    }
+}
+
+fn load_pinned_messages(group_id: String, channel_id: String, message_ids: Vec<String>) {
+    let client = get_auth_store().make_client();
+    crate::runtime::spawn(
+        async move {
+            let mut found = Vec::new();
+            for ids in message_ids.chunks(PINNED_BATCH) {
+                match client.get_messages_by_id(&group_id, &channel_id, ids).await {
+                    Ok(page) => found.extend(page.items.into_iter().filter(|m| ids.contains(&m.id))),
+                    Err(e) => tracing::warn!("Failed to load pinned messages: {}", e),
+                }
+            }
+            (channel_id, found)
+        },
+        move |(channel_id, found)| {
+            let stored: Vec<StoredMessage> = found
+                .into_iter()
+                .map(StoredMessage::from_channel_message)
+                .collect();
+            get_messages_store().merge_messages(&channel_id, stored);
+        },
+    );
+}
+
+/// Only owners and admins may pin.
+pub fn can_pin(group_id: &str) -> bool {
+    matches!(
+        get_members_store().get_my_base_role(group_id).as_deref(),
+        Some("owner") | Some("admin")
+    )
+}
+
+pub fn get_pins_store() -> PinsStore {
+    PINS_STORE.with(|s| {
+        s.borrow()
+            .expect("PinsStore not initialized")
+    })
+}
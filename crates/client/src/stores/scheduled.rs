@@ -0,0 +1,345 @@
+use chrono::{DateTime, Utc};
+use rinch::prelude::*;
+use rorumall_shared::{
+    Attachment, MessageReference, MessageType, ScheduleMessageRequest, UpdateScheduledMessageRequest,
+};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::stores::get_auth_store;
+use crate::ws::MessageOptions;
+
+const STORAGE_KEY: &str = "scheduled_messages";
+/// How often the queue is checked for due messages.
+const TICK_SECS: u64 = 15;
+/// How long a sent message waits for the server's ack before it's retried.
+const ACK_TIMEOUT_SECS: i64 = 60;
+
+/// A message waiting to be sent. Messages held by the provider carry its
+/// `remote_id` and are only mirrored here so they can be listed and edited.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledMessage {
+    pub id: String,
+    pub host: String,
+    pub group_id: String,
+    pub channel_id: String,
+    pub channel_name: String,
+    pub body: String,
+    pub send_at: DateTime<Utc>,
+    pub title: Option<String>,
+    pub message_type: Option<MessageType>,
+    pub parent_id: Option<String>,
+    pub attachments: Vec<Attachment>,
+    pub tags: Vec<String>,
+    pub reference: Option<MessageReference>,
+    #[serde(default)]
+    pub remote_id: Option<String>,
+}
+
+impl ScheduledMessage {
+    fn options(&self) -> MessageOptions {
+        MessageOptions {
+            title: self.title.clone(),
+            message_type: self.message_type.clone(),
+            attachments: self.attachments.clone(),
+            tags: self.tags.clone(),
+            reference: self.reference.clone(),
+        }
+    }
+
+    fn request(&self) -> ScheduleMessageRequest {
+        ScheduleMessageRequest {
+            body: self.body.clone(),
+            nonce: self.id.clone(),
+            send_at: self.send_at,
+            title: self.title.clone(),
+            message_type: self.message_type.clone(),
+            parent_id: self.parent_id.clone(),
+            attachments: self.attachments.clone(),
+            tags: self.tags.clone(),
+            reference: self.reference.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct ScheduledStore {
+    /// Queued messages, soonest first.
+    pub items: Signal<Vec<ScheduledMessage>>,
+    /// Local messages whose time passed while the app was closed. They wait
+    /// for the user to confirm before going out.
+    pub missed: Signal<Vec<String>>,
+    /// Whether each host advertises server-side scheduling, once checked.
+    pub server_scheduling: Signal<HashMap<String, bool>>,
+    /// Local messages handed to the socket, by id, with when they went out.
+    /// They stay queued until the server acks their nonce.
+    pub in_flight: Signal<HashMap<String, DateTime<Utc>>>,
+}
+
+thread_local! {
+    static SCHEDULED_STORE: RefCell<Option<ScheduledStore>> = const { RefCell::new(None) };
+}
+
+impl ScheduledStore {
+    pub fn init() -> Self {
+        let mut loaded = crate::storage::load::<Vec<ScheduledMessage>>(STORAGE_KEY).unwrap_or_default();
+        let now = Utc::now();
+        // Provider-held messages that are past due have already gone out
+        loaded.retain(|m| m.remote_id.is_none() || m.send_at > now);
+        let missed_ids = loaded
+            .iter()
+            .filter(|m| m.remote_id.is_none() && m.send_at <= now)
+            .map(|m| m.id.clone())
+            .collect();
+
+        let items = Signal::new(loaded);
+        let missed = Signal::new(missed_ids);
+        let server_scheduling = Signal::new(HashMap::<String, bool>::new());
+        let in_flight = Signal::new(HashMap::<String, DateTime<Utc>>::new());
+
+        let store = Self {
+            items,
+            missed,
+            server_scheduling,
+            in_flight,
+        };
+
+        SCHEDULED_STORE.with(|s| {
+            *s.borrow_mut() = Some(store);
+        });
+
+        start_ticker();
+
+        store
+    }
+
+    pub fn get(&self, id: &str) -> Option<ScheduledMessage> {
+        self.items.get().iter().find(|m| m.id == id).cloned()
+    }
+
+    pub fn is_missed(&self, id: &str) -> bool {
+        self.missed.get().iter().any(|m| m == id)
+    }
+
+    /// Queue a message. Hosts that support it hold the message themselves;
+    /// otherwise, or if handing it over fails, it's sent from this client.
+    pub fn schedule(&self, message: ScheduledMessage) {
+        let known = self.server_scheduling.get().get(&message.host).copied();
+        if known == Some(false) {
+            self.insert(message);
+            return;
+        }
+        let client = get_auth_store().make_client_for(&message.host);
+        crate::runtime::spawn(
+            async move {
+                let supported = match known {
+                    Some(supported) => supported,
+                    None => client
+                        .get_discovery()
+                        .await
+                        .map(|d| d.capabilities.scheduled_messages)
+                        .unwrap_or(false),
+                };
+                let remote = if supported {
+                    client
+                        .schedule_message(&message.group_id, &message.channel_id, &message.request())
+                        .await
+                        .map_err(|e| tracing::warn!("Server scheduling failed, queueing locally: {}", e))
+                        .ok()
+                } else {
+                    None
+                };
+                (message, supported, remote)
+            },
+            move |(mut message, supported, remote)| {
+                let store = get_scheduled_store();
+                store.server_scheduling.update(|m| { m.insert(message.host.clone(), supported); });
+                message.remote_id = remote.map(|r| r.id);
+                store.insert(message);
+            },
+        );
+    }
+
+    /// Change the text or time of a queued message.
+    pub fn update(&self, id: &str, body: String, send_at: DateTime<Utc>) {
+        let Some(message) = self.get(id) else {
+            return;
+        };
+        let Some(remote_id) = message.remote_id.clone() else {
+            self.apply_update(id, body, send_at);
+            return;
+        };
+        let client = get_auth_store().make_client_for(&message.host);
+        let id = id.to_string();
+        let req = UpdateScheduledMessageRequest {
+            body: Some(body.clone()),
+            send_at: Some(send_at),
+        };
+        crate::runtime::spawn(
+            async move {
+                let result = client
+                    .update_scheduled_message(&message.group_id, &message.channel_id, &remote_id, &req)
+                    .await;
+                (id, body, send_at, result)
+            },
+            move |(id, body, send_at, result)| match result {
+                Ok(_) => get_scheduled_store().apply_update(&id, body, send_at),
+                Err(e) => tracing::error!("Failed to update scheduled message: {}", e),
+            },
+        );
+    }
+
+    /// Drop a queued message, withdrawing it from the provider if it holds it.
+    pub fn cancel(&self, id: &str) {
+        let Some(message) = self.get(id) else {
+            return;
+        };
+        let Some(remote_id) = message.remote_id.clone() else {
+            self.remove(id);
+            return;
+        };
+        let client = get_auth_store().make_client_for(&message.host);
+        let id = id.to_string();
+        crate::runtime::spawn(
+            async move {
+                let result = client
+                    .cancel_scheduled_message(&message.group_id, &message.channel_id, &remote_id)
+                    .await;
+                (id, result)
+            },
+            move |(id, result)| match result {
+                Ok(()) => get_scheduled_store().remove(&id),
+                Err(e) => tracing::error!("Failed to cancel scheduled message: {}", e),
+            },
+        );
+    }
+
+    /// Answer the launch prompt: send missed messages now or discard them.
+    pub fn resolve_missed(&self, send: bool) {
+        let ids = self.missed.get().clone();
+        self.missed.set(Vec::new());
+        if send {
+            self.send_due();
+        } else {
+            for id in ids {
+                self.remove(&id);
+            }
+        }
+    }
+
+    /// Send a queued local message immediately.
+    pub fn send_now(&self, id: &str) {
+        self.items.update(|items| {
+            if let Some(m) = items.iter_mut().find(|m| m.id == id && m.remote_id.is_none()) {
+                m.send_at = Utc::now();
+            }
+        });
+        self.missed.update(|ids| ids.retain(|m| m != id));
+        self.send_due();
+    }
+
+    /// Send local messages whose time has come. Messages for a host that
+    /// isn't connected stay queued for the next tick, and sent ones stay
+    /// until acked, going out again if the ack doesn't arrive in time.
+    pub fn send_due(&self) {
+        let now = Utc::now();
+        self.in_flight
+            .update(|sent| sent.retain(|_, at| now - *at < chrono::Duration::seconds(ACK_TIMEOUT_SECS)));
+        let due: Vec<ScheduledMessage> = self
+            .items
+            .get()
+            .iter()
+            .filter(|m| m.send_at <= now && !self.is_missed(&m.id))
+            .filter(|m| !self.in_flight.get().contains_key(&m.id))
+            .cloned()
+            .collect();
+        for message in due {
+            if message.remote_id.is_some() {
+                // The provider posted it
+                self.remove(&message.id);
+                continue;
+            }
+            let ws_host = if message.host.is_empty() {
+                get_auth_store().domain()
+            } else {
+                message.host.clone()
+            };
+            // Frames queued while the socket reconnects can be lost
+            if !crate::ws::is_connected(&ws_host) {
+                continue;
+            }
+            let Some(handle) = crate::ws::get_handle(&ws_host) else {
+                continue;
+            };
+            let result = match &message.parent_id {
+                Some(pid) => handle.send_reply(&message.channel_id, &message.body, &message.id, pid, message.options()),
+                None => handle.send_message_with_options(&message.channel_id, &message.body, &message.id, message.options()),
+            };
+            match result {
+                Ok(()) => self.in_flight.update(|sent| { sent.insert(message.id.clone(), now); }),
+                Err(e) => tracing::warn!("Failed to send scheduled message: {}", e),
+            }
+        }
+    }
+
+    /// The server acked `nonce`; if it was a scheduled message it's done.
+    pub fn acknowledge(&self, nonce: &str) {
+        if self.in_flight.get().contains_key(nonce) {
+            self.in_flight.update(|sent| { sent.remove(nonce); });
+            self.remove(nonce);
+        }
+    }
+
+    fn insert(&self, message: ScheduledMessage) {
+        self.items.update(|items| {
+            items.retain(|m| m.id != message.id);
+            items.push(message);
+            items.sort_by_key(|m| m.send_at);
+        });
+        self.persist();
+    }
+
+    fn apply_update(&self, id: &str, body: String, send_at: DateTime<Utc>) {
+        self.items.update(|items| {
+            if let Some(m) = items.iter_mut().find(|m| m.id == id) {
+                m.body = body;
+                m.send_at = send_at;
+            }
+            items.sort_by_key(|m| m.send_at);
+        });
+        // A missed message given a new time is back on schedule
+        if send_at > Utc::now() {
+            self.missed.update(|ids| ids.retain(|m| m != id));
+        }
+        self.persist();
+    }
+
+    fn remove(&self, id: &str) {
+        self.items.update(|items| items.retain(|m| m.id != id));
+        self.missed.update(|ids| ids.retain(|m| m != id));
+        self.persist();
+    }
+
+    fn persist(&self) {
+        crate::storage::save(STORAGE_KEY, &self.items.get());
+    }
+}
+
+/// Background timer driving `send_due`.
+fn start_ticker() {
+    crate::runtime::spawn(
+        async { tokio::time::sleep(tokio::time::Duration::from_secs(TICK_SECS)).await },
+        |()| {
+            get_scheduled_store().send_due();
+            start_ticker();
+        },
+    );
+}
+
+pub fn get_scheduled_store() -> ScheduledStore {
+    SCHEDULED_STORE.with(|s| {
+        s.borrow()
+            .expect("ScheduledStore not initialized")
+    })
+}
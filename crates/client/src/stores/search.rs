@@ -0,0 +1,110 @@
+use rinch::prelude::*;
+use std::cell::RefCell;
+
+use crate::search::{SearchFilters, SearchHit, SearchIndex};
+use crate::stores::{ChannelLocation, StoredMessage};
+
+#[derive(Clone, Copy)]
+pub struct SearchStore {
+    pub index: Signal<SearchIndex>,
+    pub query: Signal<String>,
+    pub filters: Signal<SearchFilters>,
+    pub results: Signal<Vec<SearchHit>>,
+}
+
+thread_local! {
+    static SEARCH_STORE: RefCell<Option<SearchStore>> = const { RefCell::new(None) };
+}
+
+impl SearchStore {
+    pub fn init() -> Self {
+        let index = Signal::new(SearchIndex::new());
+        let query = Signal::new(String::new());
+        let filters = Signal::new(SearchFilters::default());
+        let results = Signal::new(Vec::<SearchHit>::new());
+
+        let store = Self {
+            index,
+            query,
+            filters,
+            results,
+        };
+
+        SEARCH_STORE.with(|s| {
+            *s.borrow_mut() = Some(store);
+        });
+
+        store.load_cached();
+        store
+    }
+
+    /// Index the message cache in the background. Messages indexed in the
+    /// meantime are newer than the cache, so they replace cached copies.
+    fn load_cached(&self) {
+        crate::runtime::spawn(
+            async {
+                tokio::task::spawn_blocking(|| {
+                    let mut index = SearchIndex::new();
+                    for cached in crate::message_cache::load_all() {
+                        for msg in cached.messages {
+                            index.insert(&cached.location, msg);
+                        }
+                    }
+                    index
+                })
+                .await
+            },
+            |result| match result {
+                Ok(mut cached) => {
+                    tracing::info!("Search index loaded with {} cached messages", cached.len());
+                    let store = get_search_store();
+                    store.index.update(|idx| {
+                        cached.extend(std::mem::take(idx));
+                        *idx = cached;
+                    });
+                    store.refresh();
+                }
+                Err(e) => tracing::warn!("Search index loader failed: {}", e),
+            },
+        );
+    }
+
+    pub fn index_message(&self, location: &ChannelLocation, msg: StoredMessage) {
+        self.index.update(|idx| idx.insert(location, msg));
+        self.refresh();
+    }
+
+    pub fn index_channel(&self, location: &ChannelLocation, messages: &[StoredMessage]) {
+        self.index.update(|idx| {
+            for msg in messages {
+                idx.insert(location, msg.clone());
+            }
+        });
+        self.refresh();
+    }
+
+    pub fn set_query(&self, query: String) {
+        self.query.set(query);
+        self.refresh();
+    }
+
+    pub fn set_filters(&self, filters: SearchFilters) {
+        self.filters.set(filters);
+        self.refresh();
+    }
+
+    /// Re-run the current query against the index.
+    pub fn refresh(&self) {
+        let query = self.query.get().clone();
+        let filters = self.filters.get().clone();
+        let hits = self.index.get().search(&query, &filters);
+        self.results.set(hits);
+    }
+}
+
+pub fn get_search_store() -> SearchStore {
+    SEARCH_STORE.with(|s| {
+        s.borrow()
+            .expect("SearchStore not initialized")
+    })
+}
@@ -0,0 +1,90 @@
+use rinch::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+
+use crate::imaging::ImageSettings;
+use crate::link_preview::LinkPreviewPolicy;
+
+const STORAGE_KEY_PREFIX: &str = "preferences";
+
+/// Local display preferences for the signed-in user.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    /// Render Markdown in chat messages and memos instead of showing raw text.
+    pub render_markdown: bool,
+    /// Off by default: fetching a preview reveals the user's IP to the linked site.
+    pub link_previews: LinkPreviewPolicy,
+    /// Scaling and re-encoding of pictures before upload.
+    pub images: ImageSettings,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            render_markdown: true,
+            link_previews: LinkPreviewPolicy::default(),
+            images: ImageSettings::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct SettingsStore {
+    pub preferences: Signal<Preferences>,
+}
+
+thread_local! {
+    static SETTINGS_STORE: RefCell<Option<SettingsStore>> = const { RefCell::new(None) };
+}
+
+impl SettingsStore {
+    /// Call after `AuthStore::init`: preferences are loaded for the
+    /// signed-in user.
+    pub fn init() -> Self {
+        let preferences = Signal::new(
+            storage_key()
+                .and_then(|key| crate::storage::load::<Preferences>(&key))
+                .unwrap_or_default(),
+        );
+
+        let store = Self { preferences };
+
+        SETTINGS_STORE.with(|s| {
+            *s.borrow_mut() = Some(store);
+        });
+
+        store
+    }
+
+    /// Switch to the preferences of whoever is signed in now, or the
+    /// defaults when signed out.
+    pub fn reload(&self) {
+        let preferences = storage_key()
+            .and_then(|key| crate::storage::load::<Preferences>(&key))
+            .unwrap_or_default();
+        self.preferences.set(preferences);
+    }
+
+    /// Change the preferences, saving them for the signed-in user. Changes
+    /// made while signed out only last for the session.
+    pub fn update(&self, f: impl FnOnce(&mut Preferences)) {
+        self.preferences.update(f);
+        if let Some(key) = storage_key() {
+            crate::storage::save(&key, &self.preferences.get());
+        }
+    }
+}
+
+/// Each account on the machine keeps its own preferences.
+fn storage_key() -> Option<String> {
+    let user_id = crate::stores::get_auth_store().user_id()?;
+    Some(format!("{}/{}", STORAGE_KEY_PREFIX, user_id))
+}
+
+pub fn get_settings_store() -> SettingsStore {
+    SETTINGS_STORE.with(|s| {
+        s.borrow()
+            .expect("SettingsStore not initialized")
+    })
+}
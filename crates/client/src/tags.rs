@@ -0,0 +1,94 @@
+//! Message tags.
+//!
+//! Tags are short lowercase labels such as `release` or `incident-42`. They
+//! can be typed inline as `#tag` in a message or article body, or entered
+//! explicitly; both end up in the message's `tags` list.
+
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+
+const MAX_TAG_CHARS: usize = 32;
+const MAX_TAGS: usize = 10;
+
+/// Normalize user input to a tag: strips a leading `#`, lowercases, and
+/// rejects anything empty, too long, purely numeric or containing characters
+/// other than letters, digits, `-` and `_`.
+pub fn normalize_tag(input: &str) -> Option<String> {
+    let tag = input.trim().trim_start_matches('#').to_lowercase();
+    let valid = !tag.is_empty()
+        && tag.chars().count() <= MAX_TAG_CHARS
+        && tag.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        && tag.chars().any(|c| c.is_alphabetic());
+    valid.then_some(tag)
+}
+
+/// Parse a comma or whitespace separated list of tags.
+pub fn parse_tag_list(input: &str) -> Vec<String> {
+    merge_tags(
+        input
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter_map(normalize_tag),
+        std::iter::empty(),
+    )
+}
+
+/// `#tag` mentions in Markdown text. Code spans and code blocks are skipped,
+/// and so are `#` characters inside words or URLs; Markdown headings need a
+/// space after the `#` so they never match.
+pub fn extract_hashtags(text: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut in_code_block = false;
+    for event in Parser::new(text) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Text(t) if !in_code_block => found.extend(hashtags_in(&t)),
+            _ => {}
+        }
+    }
+    merge_tags(found, std::iter::empty())
+}
+
+/// Combine tag lists, keeping the first occurrence of each and capping the total.
+pub fn merge_tags(
+    first: impl IntoIterator<Item = String>,
+    second: impl IntoIterator<Item = String>,
+) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in first.into_iter().chain(second) {
+        if tags.len() == MAX_TAGS {
+            break;
+        }
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+fn hashtags_in(text: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let at_boundary = prev.is_none_or(|p| p.is_whitespace() || "([{,;".contains(p));
+        prev = Some(c);
+        if c != '#' || !at_boundary {
+            continue;
+        }
+        let start = i + 1;
+        let mut end = start;
+        while let Some(&(j, next)) = chars.peek() {
+            if next.is_alphanumeric() || next == '-' || next == '_' {
+                end = j + next.len_utf8();
+                prev = Some(next);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        if let Some(tag) = normalize_tag(text[start..end].trim_end_matches(['-', '_'])) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
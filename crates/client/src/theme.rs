@@ -125,6 +125,124 @@ body {
     overflow-x: auto;
 }
 
+.message-markdown {
+    font-size: var(--rinch-font-size-sm);
+}
+
+.message-markdown p {
+    margin: 0;
+}
+
+.custom-emoji {
+    height: 1.4em;
+    width: auto;
+    vertical-align: middle;
+}
+
+.emoji-grid {
+    display: flex;
+    flex-wrap: wrap;
+    gap: 2px;
+    margin: 4px 0 8px 0;
+}
+
+.emoji-cell {
+    width: 32px;
+    height: 32px;
+    display: flex;
+    align-items: center;
+    justify-content: center;
+    font-size: 20px;
+    border-radius: var(--rinch-radius-sm);
+    cursor: pointer;
+}
+
+.emoji-cell:hover {
+    background: var(--rinch-color-dark-4, #373a40);
+}
+
+.reaction-chip {
+    display: flex;
+    align-items: center;
+    gap: 4px;
+    height: 24px;
+    padding: 0 6px;
+    border: 1px solid var(--rinch-color-dark-4, #373a40);
+    border-radius: 12px;
+    cursor: pointer;
+}
+
+.reaction-chip:hover {
+    background: var(--rinch-color-dark-5, #2c2e33);
+}
+
+.reaction-chip-own {
+    border-color: var(--rinch-color-indigo-7, #4c6ef5);
+    background: var(--rinch-color-indigo-9, #26326e);
+}
+
+.markdown-content ul, .markdown-content ol {
+    margin: var(--rinch-spacing-xs) 0;
+    padding-left: var(--rinch-spacing-lg);
+}
+
+.markdown-content a {
+    color: var(--rinch-color-indigo-4, #748ffc);
+}
+
+.toc-link {
+    display: block;
+    font-size: 13px;
+    line-height: 1.8;
+    color: var(--rinch-color-dark-1, #a6a7ab);
+    text-decoration: none;
+    white-space: nowrap;
+    overflow: hidden;
+    text-overflow: ellipsis;
+}
+
+.toc-link:hover {
+    color: var(--rinch-color-indigo-4, #748ffc);
+}
+
+.code-block {
+    margin: var(--rinch-spacing-xs) 0;
+    border-radius: var(--rinch-radius-md);
+    background: var(--rinch-color-dark-5, #2c2e33);
+    overflow: hidden;
+}
+
+.code-block-header {
+    display: flex;
+    align-items: center;
+    justify-content: space-between;
+    padding: 2px 8px;
+    border-bottom: 1px solid var(--rinch-color-dark-4, #373a40);
+}
+
+.code-block pre {
+    margin: 0;
+    border-radius: 0;
+}
+
+.code-block code {
+    padding: 0;
+    background: none;
+}
+
+/* Syntax highlighting — class names come from syntect scopes (see highlight.rs) */
+.hl-punctuation { color: var(--rinch-color-dark-1, #a6a7ab); }
+.hl-comment { color: var(--rinch-color-dark-2, #909296); font-style: italic; }
+.hl-string { color: var(--rinch-color-green-4, #69db7c); }
+.hl-constant { color: var(--rinch-color-orange-4, #ffa94d); }
+.hl-keyword { color: var(--rinch-color-violet-4, #9775fa); }
+.hl-storage { color: var(--rinch-color-violet-4, #9775fa); }
+.hl-entity.hl-name { color: var(--rinch-color-blue-4, #4dabf7); }
+.hl-support { color: var(--rinch-color-cyan-4, #3bc9db); }
+.hl-variable.hl-function { color: var(--rinch-color-blue-4, #4dabf7); }
+.hl-variable.hl-parameter { color: var(--rinch-color-yellow-4, #ffd43b); }
+.hl-invalid { color: var(--rinch-color-red-5, #ff6b6b); }
+
 .markdown-content blockquote {
     border-left: 3px solid var(--rinch-color-indigo-6);
     padding-left: var(--rinch-spacing-sm);
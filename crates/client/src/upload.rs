@@ -0,0 +1,342 @@
+//! File uploads streamed from disk.
+//!
+//! Files are never read into memory whole. Providers that advertise
+//! `resumable_uploads` receive the file in chunks through an upload session,
+//! which picks up where it left off after a dropped connection or a restart;
+//! other providers get a streamed multipart body. The provider's
+//! `max_upload_size` is checked before anything is sent.
+//!
+//! Uploads are signed like any other request. A streamed body is hashed in
+//! a first pass over the file so the signature can cover it; session chunks
+//! are signed one by one, and the whole file's hash is sent on completion.
+
+use futures_util::StreamExt;
+use rorumall_shared::{ApiError, Attachment, CompleteUploadSessionRequest, CreateUploadSessionRequest, Limits, UploadSession};
+use sha2::{Digest, Sha256};
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::api_client::ApiClient;
+use crate::multipart::Boundary;
+
+/// Read size for streamed bodies.
+const READ_SIZE: usize = 64 * 1024;
+/// Largest chunk sent to an upload session, whatever the provider allows.
+const MAX_CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+const MAX_RETRIES: u32 = 5;
+/// Storage key prefix for the session of a partly uploaded file.
+const SESSION_KEY_PREFIX: &str = "upload_session_";
+
+/// A file on disk about to be uploaded.
+#[derive(Clone, Debug)]
+pub struct UploadSource {
+    pub path: PathBuf,
+    pub filename: String,
+    pub mime: String,
+    pub size: u64,
+    modified: Option<u64>,
+}
+
+impl UploadSource {
+    pub fn from_path(path: PathBuf) -> std::io::Result<Self> {
+        let meta = std::fs::metadata(&path)?;
+        if !meta.is_file() {
+            return Err(std::io::Error::other("not a regular file"));
+        }
+        let filename = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "file".to_string());
+        let mime = crate::components::messages::message_input::mime_from_extension(&filename);
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+        Ok(Self { path, filename, mime, size: meta.len(), modified })
+    }
+
+    /// Identifies this exact file (path, size and modification time), so a
+    /// session is only resumed for unchanged content.
+    fn session_key(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.path.to_string_lossy().as_bytes());
+        hasher.update(self.size.to_le_bytes());
+        hasher.update(self.modified.unwrap_or_default().to_le_bytes());
+        format!("{}{}", SESSION_KEY_PREFIX, &hex::encode(hasher.finalize())[..16])
+    }
+}
+
+/// Cancels an upload from the UI thread.
+#[derive(Clone, Debug, Default)]
+pub struct UploadControl(Arc<AtomicBool>);
+
+impl UploadControl {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for UploadControl {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UploadError {
+    TooLarge { size: u64, max: u64 },
+    Cancelled,
+    Io(String),
+    Api(ApiError),
+}
+
+impl std::fmt::Display for UploadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UploadError::TooLarge { size, max } => write!(
+                f,
+                "file is {} but the server accepts at most {}",
+                format_size(*size),
+                format_size(*max),
+            ),
+            UploadError::Cancelled => write!(f, "upload cancelled"),
+            UploadError::Io(e) => write!(f, "{}", e),
+            UploadError::Api(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<std::io::Error> for UploadError {
+    fn from(e: std::io::Error) -> Self {
+        UploadError::Io(e.to_string())
+    }
+}
+
+/// Reject files over the provider's limit before sending anything.
+pub fn check_size(size: u64, limits: Option<&Limits>) -> Result<(), UploadError> {
+    match limits {
+        Some(limits) if limits.max_upload_size > 0 && size > limits.max_upload_size => {
+            Err(UploadError::TooLarge { size, max: limits.max_upload_size })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Upload `source`, reporting bytes sent to `progress` as it goes.
+pub async fn upload(
+    client: &ApiClient,
+    source: &UploadSource,
+    control: &UploadControl,
+    progress: impl FnMut(u64) + Send + 'static,
+) -> Result<Attachment, UploadError> {
+    // Without discovery assume no limit and plain uploads; the server still
+    // enforces its own limit.
+    let capabilities = client.get_discovery().await.ok().map(|d| d.capabilities);
+    check_size(source.size, capabilities.as_ref().and_then(|c| c.limits.as_ref()))?;
+
+    if capabilities.is_some_and(|c| c.resumable_uploads) {
+        upload_resumable(client, source, control, progress).await
+    } else {
+        upload_streamed(client, source, control, progress).await
+    }
+}
+
+/// One multipart request with the file streamed from disk.
+async fn upload_streamed(
+    client: &ApiClient,
+    source: &UploadSource,
+    control: &UploadControl,
+    progress: impl FnMut(u64) + Send + 'static,
+) -> Result<Attachment, UploadError> {
+    let boundary = Boundary::random();
+    let head = boundary.head(&source.filename, &source.mime);
+    let tail = boundary.tail();
+    let body_hash = hash_file(source, &head, &tail, control).await?;
+    let content_length = head.len() as u64 + source.size + tail.len() as u64;
+
+    let file = tokio::fs::File::open(&source.path).await?;
+    let reporter = Reporter::new(source.size, progress);
+    let state = (file, source.size, reporter, control.clone());
+
+    // Send exactly the bytes that were hashed, even if the file grew since
+    let file_stream = futures_util::stream::try_unfold(state, |(mut file, remaining, mut reporter, control)| async move {
+        // Failing the body aborts the request
+        if control.is_cancelled() {
+            return Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "upload cancelled"));
+        }
+        if remaining == 0 {
+            return Ok(None);
+        }
+        let mut buf = vec![0u8; remaining.min(READ_SIZE as u64) as usize];
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "file shrank during upload"));
+        }
+        buf.truncate(n);
+        reporter.advance(n as u64);
+        Ok(Some((buf, (file, remaining - n as u64, reporter, control))))
+    });
+    let stream = futures_util::stream::iter([Ok(head)])
+        .chain(file_stream)
+        .chain(futures_util::stream::iter([Ok(tail)]));
+
+    let body = reqwest::Body::wrap_stream(stream);
+    match client.upload_stream(body, &boundary, content_length, &body_hash).await {
+        Err(_) if control.is_cancelled() => Err(UploadError::Cancelled),
+        result => result.map_err(UploadError::Api),
+    }
+}
+
+/// Hex SHA-256 of `head`, the file's `size` bytes and `tail`, read in
+/// pieces from disk.
+async fn hash_file(source: &UploadSource, head: &[u8], tail: &[u8], control: &UploadControl) -> Result<String, UploadError> {
+    let mut hasher = Sha256::new();
+    hasher.update(head);
+    let mut file = tokio::fs::File::open(&source.path).await?;
+    let mut buf = vec![0u8; READ_SIZE];
+    let mut remaining = source.size;
+    while remaining > 0 {
+        if control.is_cancelled() {
+            return Err(UploadError::Cancelled);
+        }
+        let want = remaining.min(READ_SIZE as u64) as usize;
+        let n = file.read(&mut buf[..want]).await?;
+        if n == 0 {
+            return Err(UploadError::Io("file shrank during upload".to_string()));
+        }
+        hasher.update(&buf[..n]);
+        remaining -= n as u64;
+    }
+    hasher.update(tail);
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Chunks sent through an upload session, resuming an earlier session for
+/// the same file if the provider still has it.
+async fn upload_resumable(
+    client: &ApiClient,
+    source: &UploadSource,
+    control: &UploadControl,
+    mut progress: impl FnMut(u64) + Send + 'static,
+) -> Result<Attachment, UploadError> {
+    let key = source.session_key();
+    let mut session = match resume_session(client, &key, source.size).await {
+        Some(session) => session,
+        None => {
+            let req = CreateUploadSessionRequest {
+                filename: source.filename.clone(),
+                mime: source.mime.clone(),
+                size: source.size,
+            };
+            let session = client.create_upload_session(&req).await.map_err(UploadError::Api)?;
+            crate::storage::save(&key, &session.id);
+            session
+        }
+    };
+
+    let sha256 = hash_file(source, &[], &[], control).await?;
+    let mut file = tokio::fs::File::open(&source.path).await?;
+    let mut retries = 0;
+    while session.offset < source.size {
+        if control.is_cancelled() {
+            let _ = client.cancel_upload_session(&session.id).await;
+            crate::storage::remove(&key);
+            return Err(UploadError::Cancelled);
+        }
+        progress(session.offset);
+
+        let chunk_size = match session.chunk_size {
+            0 => MAX_CHUNK_SIZE,
+            n => n.min(MAX_CHUNK_SIZE),
+        };
+        let len = (source.size - session.offset).min(chunk_size);
+        let mut chunk = vec![0u8; len as usize];
+        file.seek(SeekFrom::Start(session.offset)).await?;
+        file.read_exact(&mut chunk).await?;
+
+        let offset = session.offset;
+        match client.put_upload_chunk(&session.id, offset, source.size, chunk).await {
+            Ok(next) if next.offset > offset => {
+                session = next;
+                retries = 0;
+            }
+            Ok(_) | Err(ApiError::Network(_)) if retries < MAX_RETRIES => {
+                retries += 1;
+                tracing::warn!("Upload chunk at {} did not go through, retry {}", offset, retries);
+                tokio::time::sleep(tokio::time::Duration::from_secs(1 << retries)).await;
+                // The chunk may have landed before the connection dropped
+                if let Ok(current) = client.get_upload_session(&session.id).await {
+                    session = current;
+                }
+            }
+            Ok(_) => return Err(UploadError::Io("the server stopped accepting the upload".to_string())),
+            // The session is kept so a later attempt can resume it
+            Err(e) => return Err(UploadError::Api(e)),
+        }
+    }
+    progress(source.size);
+
+    let attachment = client
+        .complete_upload_session(&session.id, &CompleteUploadSessionRequest { sha256 })
+        .await
+        .map_err(UploadError::Api)?;
+    crate::storage::remove(&key);
+    Ok(attachment)
+}
+
+async fn resume_session(client: &ApiClient, key: &str, size: u64) -> Option<UploadSession> {
+    let id = crate::storage::load::<String>(key)?;
+    match client.get_upload_session(&id).await {
+        Ok(session) if session.size == size => {
+            tracing::info!("Resuming upload session {} at {} of {} bytes", session.id, session.offset, size);
+            Some(session)
+        }
+        _ => {
+            crate::storage::remove(key);
+            None
+        }
+    }
+}
+
+/// Calls `progress` about once per percent rather than for every read.
+struct Reporter<F> {
+    sent: u64,
+    last: u64,
+    step: u64,
+    size: u64,
+    progress: F,
+}
+
+impl<F: FnMut(u64)> Reporter<F> {
+    fn new(size: u64, progress: F) -> Self {
+        Self { sent: 0, last: 0, step: (size / 100).max(READ_SIZE as u64), size, progress }
+    }
+
+    fn advance(&mut self, n: u64) {
+        self.sent += n;
+        if self.sent - self.last >= self.step || self.sent >= self.size {
+            self.last = self.sent;
+            (self.progress)(self.sent);
+        }
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else if bytes < 1024 * 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+    }
+}
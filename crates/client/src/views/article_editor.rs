@@ -1,24 +1,123 @@
 use rinch::prelude::*;
 use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use rorumall_shared::Attachment;
 use crate::navigation::{get_nav, navigate, AppRoute};
-use crate::stores::get_auth_store;
+use crate::stores::{article_draft_key, get_auth_store, get_drafts_store, get_messages_store, ArticleDraft, StoredMessage};
 
 #[component]
 pub fn article_editor_view() -> NodeHandle {
     let nav = get_nav();
-    let (host, group_id, channel_id) = match nav.get().clone() {
-        AppRoute::ComposeArticle { host, group_id, channel_id } => (host, group_id, channel_id),
+    let (host, group_id, channel_id, editing) = match nav.get().clone() {
+        AppRoute::ComposeArticle { host, group_id, channel_id } => (host, group_id, channel_id, None),
+        AppRoute::EditArticle { host, group_id, channel_id, message_id } => {
+            (host, group_id, channel_id, Some(message_id))
+        }
         _ => return rsx! { div { "Invalid route" } },
     };
 
-    let title = Signal::new(String::new());
-    let body = Signal::new(String::new());
+    // Restore a draft, or start from the published article when editing
+    let key = article_draft_key(&host, &group_id, &channel_id, editing.as_deref());
+    let original = editing
+        .as_deref()
+        .and_then(|mid| get_messages_store().find_message(&channel_id, mid));
+    let draft = get_drafts_store().get_article_draft(&key).or_else(|| {
+        original.as_ref().map(|msg| ArticleDraft {
+            title: msg.title.clone().unwrap_or_default(),
+            body: msg.content.clone(),
+            attachments: msg.attachments.clone(),
+            tags: msg.tags.clone(),
+            updated_at: msg.created_at,
+        })
+    });
+    let restored_at = get_drafts_store().get_article_draft(&key).map(|d| d.updated_at);
+
+    let title = Signal::new(draft.as_ref().map(|d| d.title.clone()).unwrap_or_default());
+    let body = Signal::new(draft.as_ref().map(|d| d.body.clone()).unwrap_or_default());
+    let attachments = Signal::new(draft.as_ref().map(|d| d.attachments.clone()).unwrap_or_default());
+    let tag_input = Signal::new(draft.map(|d| d.tags.join(", ")).unwrap_or_default());
+    let uploading = Signal::new(0usize);
     let error_msg = Signal::new(None::<String>);
     let loading = Signal::new(false);
 
+    if editing.is_some() && original.is_none() {
+        error_msg.set(Some("The article to edit isn't loaded in this channel".to_string()));
+    }
+
+    // Autosave the draft on every change. An edit that matches the published
+    // article isn't a draft.
+    let autosave_key = key.clone();
+    let published = original.clone();
+    Effect::new(move || {
+        let draft = ArticleDraft {
+            title: title.get().clone(),
+            body: body.get().clone(),
+            attachments: attachments.get().clone(),
+            tags: crate::tags::parse_tag_list(&tag_input.get()),
+            updated_at: chrono::Utc::now(),
+        };
+        let unchanged = published.as_ref().is_some_and(|m| {
+            m.title.clone().unwrap_or_default() == draft.title
+                && m.content == draft.body
+                && m.attachments == draft.attachments
+                && m.tags == draft.tags
+        });
+        if unchanged {
+            get_drafts_store().clear_article_draft(&autosave_key);
+        } else {
+            get_drafts_store().set_article_draft(&autosave_key, draft);
+        }
+    });
+
+    let on_attach = move || {
+        let Some(path) = rinch::dialogs::open_file()
+            .add_filter("All files", &["*"])
+            .pick_file()
+        else {
+            return;
+        };
+        let source = match crate::upload::UploadSource::from_path(path) {
+            Ok(source) => source,
+            Err(e) => {
+                error_msg.set(Some(format!("Couldn't read file: {}", e)));
+                return;
+            }
+        };
+
+        uploading.update(|n| *n += 1);
+        let client = get_auth_store().make_client();
+        crate::runtime::spawn(
+            async move {
+                let control = crate::upload::UploadControl::default();
+                let att = crate::upload::upload(&client, &source, &control, |_| {}).await?;
+                Ok::<_, crate::upload::UploadError>((att, source.filename))
+            },
+            move |result| {
+                uploading.update(|n| *n = n.saturating_sub(1));
+                match result {
+                    Ok((att, filename)) => {
+                        body.update(|b| {
+                            if !b.is_empty() && !b.ends_with('\n') {
+                                b.push('\n');
+                            }
+                            b.push_str(&markdown_reference(&att, &filename));
+                            b.push('\n');
+                        });
+                        attachments.update(|atts| atts.push(att));
+                    }
+                    Err(e) => {
+                        tracing::error!("Upload failed: {}", e);
+                        error_msg.set(Some(format!("Upload failed: {}", e)));
+                    }
+                }
+            },
+        );
+    };
+
     let host_c = host.clone();
     let gid_c = group_id.clone();
     let cid_c = channel_id.clone();
+    let editing_c = editing.clone();
+    let key_c = key.clone();
 
     let on_publish = move || {
         let t = title.get().clone();
@@ -28,24 +127,74 @@ pub fn article_editor_view() -> NodeHandle {
             error_msg.set(Some("Title and body are required".to_string()));
             return;
         }
+        if uploading.get() > 0 {
+            error_msg.set(Some("Wait for attachments to finish uploading".to_string()));
+            return;
+        }
+
+        // Attachments the body doesn't link stay on as downloads; only the
+        // remove button drops one
+        let atts: Vec<Attachment> = attachments.get().clone();
+        let tags = crate::tags::merge_tags(
+            crate::tags::parse_tag_list(&tag_input.get()),
+            crate::tags::extract_hashtags(&b),
+        );
 
         loading.set(true);
         error_msg.set(None);
 
+        if let Some(message_id) = editing_c.clone() {
+            let client = get_auth_store().make_client();
+            let gid = gid_c.clone();
+            let cid = cid_c.clone();
+            let (host_nav, gid_nav, cid_nav) = (host_c.clone(), gid_c.clone(), cid_c.clone());
+            let draft_key = key_c.clone();
+            crate::runtime::spawn(
+                async move {
+                    let req = rorumall_shared::UpdateMessageRequest {
+                        title: Some(t),
+                        body: Some(b),
+                        attachments: Some(atts),
+                        tags: Some(tags),
+                    };
+                    client.update_message(&gid, &cid, &message_id, &req).await
+                },
+                move |result| {
+                    loading.set(false);
+                    match result {
+                        Ok(updated) => {
+                            get_messages_store().update_message(&cid_nav, StoredMessage::from_channel_message(updated));
+                            get_drafts_store().clear_article_draft(&draft_key);
+                            navigate(AppRoute::Channel {
+                                host: host_nav,
+                                group_id: gid_nav,
+                                channel_id: cid_nav,
+                            });
+                        }
+                        Err(e) => {
+                            error_msg.set(Some(format!("Failed to update: {}", e)));
+                        }
+                    }
+                },
+            );
+            return;
+        }
+
         let domain = get_auth_store().domain();
         let ws_host = if host_c.is_empty() { domain } else { host_c.clone() };
 
         if let Some(handle) = crate::ws::get_handle(&ws_host) {
             let nonce = uuid::Uuid::new_v4().to_string();
-            match handle.send_message_with_options(
-                &cid_c,
-                &b,
-                &nonce,
-                Some(t),
-                Some(rorumall_shared::MessageType::Article),
-                vec![],
-            ) {
+            let options = crate::ws::MessageOptions {
+                title: Some(t),
+                message_type: Some(rorumall_shared::MessageType::Article),
+                attachments: atts,
+                tags,
+                ..Default::default()
+            };
+            match handle.send_message_with_options(&cid_c, &b, &nonce, options) {
                 Ok(()) => {
+                    get_drafts_store().clear_article_draft(&key_c);
                     navigate(AppRoute::Channel {
                         host: host_c.clone(),
                         group_id: gid_c.clone(),
@@ -62,13 +211,18 @@ pub fn article_editor_view() -> NodeHandle {
         loading.set(false);
     };
 
+    // Discarding an edit goes back to the published version
+    let key_discard = key.clone();
+    let original = Signal::new(original);
     let host_back = host.clone();
     let gid_back = group_id.clone();
     let cid_back = channel_id.clone();
+    let heading = if editing.is_some() { "Edit Article" } else { "Compose Article" };
+    let publish_label = if editing.is_some() { " Publish update" } else { " Publish" };
 
     rsx! {
         div {
-            style: "display: flex; flex-direction: column; height: 100%; padding: 24px;",
+            style: "display: flex; flex-direction: column; height: 100%; padding: 24px; overflow: hidden;",
 
             // Header
             Group {
@@ -90,17 +244,43 @@ pub fn article_editor_view() -> NodeHandle {
 
                     Title {
                         order: 3,
-                        "Compose Article"
+                        {heading}
+                    }
+
+                    if let Some(saved) = restored_at {
+                        Text {
+                            size: "xs",
+                            color: "dimmed",
+                            {format!("Draft restored from {}", saved.with_timezone(&chrono::Local).format("%b %d %H:%M"))}
+                        }
                     }
                 }
 
-                Button {
-                    variant: "filled",
-                    color: "indigo",
-                    disabled: loading.get(),
-                    onclick: move || on_publish(),
-                    {render_tabler_icon(__scope, TablerIcon::Send, TablerIconStyle::Outline)}
-                    " Publish"
+                Group {
+                    gap: "sm",
+
+                    Button {
+                        variant: "subtle",
+                        color: "gray",
+                        onclick: move || {
+                            get_drafts_store().clear_article_draft(&key_discard);
+                            let original = original.get().clone();
+                            title.set(original.as_ref().and_then(|m| m.title.clone()).unwrap_or_default());
+                            body.set(original.as_ref().map(|m| m.content.clone()).unwrap_or_default());
+                            tag_input.set(original.as_ref().map(|m| m.tags.join(", ")).unwrap_or_default());
+                            attachments.set(original.map(|m| m.attachments).unwrap_or_default());
+                        },
+                        "Discard draft"
+                    }
+
+                    Button {
+                        variant: "filled",
+                        color: "indigo",
+                        disabled: loading.get(),
+                        onclick: move || on_publish(),
+                        {render_tabler_icon(__scope, TablerIcon::Send, TablerIconStyle::Outline)}
+                        {publish_label}
+                    }
                 }
             }
 
@@ -112,47 +292,119 @@ pub fn article_editor_view() -> NodeHandle {
                 }
             }
 
-            Stack {
-                gap: "md",
-                style: "flex: 1;",
+            TextInput {
+                label: "Title",
+                placeholder: "Article title",
+                size: "lg",
+                value_fn: move || title.get().clone(),
+                oninput: move |val: String| title.set(val),
+            }
 
-                TextInput {
-                    label: "Title",
-                    placeholder: "Article title",
-                    size: "lg",
-                    value_fn: move || title.get().clone(),
-                    oninput: move |val: String| title.set(val),
-                }
+            TextInput {
+                label: "Tags",
+                placeholder: "release-notes, incident (#tags in the body are added too)",
+                value_fn: move || tag_input.get().clone(),
+                oninput: move |val: String| tag_input.set(val),
+            }
 
-                Textarea {
-                    label: "Content (Markdown)",
-                    placeholder: "Write your article in Markdown...",
-                    style: "flex: 1;",
-                    min_rows: 15,
-                    value_fn: move || body.get().clone(),
-                    oninput: move |val: String| body.set(val),
-                }
+            // Split editor: Markdown source on the left, live preview on the right
+            div {
+                style: "flex: 1; display: flex; gap: 16px; margin-top: 16px; min-height: 0;",
 
-                // Preview
-                if !body.get().is_empty() {
-                    div {
-                        style: "border: 1px solid var(--rinch-color-dark-4, #373a40); border-radius: var(--rinch-radius-md); padding: 16px;",
+                div {
+                    style: "flex: 1; display: flex; flex-direction: column; min-width: 0; overflow-y: auto;",
+
+                    Group {
+                        justify: "space-between",
+                        style: "margin-bottom: 4px;",
 
                         Text {
                             size: "sm",
                             color: "dimmed",
-                            style: "margin-bottom: 8px;",
-                            "Preview"
+                            "Content (Markdown)"
                         }
 
+                        Button {
+                            variant: "subtle",
+                            size: "xs",
+                            onclick: move || on_attach(),
+                            {render_tabler_icon(__scope, TablerIcon::Paperclip, TablerIconStyle::Outline)}
+                            {|| if uploading.get() > 0 { format!(" Uploading {}...", uploading.get()) } else { " Attach file".to_string() }}
+                        }
+                    }
+
+                    Textarea {
+                        placeholder: "Write your article in Markdown...",
+                        style: "flex: 1;",
+                        min_rows: 20,
+                        value_fn: move || body.get().clone(),
+                        oninput: move |val: String| body.set(val),
+                    }
+
+                    if !attachments.get().is_empty() {
                         div {
-                            class: "markdown-content",
-                            // Render markdown preview
-                            {crate::components::messages::article_item::render_markdown(&body.get())}
+                            style: "display: flex; flex-wrap: wrap; gap: 6px; margin-top: 8px;",
+                            for att in attachments.get().clone() {
+                                let att_id = att.id.clone();
+                                let referenced = body.get().contains(&att.url);
+                                div {
+                                    key: att.id.clone(),
+                                    Tooltip {
+                                        label: {if referenced { "Linked in the article".to_string() } else { "Attached as a download".to_string() }},
+
+                                        Badge {
+                                            variant: {if referenced { "light".to_string() } else { "outline".to_string() }},
+                                            color: {if referenced { "indigo".to_string() } else { "gray".to_string() }},
+                                            {crate::search::attachment_name(&att)}
+                                        }
+                                    }
+                                    Tooltip {
+                                        label: "Remove attachment",
+
+                                        ActionIcon {
+                                            variant: "subtle",
+                                            size: "xs",
+                                            onclick: move || attachments.update(|atts| atts.retain(|a| a.id != att_id)),
+                                            {render_tabler_icon(__scope, TablerIcon::X, TablerIconStyle::Outline)}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    style: "flex: 1; min-width: 0; overflow-y: auto; border: 1px solid var(--rinch-color-dark-4, #373a40); border-radius: var(--rinch-radius-md); padding: 16px;",
+
+                    Text {
+                        size: "sm",
+                        color: "dimmed",
+                        style: "margin-bottom: 8px;",
+                        "Preview"
+                    }
+
+                    if !title.get().is_empty() {
+                        Title {
+                            order: 2,
+                            {title.get().clone()}
                         }
                     }
+
+                    // Render markdown preview
+                    {crate::components::messages::markdown_view::markdown_view(__scope, crate::markdown::article_blocks(&body.get()), String::new())}
                 }
             }
         }
     }
 }
+
+/// Markdown that embeds an uploaded image or links to any other file.
+fn markdown_reference(att: &Attachment, filename: &str) -> String {
+    let label = filename.replace(['[', ']'], "");
+    if att.mime.starts_with("image/") {
+        format!("![{}]({})", label, att.url)
+    } else {
+        format!("[{}]({})", label, att.url)
+    }
+}
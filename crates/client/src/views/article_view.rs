@@ -0,0 +1,222 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use crate::components::messages::reply_thread::reply_thread;
+use crate::components::messages::thread_panel::reply_composer;
+use crate::markdown::{article_blocks, headings, reading_time_minutes};
+use crate::navigation::{get_nav, navigate, AppRoute};
+use crate::stores::{get_auth_store, get_members_store, get_messages_store, get_presence_store, StoredMessage};
+
+/// Full-page reader for a single article, with a table of contents and comments.
+#[component]
+pub fn article_view() -> NodeHandle {
+    let nav = get_nav();
+    let (host, group_id, channel_id, message_id) = match nav.get().clone() {
+        AppRoute::Article { host, group_id, channel_id, message_id } => {
+            (host, group_id, channel_id, message_id)
+        }
+        _ => return rsx! { div { "Invalid route" } },
+    };
+
+    let loading = Signal::new(false);
+    let error = Signal::new(None::<String>);
+    let reply_target = Signal::new(None::<String>);
+
+    let cid = Signal::new(channel_id.clone());
+    let mid = Signal::new(message_id.clone());
+
+    // Always fetch the thread so comments are complete; the article itself
+    // may not be in the loaded page of the channel either.
+    {
+        if get_messages_store().find_message(&channel_id, &message_id).is_none() {
+            loading.set(true);
+        }
+        let client = get_auth_store().make_client_for(&host);
+        let gid = group_id.clone();
+        let ch = channel_id.clone();
+        let msg_id = message_id.clone();
+        crate::runtime::spawn(
+            async move {
+                let result = client.get_thread(&gid, &ch, &msg_id).await;
+                (ch, result)
+            },
+            move |(ch, result)| {
+                match result {
+                    Ok(page) => {
+                        let stored: Vec<StoredMessage> = page
+                            .items
+                            .into_iter()
+                            .map(StoredMessage::from_channel_message)
+                            .collect();
+                        get_messages_store().merge_messages(&ch, stored);
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to load article: {}", e);
+                        error.set(Some(format!("Failed to load article: {}", e)));
+                    }
+                }
+                loading.set(false);
+            },
+        );
+    }
+
+    let back_route = AppRoute::Channel {
+        host: host.clone(),
+        group_id: group_id.clone(),
+        channel_id: channel_id.clone(),
+    };
+
+    rsx! {
+        div {
+            style: "display: flex; flex-direction: column; height: 100%; overflow: hidden;",
+
+            // Header — matches channel header height
+            div {
+                style: "height: 52px; min-height: 52px; display: flex; align-items: center; padding: 0 16px; gap: 10px; border-bottom: 1px solid var(--rinch-color-dark-4, #373a40); flex-shrink: 0;",
+
+                ActionIcon {
+                    variant: "subtle",
+                    size: "sm",
+                    onclick: move || navigate(back_route.clone()),
+                    {render_tabler_icon(__scope, TablerIcon::ArrowLeft, TablerIconStyle::Outline)}
+                }
+
+                Text {
+                    size: "md",
+                    weight: "600",
+                    {move || get_messages_store()
+                        .find_message(&cid.get(), &mid.get())
+                        .and_then(|m| m.title)
+                        .unwrap_or_else(|| "Article".to_string())}
+                }
+            }
+
+            if let Some(err) = error.get().clone() {
+                Alert {
+                    color: "red",
+                    variant: "light",
+                    {err}
+                }
+            }
+
+            if let Some(msg) = get_messages_store().find_message(&cid.get(), &mid.get()) {
+                let toc = headings(&msg.content);
+                let minutes = reading_time_minutes(&msg.content);
+                let time = msg.created_at.format("%b %d, %Y at %H:%M").to_string();
+                let attachments = msg.attachments.clone();
+                let author = author_card(&group_id, &msg.user_id);
+                let comments = get_messages_store()
+                    .get_channel_messages(&cid.get())
+                    .and_then(|ch| ch.reply_tree(&mid.get()));
+
+                div {
+                    style: "flex: 1; display: flex; min-height: 0;",
+
+                    // Article body and comments
+                    div {
+                        style: "flex: 1; overflow-y: auto; padding: 24px 32px; min-width: 0;",
+
+                        Stack {
+                            gap: "md",
+                            style: "max-width: 760px; margin: 0 auto;",
+
+                            Title {
+                                order: 1,
+                                {msg.title.clone().unwrap_or_else(|| "Untitled Article".to_string())}
+                            }
+
+                            {crate::components::profile::profile_card::profile_card(__scope, msg.user_id.clone(), author.0, String::new(), author.1, author.2)}
+
+                            Text {
+                                size: "sm",
+                                color: "dimmed",
+                                {format!("{} · {} min read", time, minutes)}
+                            }
+
+                            Divider {}
+
+                            {crate::components::messages::markdown_view::markdown_view(__scope, article_blocks(&msg.content), String::new())}
+
+                            if !attachments.is_empty() {
+                                Divider {}
+                                div {
+                                    style: "display: flex; flex-wrap: wrap; gap: 8px;",
+                                    for att in attachments.clone() {
+                                        {crate::components::ui::attachment_display::attachment_display(__scope, att)}
+                                    }
+                                }
+                            }
+
+                            Divider {}
+
+                            Title {
+                                order: 4,
+                                "Comments"
+                            }
+
+                            if let Some(tree) = comments {
+                                {reply_thread(__scope, tree, group_id.clone(), channel_id.clone(), reply_target)}
+                            }
+
+                            {reply_composer(__scope, host.clone(), group_id.clone(), channel_id.clone(), message_id.clone(), reply_target, "Write a comment...".to_string())}
+                        }
+                    }
+
+                    // Table of contents
+                    if !toc.is_empty() {
+                        div {
+                            style: "width: 220px; flex-shrink: 0; overflow-y: auto; padding: 24px 16px; border-left: 1px solid var(--rinch-color-dark-4, #373a40);",
+
+                            Text {
+                                size: "xs",
+                                weight: "600",
+                                color: "dimmed",
+                                style: "text-transform: uppercase; margin-bottom: 8px;",
+                                "Contents"
+                            }
+
+                            for heading in toc.clone() {
+                                a {
+                                    key: heading.anchor.clone(),
+                                    class: "toc-link",
+                                    href: {format!("#{}", heading.anchor)},
+                                    style: {format!("padding-left: {}px;", (heading.level.saturating_sub(1) as u32) * 12)},
+                                    {heading.text.clone()}
+                                }
+                            }
+                        }
+                    }
+                }
+            } else if loading.get() {
+                Stack {
+                    align: "center",
+                    p: "xl",
+                    Loader {}
+                }
+            }
+        }
+    }
+}
+
+/// Display name, avatar URL and presence status for an article's author.
+fn author_card(group_id: &str, user_id: &str) -> (String, String, String) {
+    let member = get_members_store()
+        .get_group_members(group_id)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|m| m.user_id == user_id);
+    let display_name = member
+        .as_ref()
+        .and_then(|m| m.display_name.clone())
+        .unwrap_or_else(|| user_id.split('@').next().unwrap_or(user_id).to_string());
+    let avatar = member.and_then(|m| m.avatar).unwrap_or_default();
+
+    let (handle, domain) = user_id.split_once('@').unwrap_or((user_id, ""));
+    let status = match get_presence_store().get_availability(handle, domain) {
+        rorumall_shared::Availability::Online => "online",
+        rorumall_shared::Availability::Away => "away",
+        rorumall_shared::Availability::Dnd => "busy",
+        rorumall_shared::Availability::Offline => "offline",
+    };
+
+    (display_name, avatar, status.to_string())
+}
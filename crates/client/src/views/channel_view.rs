@@ -1,6 +1,7 @@
 use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
 use crate::navigation::{get_nav, AppRoute};
-use crate::stores::{get_auth_store, get_groups_store, get_messages_store, StoredMessage};
+use crate::stores::{get_auth_store, get_groups_store, get_messages_store, get_pins_store, ChannelLocation, StoredMessage};
 
 #[component]
 pub fn channel_view() -> NodeHandle {
@@ -13,6 +14,29 @@ pub fn channel_view() -> NodeHandle {
     let messages_store = get_messages_store();
     let auth = get_auth_store();
     let loading = Signal::new(false);
+    let focus_message = messages_store.focus_message;
+    let open_thread = messages_store.open_thread;
+    let show_pins = Signal::new(false);
+    let show_export = Signal::new(false);
+    let active_tab = Signal::new("messages".to_string());
+    let tag_filter = messages_store.tag_filter;
+    let pins_cid = Signal::new(channel_id.clone());
+
+    // Get channel name from groups store
+    let channel_name = get_groups_store()
+        .channels.get()
+        .iter()
+        .find(|c| c.id == channel_id)
+        .map(|c| c.name.clone())
+        .unwrap_or_else(|| channel_id.clone());
+
+    // Record where this channel lives so its messages can be cached and searched
+    messages_store.set_channel_location(ChannelLocation {
+        host: host.clone(),
+        group_id: group_id.clone(),
+        channel_id: channel_id.clone(),
+        channel_name: channel_name.clone(),
+    });
 
     // Load channel messages
     let ch_id = channel_id.clone();
@@ -32,21 +56,11 @@ pub fn channel_view() -> NodeHandle {
             move |(ch, result)| {
                 match result {
                     Ok(page) => {
-                        let stored: Vec<StoredMessage> = page.items.into_iter().map(|m| {
-                            StoredMessage {
-                                id: m.id,
-                                user_id: m.sender_user_id,
-                                title: m.title,
-                                content: m.body,
-                                message_type: m.message_type.unwrap_or(rorumall_shared::MessageType::Message),
-                                created_at: chrono::DateTime::parse_from_rfc3339(&m.created_at)
-                                    .map(|dt| dt.with_timezone(&chrono::Utc))
-                                    .unwrap_or_else(|_| chrono::Utc::now()),
-                                parent_id: m.parent_id,
-                                parent_message_type: m.parent_message_type,
-                                attachments: m.attachments,
-                            }
-                        }).collect();
+                        let stored: Vec<StoredMessage> = page
+                            .items
+                            .into_iter()
+                            .map(StoredMessage::from_channel_message)
+                            .collect();
                         get_messages_store().set_channel_history(&ch, stored);
                     }
                     Err(e) => {
@@ -58,6 +72,47 @@ pub fn channel_view() -> NodeHandle {
         );
     }
 
+    // A message to jump to that isn't in the loaded history (an older search
+    // hit, quote or link) is fetched with the messages around it
+    let around_requested = Signal::new(None::<String>);
+    {
+        let host = host.clone();
+        let group_id = group_id.clone();
+        let channel_id = channel_id.clone();
+        Effect::new(move || {
+            let Some(target) = focus_message.get().clone() else {
+                return;
+            };
+            let store = get_messages_store();
+            if !store.is_channel_loaded(&channel_id)
+                || store.find_message(&channel_id, &target).is_some()
+                || around_requested.get().as_deref() == Some(target.as_str())
+            {
+                return;
+            }
+            around_requested.set(Some(target.clone()));
+            let client = get_auth_store().make_client_for(&host);
+            let gid = group_id.clone();
+            let ch = channel_id.clone();
+            crate::runtime::spawn(
+                async move {
+                    let result = client.list_messages_around(&gid, &ch, &target, 50).await;
+                    (ch, result)
+                },
+                move |(ch, result)| match result {
+                    Ok(page) => {
+                        let stored = page.items.into_iter().map(StoredMessage::from_channel_message).collect();
+                        get_messages_store().merge_messages(&ch, stored);
+                    }
+                    Err(e) => tracing::error!("Failed to load messages around the focused one: {}", e),
+                },
+            );
+        });
+    }
+
+    // Pins mark messages in the list and fill the pinned drawer
+    get_pins_store().load(&group_id, &channel_id);
+
     // Subscribe via WS
     let ch_for_ws = channel_id.clone();
     let host_for_ws = host.clone();
@@ -69,14 +124,16 @@ pub fn channel_view() -> NodeHandle {
         }
     }
 
-    // Get channel name from groups store
-    let channel_name = get_groups_store()
-        .channels.get()
-        .iter()
-        .find(|c| c.id == channel_id)
-        .map(|c| c.name.clone())
-        .unwrap_or_else(|| channel_id.clone());
+    // Files dropped anywhere on the window wait for the composer while a
+    // channel is open
+    rinch_core::set_file_drop_handler(move |paths: Vec<std::path::PathBuf>| {
+        if !matches!(get_nav().get().clone(), AppRoute::Channel { .. }) {
+            return;
+        }
+        get_messages_store().compose_files.update(|files| files.extend(paths));
+    });
 
+    let export_channel_name = channel_name.clone();
     let input_channel_id = channel_id.clone();
     let input_group_id = group_id.clone();
     let input_host = host.clone();
@@ -101,31 +158,130 @@ pub fn channel_view() -> NodeHandle {
                     weight: "600",
                     {channel_name}
                 }
+
+                Tabs {
+                    value: {active_tab.get().clone()},
+
+                    TabsList {
+                        Tab { value: "messages", onclick: move || active_tab.set("messages".to_string()), "Messages" }
+                        Tab { value: "media", onclick: move || active_tab.set("media".to_string()), "Media" }
+                    }
+                }
+
+                div { style: "flex: 1;" }
+
+                Tooltip {
+                    label: "Export history",
+
+                    ActionIcon {
+                        variant: "subtle",
+                        onclick: move || show_export.set(true),
+                        {render_tabler_icon(__scope, TablerIcon::FileExport, TablerIconStyle::Outline)}
+                    }
+                }
+
+                Tooltip {
+                    label: "Pinned messages",
+
+                    ActionIcon {
+                        variant: {if show_pins.get() { "light".to_string() } else { "subtle".to_string() }},
+                        onclick: move || show_pins.update(|v| *v = !*v),
+                        {render_tabler_icon(__scope, TablerIcon::Pin, TablerIconStyle::Outline)}
+                    }
+                }
+
+                if !get_pins_store().get_pins(&pins_cid.get()).is_empty() {
+                    Text {
+                        size: "xs",
+                        color: "dimmed",
+                        {get_pins_store().get_pins(&pins_cid.get()).len().to_string()}
+                    }
+                }
             }
 
-            // Message list — column-reverse keeps scroll anchored to bottom
+            {crate::components::messages::export::export_modal(__scope, host.clone(), group_id.clone(), channel_id.clone(), export_channel_name, show_export)}
+
+            {crate::components::messages::tags::tag_filter_bar(__scope, host.clone(), group_id.clone(), channel_id.clone())}
+
             div {
-                style: "flex: 1; overflow-y: auto; padding: 16px; min-height: 0; display: flex; flex-direction: column-reverse;",
+                style: "flex: 1; display: flex; overflow: hidden; min-height: 0;",
+
+                if active_tab.get().as_str() == "media" {
+                    div {
+                        style: "flex: 1; display: flex; flex-direction: column; overflow: hidden; min-width: 0;",
+                        {crate::components::messages::media_gallery::media_gallery(__scope, host.clone(), channel_id.clone())}
+                    }
+                } else {
+                    div {
+                        style: "flex: 1; display: flex; flex-direction: column; overflow: hidden; min-width: 0;",
+
+                        // Message list — column-reverse keeps scroll anchored to bottom
+                        div {
+                            style: "flex: 1; overflow-y: auto; padding: 16px; min-height: 0; display: flex; flex-direction: column-reverse;",
+
+                            if loading.get() {
+                                Stack {
+                                    align: "center",
+                                    p: "xl",
+                                    Loader {}
+                                }
+                            }
+
+                            for msg in messages_store.messages.get().get(&channel_id).map(|ch| ch.visible(focus_message.get().as_deref(), tag_filter.get().as_ref())).unwrap_or_default().into_iter().rev() {
+                                let is_focused = focus_message.get().as_deref() == Some(msg.id.as_str());
+                                div {
+                                    key: msg.id.clone(),
+                                    id: msg.id.clone(),
+                                    style: {if is_focused { "background: var(--rinch-color-indigo-9, #26326e); border-radius: 6px;".to_string() } else { String::new() }},
+                                    {crate::components::messages::message_item::message_item(__scope, msg, group_id.clone(), channel_id.clone(), false)}
+                                }
+                            }
+                        }
+
+                        // Jumped to an older message; newer ones wait below
+                        if messages_store.messages.get().get(&channel_id).is_some_and(|ch| ch.hides_newer(focus_message.get().as_deref())) {
+                            div {
+                                style: "display: flex; align-items: center; gap: 8px; padding: 6px 16px; border-top: 1px solid var(--rinch-color-dark-4, #373a40);",
+
+                                Text {
+                                    size: "xs",
+                                    color: "dimmed",
+                                    style: "flex: 1;",
+                                    "You're viewing older messages"
+                                }
 
-                if loading.get() {
-                    Stack {
-                        align: "center",
-                        p: "xl",
-                        Loader {}
+                                Button {
+                                    variant: "light",
+                                    size: "xs",
+                                    onclick: move || focus_message.set(None),
+                                    "Jump to latest"
+                                }
+                            }
+                        }
+
+                        // Message input
+                        div {
+                            {crate::components::messages::message_input::message_input(__scope, input_channel_id.clone(), input_group_id.clone(), input_host.clone())}
+                        }
                     }
                 }
 
-                for msg in messages_store.messages.get().get(&channel_id).map(|ch| ch.messages.clone()).unwrap_or_default().into_iter().rev() {
+                // Pinned messages drawer
+                if show_pins.get() {
                     div {
-                        key: msg.id.clone(),
-                        {crate::components::messages::message_item::message_item(__scope, msg, group_id.clone())}
+                        style: "width: 340px; min-width: 340px; border-left: 1px solid var(--rinch-color-dark-4, #373a40); overflow: hidden;",
+                        {crate::components::messages::pins::pinned_drawer(__scope, group_id.clone(), channel_id.clone(), show_pins)}
                     }
                 }
-            }
 
-            // Message input
-            div {
-                {crate::components::messages::message_input::message_input(__scope, input_channel_id, input_group_id, input_host)}
+                // Thread side panel
+                for thread_id in open_thread.get().clone().into_iter() {
+                    div {
+                        key: thread_id.clone(),
+                        style: "width: 380px; min-width: 380px; border-left: 1px solid var(--rinch-color-dark-4, #373a40); overflow: hidden;",
+                        {crate::components::messages::thread_panel::thread_panel(__scope, host.clone(), group_id.clone(), channel_id.clone(), thread_id)}
+                    }
+                }
             }
         }
     }
@@ -0,0 +1,381 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use rorumall_shared::JoinGroupRequest;
+use crate::discovery::{DiscoveredGroup, JoinOutcome, JoinPolicy, JoinRequest};
+use crate::navigation::{navigate, AppRoute};
+use crate::stores::{get_auth_store, get_groups_store, get_invites_store};
+
+/// Public groups on the home server or on a federated one, with search.
+#[component]
+pub fn discover_view() -> NodeHandle {
+    let home = get_auth_store().domain();
+    let server = Signal::new(String::new());
+    let query = Signal::new(String::new());
+    let results = Signal::new(Vec::<DiscoveredGroup>::new());
+    let domains = Signal::new(crate::discovery::saved_domains());
+    let loading = Signal::new(false);
+    let error = Signal::new(None::<String>);
+    let invite_input = Signal::new(String::new());
+
+    let home_domain = Signal::new(home.clone());
+
+    // Requests sent earlier may have been answered since
+    get_groups_store().check_join_requests();
+    let run_search = move || {
+        let Some(host) = crate::discovery::host_for(&server.get(), &home_domain.get()) else {
+            error.set(Some(format!("\"{}\" isn't a server address", server.get().trim())));
+            return;
+        };
+        loading.set(true);
+        error.set(None);
+        let client = get_auth_store().make_client_for(&host);
+        let q = query.get().clone();
+        crate::runtime::spawn(
+            async move {
+                let result = crate::discovery::search(&client, &host, &q).await;
+                (host, result)
+            },
+            move |(host, result)| {
+                match result {
+                    Ok(found) => {
+                        if !host.is_empty() {
+                            crate::discovery::remember_domain(&host);
+                            domains.set(crate::discovery::saved_domains());
+                        }
+                        results.set(found);
+                    }
+                    Err(e) => {
+                        results.set(Vec::new());
+                        error.set(Some(format!("Couldn't load groups: {}", e)));
+                    }
+                }
+                loading.set(false);
+            },
+        );
+    };
+
+    // A pasted invite link opens the accept-invite dialog straight away
+    let on_invite_input = move |val: String| {
+        match crate::invites::InviteLink::find(&val, &home_domain.get()) {
+            Some(link) => {
+                invite_input.set(String::new());
+                error.set(None);
+                get_invites_store().open(link);
+            }
+            None => invite_input.set(val),
+        }
+    };
+
+    // Start with the home server's directory
+    run_search();
+
+    rsx! {
+        div {
+            style: "flex: 1; display: flex; flex-direction: column; overflow: hidden;",
+
+            // Header — matches channel header height
+            div {
+                style: "height: 52px; min-height: 52px; display: flex; align-items: center; padding: 0 20px; gap: 10px; border-bottom: 1px solid var(--rinch-color-dark-4, #373a40); flex-shrink: 0;",
+
+                {render_tabler_icon(__scope, TablerIcon::Compass, TablerIconStyle::Outline)}
+
+                Text {
+                    size: "md",
+                    weight: "600",
+                    "Discover groups"
+                }
+            }
+
+            div {
+                style: "flex: 1; overflow-y: auto; padding: 16px;",
+
+                Stack {
+                    gap: "md",
+
+                    Group {
+                        gap: "sm",
+
+                        TextInput {
+                            placeholder: {home.clone()},
+                            style: "width: 220px;",
+                            value_fn: move || server.get().clone(),
+                            oninput: move |val: String| server.set(val),
+                            onsubmit: move || run_search(),
+                        }
+
+                        TextInput {
+                            placeholder: "Search groups...",
+                            style: "flex: 1;",
+                            value_fn: move || query.get().clone(),
+                            oninput: move |val: String| query.set(val),
+                            onsubmit: move || run_search(),
+                        }
+
+                        Button {
+                            variant: "filled",
+                            color: "indigo",
+                            loading: loading.get(),
+                            onclick: move || run_search(),
+                            "Search"
+                        }
+                    }
+
+                    TextInput {
+                        placeholder: "Have an invite link? Paste it here",
+                        value_fn: move || invite_input.get().clone(),
+                        oninput: move |val: String| on_invite_input(val),
+                        onsubmit: move || {
+                            if !invite_input.get().trim().is_empty() {
+                                error.set(Some("That isn't an invite link".to_string()));
+                            }
+                        },
+                    }
+
+                    // Servers searched before
+                    if !domains.get().is_empty() {
+                        Group {
+                            gap: "xs",
+
+                            Button {
+                                variant: "subtle",
+                                size: "xs",
+                                onclick: move || { server.set(String::new()); run_search(); },
+                                "Home server"
+                            }
+
+                            for domain in domains.get().clone() {
+                                Button {
+                                    variant: "subtle",
+                                    size: "xs",
+                                    onclick: {
+                                        let domain = domain.clone();
+                                        move || { server.set(domain.clone()); run_search(); }
+                                    },
+                                    {domain.clone()}
+                                }
+                            }
+                        }
+                    }
+
+                    if error.get().is_some() {
+                        Alert {
+                            color: "red",
+                            variant: "light",
+                            {error.get().clone().unwrap_or_default()}
+                        }
+                    }
+
+                    if loading.get() && results.get().is_empty() {
+                        Loader {}
+                    }
+
+                    if !loading.get() && error.get().is_none() && results.get().is_empty() {
+                        Text {
+                            size: "sm",
+                            color: "dimmed",
+                            "No public groups found. Try another search or server."
+                        }
+                    }
+
+                    for found in results.get().clone() {
+                        div {
+                            key: found.key(),
+                            {discovered_group(__scope, found)}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn discovered_group(found: DiscoveredGroup) -> NodeHandle {
+    let policy = JoinPolicy::of(&found.group);
+    let request_key = Signal::new(found.key());
+    let requested = move || get_groups_store().is_requested(&request_key.get());
+    let asking = Signal::new(false);
+    let message = Signal::new(String::new());
+    let joining = Signal::new(false);
+    let error = Signal::new(None::<String>);
+    let group_id = found.group.id.clone();
+    let is_member = move || get_groups_store().joined_groups.get().iter().any(|g| g.group_id == group_id);
+    let server = if found.host.is_empty() {
+        get_auth_store().domain()
+    } else {
+        found.host.clone()
+    };
+    let host = found.host.clone();
+    let open_id = found.group.id.clone();
+    let found = Signal::new(found);
+
+    let on_join = move || {
+        let note = message.get().trim().to_string();
+        joining.set(true);
+        error.set(None);
+        let found = found.get().clone();
+        let auth = get_auth_store();
+        let group_client = auth.make_client_for(&found.host);
+        let home_client = auth.make_client();
+        let user_id = auth.user_id().unwrap_or_default();
+        crate::runtime::spawn(
+            async move {
+                let req = JoinGroupRequest {
+                    message: if note.is_empty() { None } else { Some(note) },
+                    invite_code: None,
+                };
+                let result = crate::discovery::join(&group_client, &home_client, &user_id, &found, &req).await;
+                (found, result)
+            },
+            move |(found, result)| {
+                match result {
+                    Ok(JoinOutcome::Joined(joined)) => {
+                        get_groups_store().add_joined_group(joined);
+                        navigate(AppRoute::Group { host: found.host.clone(), group_id: found.group.id.clone() });
+                    }
+                    Ok(JoinOutcome::Pending) => {
+                        get_groups_store().add_join_request(JoinRequest::from(&found));
+                        asking.set(false);
+                    }
+                    Err(e) => error.set(Some(format!("Couldn't join: {}", e))),
+                }
+                joining.set(false);
+            },
+        );
+    };
+
+    rsx! {
+        Card {
+            shadow: "xs",
+            p: "sm",
+
+            Group {
+                gap: "sm",
+
+                Avatar {
+                    size: "md",
+                    color: "indigo",
+                    src: {found.get().group.avatar.clone().unwrap_or_default()},
+                    name: found.get().group.name.clone(),
+                }
+
+                Stack {
+                    gap: "xs",
+                    style: "flex: 1; min-width: 0;",
+
+                    Group {
+                        gap: "xs",
+
+                        Text {
+                            size: "sm",
+                            weight: "600",
+                            {found.get().group.name.clone()}
+                        }
+
+                        Badge {
+                            variant: "light",
+                            color: {match policy {
+                                JoinPolicy::Open => "green",
+                                JoinPolicy::Request => "orange",
+                                JoinPolicy::Invite => "gray",
+                            }},
+                            size: "xs",
+                            {policy.label()}
+                        }
+                    }
+
+                    Text {
+                        size: "xs",
+                        color: "dimmed",
+                        {server}
+                    }
+
+                    if let Some(description) = found.get().group.description.clone().filter(|d| !d.is_empty()) {
+                        Text {
+                            size: "sm",
+                            {description}
+                        }
+                    }
+                }
+
+                if is_member() {
+                    Button {
+                        variant: "light",
+                        onclick: move || navigate(AppRoute::Group { host: host.clone(), group_id: open_id.clone() }),
+                        "Open"
+                    }
+                } else if requested() {
+                    Text {
+                        size: "sm",
+                        color: "dimmed",
+                        "Request sent"
+                    }
+                } else if policy == JoinPolicy::Open {
+                    Button {
+                        variant: "filled",
+                        color: "indigo",
+                        loading: joining.get(),
+                        onclick: move || on_join(),
+                        "Join"
+                    }
+                } else if policy == JoinPolicy::Request && !asking.get() {
+                    Button {
+                        variant: "light",
+                        color: "indigo",
+                        onclick: move || asking.set(true),
+                        "Ask to join"
+                    }
+                } else if policy == JoinPolicy::Invite {
+                    Text {
+                        size: "sm",
+                        color: "dimmed",
+                        "Ask a member for an invite"
+                    }
+                }
+            }
+
+            if asking.get() && !requested() {
+                Stack {
+                    gap: "xs",
+                    style: "margin-top: 8px;",
+
+                    Textarea {
+                        placeholder: "A note for the admins (optional)",
+                        value_fn: move || message.get().clone(),
+                        oninput: move |val: String| message.set(val),
+                    }
+
+                    Group {
+                        justify: "flex-end",
+
+                        Button {
+                            variant: "subtle",
+                            onclick: move || asking.set(false),
+                            "Cancel"
+                        }
+
+                        Button {
+                            variant: "filled",
+                            color: "indigo",
+                            loading: joining.get(),
+                            onclick: move || on_join(),
+                            "Send request"
+                        }
+                    }
+                }
+            }
+
+            if error.get().is_some() {
+                div {
+                    style: "margin-top: 8px;",
+
+                    Alert {
+                        color: "red",
+                        variant: "light",
+                        {error.get().clone().unwrap_or_default()}
+                    }
+                }
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
 use crate::navigation::{get_nav, navigate, AppRoute};
 use crate::stores::get_auth_store;
 use crate::stores::get_groups_store;
+use crate::stores::get_invites_store;
 
 #[component]
 pub fn home_view() -> NodeHandle {
@@ -18,6 +19,9 @@ pub fn home_view() -> NodeHandle {
         navigate(AppRoute::Login);
     }
 
+    // A link the app was launched with, now that someone is signed in
+    crate::deep_link::open_pending();
+
     // Load joined groups on mount
     {
         let client = auth.make_client();
@@ -32,6 +36,7 @@ pub fn home_view() -> NodeHandle {
                 match result {
                     Ok(groups) => {
                         get_groups_store().set_joined_groups(groups);
+                        get_groups_store().check_join_requests();
 
                         // Connect WS to home provider
                         let domain = get_auth_store().domain();
@@ -113,6 +118,40 @@ pub fn home_view() -> NodeHandle {
                         get_auth_store().domain(),
                     )}
 
+                    // Search button
+                    ActionIcon {
+                        variant: "subtle",
+                        size: "lg",
+                        onclick: move || navigate(AppRoute::Search),
+                        {render_tabler_icon(__scope, TablerIcon::Search, TablerIconStyle::Outline)}
+                    }
+
+                    // Scheduled messages
+                    Tooltip {
+                        label: "Scheduled messages",
+                        position: "right",
+
+                        ActionIcon {
+                            variant: "subtle",
+                            size: "lg",
+                            onclick: move || navigate(AppRoute::Scheduled),
+                            {render_tabler_icon(__scope, TablerIcon::Clock, TablerIconStyle::Outline)}
+                        }
+                    }
+
+                    // Group directory
+                    Tooltip {
+                        label: "Discover groups",
+                        position: "right",
+
+                        ActionIcon {
+                            variant: "subtle",
+                            size: "lg",
+                            onclick: move || navigate(AppRoute::Discover),
+                            {render_tabler_icon(__scope, TablerIcon::Compass, TablerIconStyle::Outline)}
+                        }
+                    }
+
                     // Add group button
                     ActionIcon {
                         variant: "light",
@@ -132,10 +171,12 @@ pub fn home_view() -> NodeHandle {
                 }
 
                 // Channel sidebar (when group selected)
-                if matches!(nav.get().clone(), AppRoute::Group { .. } | AppRoute::Channel { .. }) {
+                if matches!(nav.get().clone(), AppRoute::Group { .. } | AppRoute::Channel { .. } | AppRoute::Article { .. } | AppRoute::Tag { .. }) {
                     let (host, gid) = match nav.get().clone() {
                         AppRoute::Group { host, group_id } => (host, group_id),
                         AppRoute::Channel { host, group_id, .. } => (host, group_id),
+                        AppRoute::Article { host, group_id, .. } => (host, group_id),
+                        AppRoute::Tag { host, group_id, .. } => (host, group_id),
                         _ => (String::new(), String::new()),
                     };
 
@@ -191,7 +232,58 @@ pub fn home_view() -> NodeHandle {
                         }
                     }
 
-                    if !matches!(nav.get().clone(), AppRoute::Channel { .. } | AppRoute::Group { .. }) {
+                    // Keyed on message_id so following a link to another article rebuilds the reader
+                    for route in std::iter::once(nav.get().clone()).filter(|r| matches!(r, AppRoute::Article { .. })) {
+                        let article_key = match route {
+                            AppRoute::Article { ref message_id, .. } => message_id.clone(),
+                            _ => String::new(),
+                        };
+                        div {
+                            key: article_key,
+                            style: "flex: 1; display: flex; flex-direction: column; overflow: hidden;",
+                            {crate::views::article_view::article_view(__scope)}
+                        }
+                    }
+
+                    // Keyed on the tag so switching tags rebuilds the view
+                    for route in std::iter::once(nav.get().clone()).filter(|r| matches!(r, AppRoute::Tag { .. })) {
+                        let tag_key = match route {
+                            AppRoute::Tag { ref group_id, ref tag, .. } => format!("{}/{}", group_id, tag),
+                            _ => String::new(),
+                        };
+                        div {
+                            key: tag_key,
+                            style: "flex: 1; display: flex; flex-direction: column; overflow: hidden;",
+                            {crate::views::tag_view::tag_view(__scope)}
+                        }
+                    }
+
+                    if matches!(nav.get().clone(), AppRoute::Search) {
+                        {crate::views::search_view::search_view(__scope)}
+                    }
+
+                    if matches!(nav.get().clone(), AppRoute::Scheduled) {
+                        {crate::views::scheduled_view::scheduled_view(__scope)}
+                    }
+
+                    if matches!(nav.get().clone(), AppRoute::Discover) {
+                        {crate::views::discover_view::discover_view(__scope)}
+                    }
+
+                    // Keyed on the user so following a link to someone else rebuilds the view
+                    for route in std::iter::once(nav.get().clone()).filter(|r| matches!(r, AppRoute::UserProfile { .. })) {
+                        let user_key = match route {
+                            AppRoute::UserProfile { ref host, ref handle } => format!("{}/{}", host, handle),
+                            _ => String::new(),
+                        };
+                        div {
+                            key: user_key,
+                            style: "flex: 1; display: flex; flex-direction: column; overflow: hidden;",
+                            {crate::views::user_profile_view::user_profile_view(__scope)}
+                        }
+                    }
+
+                    if !matches!(nav.get().clone(), AppRoute::Channel { .. } | AppRoute::Group { .. } | AppRoute::Article { .. } | AppRoute::Tag { .. } | AppRoute::Search | AppRoute::Scheduled | AppRoute::Discover | AppRoute::UserProfile { .. }) {
                         div {
                             style: "flex: 1; display: flex; align-items: center; justify-content: center;",
                             Stack {
@@ -216,6 +308,12 @@ pub fn home_view() -> NodeHandle {
             }
 
             // Modals
+            {crate::views::scheduled_view::missed_schedule_prompt(__scope)}
+
+            if get_invites_store().pending.get().is_some() {
+                {crate::components::ui::accept_invite_modal::accept_invite_modal(__scope)}
+            }
+
             if show_create_group.get() {
                 {crate::components::ui::create_group_modal::create_group_modal(__scope, show_create_group)}
             }
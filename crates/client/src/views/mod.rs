@@ -1,6 +1,12 @@
 pub mod article_editor;
+pub mod article_view;
 pub mod channel_view;
+pub mod discover_view;
 pub mod home;
 pub mod login;
 pub mod profile_view;
 pub mod register;
+pub mod scheduled_view;
+pub mod search_view;
+pub mod tag_view;
+pub mod user_profile_view;
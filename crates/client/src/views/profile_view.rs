@@ -259,6 +259,13 @@ pub fn profile_view() -> NodeHandle {
                             "public".to_string(),
                         )}
                     }
+
+                    Divider {}
+
+                    // Display settings
+                    div {
+                        {crate::components::profile::display_settings::display_settings(__scope)}
+                    }
                 }
             }
         }
@@ -0,0 +1,253 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use crate::components::messages::schedule::{format_input_time, format_local_time, parse_local_time};
+use crate::stores::{get_scheduled_store, ScheduledMessage};
+
+/// Messages queued with "send later", soonest first.
+#[component]
+pub fn scheduled_view() -> NodeHandle {
+    let store = get_scheduled_store();
+
+    rsx! {
+        div {
+            style: "flex: 1; display: flex; flex-direction: column; overflow: hidden;",
+
+            // Header — matches channel header height
+            div {
+                style: "height: 52px; min-height: 52px; display: flex; align-items: center; padding: 0 20px; gap: 10px; border-bottom: 1px solid var(--rinch-color-dark-4, #373a40); flex-shrink: 0;",
+
+                {render_tabler_icon(__scope, TablerIcon::Clock, TablerIconStyle::Outline)}
+
+                Text {
+                    size: "md",
+                    weight: "600",
+                    "Scheduled messages"
+                }
+            }
+
+            div {
+                style: "flex: 1; overflow-y: auto; padding: 16px;",
+
+                if store.items.get().is_empty() {
+                    Text {
+                        size: "sm",
+                        color: "dimmed",
+                        "Nothing scheduled. Use the clock next to the send button to send a message later."
+                    }
+                }
+
+                Stack {
+                    gap: "sm",
+
+                    for item in store.items.get().clone() {
+                        div {
+                            key: item.id.clone(),
+                            {scheduled_item(__scope, item)}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn scheduled_item(item: ScheduledMessage) -> NodeHandle {
+    let id = Signal::new(item.id.clone());
+    let editing = Signal::new(false);
+    let body = Signal::new(item.body.clone());
+    let time = Signal::new(format_input_time(item.send_at));
+    let error = Signal::new(None::<String>);
+    let is_local = item.remote_id.is_none();
+    let channel = if item.channel_name.is_empty() {
+        "channel".to_string()
+    } else {
+        format!("#{}", item.channel_name)
+    };
+
+    let on_save = move || {
+        let Some(send_at) = parse_local_time(&time.get()) else {
+            error.set(Some("Use the format YYYY-MM-DD HH:MM".to_string()));
+            return;
+        };
+        if send_at <= chrono::Utc::now() {
+            error.set(Some("Pick a time in the future".to_string()));
+            return;
+        }
+        error.set(None);
+        get_scheduled_store().update(&id.get(), body.get().clone(), send_at);
+        editing.set(false);
+    };
+
+    rsx! {
+        Card {
+            shadow: "xs",
+            p: "sm",
+
+            Group {
+                gap: "xs",
+
+                Text {
+                    size: "sm",
+                    weight: "600",
+                    {channel}
+                }
+
+                Text {
+                    size: "xs",
+                    color: "dimmed",
+                    {format_local_time(item.send_at)}
+                }
+
+                if get_scheduled_store().is_missed(&id.get()) {
+                    Badge {
+                        variant: "light",
+                        color: "orange",
+                        size: "xs",
+                        "Missed"
+                    }
+                }
+
+                if !is_local {
+                    Tooltip {
+                        label: "Held by the server",
+                        {render_tabler_icon(__scope, TablerIcon::Cloud, TablerIconStyle::Outline)}
+                    }
+                }
+            }
+
+            if editing.get() {
+                Stack {
+                    gap: "xs",
+                    style: "margin-top: 8px;",
+
+                    if error.get().is_some() {
+                        Alert {
+                            color: "red",
+                            {error.get().clone().unwrap_or_default()}
+                        }
+                    }
+
+                    Textarea {
+                        value_fn: move || body.get().clone(),
+                        oninput: move |val: String| body.set(val),
+                    }
+
+                    TextInput {
+                        label: "Send at",
+                        placeholder: "YYYY-MM-DD HH:MM",
+                        value_fn: move || time.get().clone(),
+                        oninput: move |val: String| time.set(val),
+                        onsubmit: move || on_save(),
+                    }
+
+                    Group {
+                        justify: "flex-end",
+
+                        Button {
+                            variant: "subtle",
+                            size: "xs",
+                            onclick: move || editing.set(false),
+                            "Discard changes"
+                        }
+
+                        Button {
+                            variant: "filled",
+                            color: "indigo",
+                            size: "xs",
+                            onclick: move || on_save(),
+                            "Save"
+                        }
+                    }
+                }
+            } else {
+                Text {
+                    size: "sm",
+                    style: "margin-top: 4px; white-space: pre-wrap;",
+                    {crate::markdown::preview(&item.body, 280)}
+                }
+
+                Group {
+                    gap: "xs",
+                    style: "margin-top: 8px;",
+
+                    Button {
+                        variant: "subtle",
+                        size: "xs",
+                        onclick: move || editing.set(true),
+                        "Edit"
+                    }
+
+                    if is_local {
+                        Button {
+                            variant: "subtle",
+                            size: "xs",
+                            onclick: move || get_scheduled_store().send_now(&id.get()),
+                            "Send now"
+                        }
+                    }
+
+                    Button {
+                        variant: "subtle",
+                        color: "red",
+                        size: "xs",
+                        onclick: move || get_scheduled_store().cancel(&id.get()),
+                        "Cancel message"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Shown on launch when local scheduled messages came due while the app
+/// was closed. Nothing is sent without confirmation.
+#[component]
+pub fn missed_schedule_prompt() -> NodeHandle {
+    let store = get_scheduled_store();
+    let dismissed = Signal::new(false);
+
+    rsx! {
+        Modal {
+            opened_fn: move || !dismissed.get() && !get_scheduled_store().missed.get().is_empty(),
+            onclose: move || dismissed.set(true),
+            title: "Missed scheduled messages",
+
+            Stack {
+                gap: "md",
+
+                Text {
+                    size: "sm",
+                    {move || format!(
+                        "{} scheduled message(s) came due while Rorumall was closed. Send them now?",
+                        get_scheduled_store().missed.get().len(),
+                    )}
+                }
+
+                Group {
+                    justify: "flex-end",
+
+                    Button {
+                        variant: "subtle",
+                        onclick: move || dismissed.set(true),
+                        "Review later"
+                    }
+
+                    Button {
+                        variant: "light",
+                        color: "red",
+                        onclick: move || store.resolve_missed(false),
+                        "Discard"
+                    }
+
+                    Button {
+                        variant: "filled",
+                        color: "indigo",
+                        onclick: move || store.resolve_missed(true),
+                        "Send now"
+                    }
+                }
+            }
+        }
+    }
+}
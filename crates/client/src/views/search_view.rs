@@ -0,0 +1,339 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use rorumall_shared::MessageType;
+use crate::navigation::navigate_to_message;
+use crate::search::{SearchFilters, SearchHit};
+use crate::stores::{get_groups_store, get_search_store};
+
+#[component]
+pub fn search_view() -> NodeHandle {
+    let store = get_search_store();
+    let current = store.filters.get().clone();
+
+    let author = Signal::new(current.author.clone().unwrap_or_default());
+    let group_filter = Signal::new(current.group_id.clone().unwrap_or_default());
+    let channel_filter = Signal::new(current.channel_id.clone().unwrap_or_default());
+    let type_filter = Signal::new(match current.message_type {
+        Some(MessageType::Message) => "message".to_string(),
+        Some(MessageType::Memo) => "memo".to_string(),
+        Some(MessageType::Article) => "article".to_string(),
+        None => String::new(),
+    });
+    let attachment_filter = Signal::new(match current.has_attachment {
+        Some(true) => "yes".to_string(),
+        Some(false) => "no".to_string(),
+        None => String::new(),
+    });
+    let tag_filter = Signal::new(current.tag.clone().unwrap_or_default());
+    let from_date = Signal::new(current.from.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default());
+    let to_date = Signal::new(current.to.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default());
+    let date_error = Signal::new(None::<String>);
+
+    let apply_filters = move || {
+        let from = match parse_day(&from_date.get()) {
+            Ok(d) => d.map(|d| Utc.from_utc_datetime(&d.and_hms_opt(0, 0, 0).unwrap_or_default())),
+            Err(e) => {
+                date_error.set(Some(e));
+                return;
+            }
+        };
+        let to = match parse_day(&to_date.get()) {
+            Ok(d) => d.map(|d| Utc.from_utc_datetime(&d.and_hms_opt(23, 59, 59).unwrap_or_default())),
+            Err(e) => {
+                date_error.set(Some(e));
+                return;
+            }
+        };
+        date_error.set(None);
+
+        let non_empty = |s: String| if s.trim().is_empty() { None } else { Some(s.trim().to_string()) };
+        get_search_store().set_filters(SearchFilters {
+            author: non_empty(author.get().clone()),
+            group_id: non_empty(group_filter.get().clone()),
+            channel_id: non_empty(channel_filter.get().clone()),
+            message_type: match type_filter.get().as_str() {
+                "message" => Some(MessageType::Message),
+                "memo" => Some(MessageType::Memo),
+                "article" => Some(MessageType::Article),
+                _ => None,
+            },
+            has_attachment: match attachment_filter.get().as_str() {
+                "yes" => Some(true),
+                "no" => Some(false),
+                _ => None,
+            },
+            tag: crate::tags::normalize_tag(&tag_filter.get()),
+            from,
+            to,
+        });
+    };
+
+    let on_clear = move || {
+        author.set(String::new());
+        group_filter.set(String::new());
+        channel_filter.set(String::new());
+        type_filter.set(String::new());
+        attachment_filter.set(String::new());
+        tag_filter.set(String::new());
+        from_date.set(String::new());
+        to_date.set(String::new());
+        date_error.set(None);
+        get_search_store().set_filters(SearchFilters::default());
+    };
+
+    let groups = get_groups_store().joined_groups.get().clone();
+
+    rsx! {
+        div {
+            style: "flex: 1; display: flex; flex-direction: column; overflow: hidden;",
+
+            // Search header — matches channel header height
+            div {
+                style: "height: 52px; min-height: 52px; display: flex; align-items: center; padding: 0 20px; gap: 10px; border-bottom: 1px solid var(--rinch-color-dark-4, #373a40); flex-shrink: 0;",
+
+                {render_tabler_icon(__scope, TablerIcon::Search, TablerIconStyle::Outline)}
+
+                TextInput {
+                    placeholder: "Search messages, articles and attachments...",
+                    style: "flex: 1;",
+                    value_fn: move || get_search_store().query.get().clone(),
+                    oninput: move |val: String| get_search_store().set_query(val),
+                }
+            }
+
+            div {
+                style: "flex: 1; display: flex; overflow: hidden;",
+
+                // Filters
+                div {
+                    style: "width: 240px; padding: 16px; border-right: 1px solid var(--rinch-color-dark-4, #373a40); overflow-y: auto;",
+
+                    Stack {
+                        gap: "sm",
+
+                        TextInput {
+                            label: "Author",
+                            placeholder: "handle",
+                            value_fn: move || author.get().clone(),
+                            oninput: move |val: String| { author.set(val); apply_filters(); },
+                        }
+
+                        Select {
+                            label: "Group",
+                            value_fn: move || group_filter.get().clone(),
+                            onchange: move |val: String| {
+                                group_filter.set(val);
+                                channel_filter.set(String::new());
+                                apply_filters();
+                            },
+                            option { value: "", "Any group" }
+                            for g in groups.clone() {
+                                option { value: g.group_id.clone(), {g.name.clone()} }
+                            }
+                        }
+
+                        Select {
+                            label: "Channel",
+                            value_fn: move || channel_filter.get().clone(),
+                            onchange: move |val: String| { channel_filter.set(val); apply_filters(); },
+                            option { value: "", "Any channel" }
+                            for ch in store.index.get().channels().into_iter().filter(|c| group_filter.get().is_empty() || c.group_id == *group_filter.get()) {
+                                option { value: ch.channel_id.clone(), {format!("#{}", ch.channel_name)} }
+                            }
+                        }
+
+                        Select {
+                            label: "Type",
+                            value_fn: move || type_filter.get().clone(),
+                            onchange: move |val: String| { type_filter.set(val); apply_filters(); },
+                            option { value: "", "Any type" }
+                            option { value: "message", "Message" }
+                            option { value: "memo", "Memo" }
+                            option { value: "article", "Article" }
+                        }
+
+                        Select {
+                            label: "Attachments",
+                            value_fn: move || attachment_filter.get().clone(),
+                            onchange: move |val: String| { attachment_filter.set(val); apply_filters(); },
+                            option { value: "", "Any" }
+                            option { value: "yes", "Has attachment" }
+                            option { value: "no", "No attachment" }
+                        }
+
+                        TextInput {
+                            label: "Tag",
+                            placeholder: "#tag",
+                            value_fn: move || tag_filter.get().clone(),
+                            oninput: move |val: String| { tag_filter.set(val); apply_filters(); },
+                        }
+
+                        TextInput {
+                            label: "From",
+                            placeholder: "YYYY-MM-DD",
+                            value_fn: move || from_date.get().clone(),
+                            oninput: move |val: String| from_date.set(val),
+                            onsubmit: move || apply_filters(),
+                        }
+
+                        TextInput {
+                            label: "To",
+                            placeholder: "YYYY-MM-DD",
+                            value_fn: move || to_date.get().clone(),
+                            oninput: move |val: String| to_date.set(val),
+                            onsubmit: move || apply_filters(),
+                        }
+
+                        if date_error.get().is_some() {
+                            Alert {
+                                color: "red",
+                                variant: "light",
+                                {date_error.get().clone().unwrap_or_default()}
+                            }
+                        }
+
+                        Group {
+                            gap: "xs",
+
+                            Button {
+                                variant: "light",
+                                size: "xs",
+                                onclick: move || apply_filters(),
+                                "Apply"
+                            }
+
+                            Button {
+                                variant: "subtle",
+                                size: "xs",
+                                onclick: move || on_clear(),
+                                "Clear"
+                            }
+                        }
+                    }
+                }
+
+                // Results
+                div {
+                    style: "flex: 1; overflow-y: auto; padding: 16px;",
+
+                    Text {
+                        size: "xs",
+                        color: "dimmed",
+                        style: "margin-bottom: 8px;",
+                        {|| format!("{} results from {} indexed messages", get_search_store().results.get().len(), get_search_store().index.get().len())}
+                    }
+
+                    Stack {
+                        gap: "xs",
+
+                        for hit in store.results.get().clone() {
+                            div {
+                                key: hit.message.id.clone(),
+                                {search_result(__scope, hit)}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn search_result(hit: SearchHit) -> NodeHandle {
+    let user_display = hit.message.user_id.split('@').next().unwrap_or(&hit.message.user_id).to_string();
+    let time = hit.message.created_at.format("%b %d, %Y %H:%M").to_string();
+    let badge = match hit.message.message_type {
+        MessageType::Memo => Some(("Memo", "yellow")),
+        MessageType::Article => Some(("Article", "indigo")),
+        MessageType::Message => None,
+    };
+    let title = hit.message.title.clone();
+    let attachment_count = hit.message.attachments.len();
+    let location = hit.location.clone();
+    let message_id = hit.message.id.clone();
+
+    rsx! {
+        div {
+            style: "cursor: pointer;",
+            onclick: move || navigate_to_message(
+                location.host.clone(),
+                location.group_id.clone(),
+                location.channel_id.clone(),
+                message_id.clone(),
+            ),
+
+            Card {
+                shadow: "xs",
+                p: "sm",
+
+                Stack {
+                    gap: "xs",
+
+                    Group {
+                        gap: "xs",
+
+                        Text {
+                            size: "xs",
+                            color: "dimmed",
+                            {format!("#{}", hit.location.channel_name)}
+                        }
+
+                        Text {
+                            size: "sm",
+                            weight: "600",
+                            {user_display}
+                        }
+
+                        Text {
+                            size: "xs",
+                            color: "dimmed",
+                            {time}
+                        }
+
+                        if let Some((label, color)) = badge {
+                            Badge {
+                                variant: "light",
+                                color: color,
+                                size: "xs",
+                                {label}
+                            }
+                        }
+
+                        if attachment_count > 0 {
+                            Badge {
+                                variant: "outline",
+                                size: "xs",
+                                {format!("{} attachment(s)", attachment_count)}
+                            }
+                        }
+                    }
+
+                    if let Some(t) = title.clone() {
+                        Text {
+                            size: "sm",
+                            weight: "600",
+                            {t}
+                        }
+                    }
+
+                    Text {
+                        size: "sm",
+                        {hit.snippet.clone()}
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn parse_day(input: &str) -> Result<Option<NaiveDate>, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(trimmed, "%Y-%m-%d")
+        .map(Some)
+        .map_err(|_| format!("Invalid date \"{}\", expected YYYY-MM-DD", trimmed))
+}
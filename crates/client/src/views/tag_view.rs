@@ -0,0 +1,65 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use crate::navigation::{get_nav, AppRoute};
+use crate::search::SearchFilters;
+use crate::stores::get_search_store;
+use crate::views::search_view::search_result;
+
+/// Every locally known message in a group carrying a tag, newest first.
+/// Channels that have never been opened aren't cached, so they can't appear.
+#[component]
+pub fn tag_view() -> NodeHandle {
+    let nav = get_nav();
+    let (group_id, tag) = match nav.get().clone() {
+        AppRoute::Tag { group_id, tag, .. } => (group_id, tag),
+        _ => return rsx! { div { "Invalid route" } },
+    };
+
+    let filters = SearchFilters {
+        group_id: Some(group_id),
+        tag: Some(tag.clone()),
+        ..SearchFilters::default()
+    };
+    let filters = Signal::new(filters);
+    let hits = move || get_search_store().index.get().search("", &filters.get());
+
+    rsx! {
+        div {
+            style: "flex: 1; display: flex; flex-direction: column; overflow: hidden;",
+
+            // Header — matches channel header height
+            div {
+                style: "height: 52px; min-height: 52px; display: flex; align-items: center; padding: 0 20px; gap: 10px; border-bottom: 1px solid var(--rinch-color-dark-4, #373a40); flex-shrink: 0;",
+
+                {render_tabler_icon(__scope, TablerIcon::Tag, TablerIconStyle::Outline)}
+
+                Text {
+                    size: "md",
+                    weight: "600",
+                    {format!("#{}", tag)}
+                }
+
+                Text {
+                    size: "xs",
+                    color: "dimmed",
+                    {move || format!("{} messages in channels you've opened", hits().len())}
+                }
+            }
+
+            div {
+                style: "flex: 1; overflow-y: auto; padding: 16px;",
+
+                Stack {
+                    gap: "xs",
+
+                    for hit in hits() {
+                        div {
+                            key: hit.message.id.clone(),
+                            {search_result(__scope, hit)}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
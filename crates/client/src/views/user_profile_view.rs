@@ -0,0 +1,85 @@
+use rinch::prelude::*;
+use rinch_tabler_icons::{render_tabler_icon, TablerIcon, TablerIconStyle};
+use rorumall_shared::UserProfile;
+use crate::navigation::{get_nav, AppRoute};
+use crate::stores::{get_auth_store, get_profile_store};
+
+/// Someone's public profile, reached through a `users/<handle>` link.
+#[component]
+pub fn user_profile_view() -> NodeHandle {
+    let nav = get_nav();
+    let (host, handle) = match nav.get().clone() {
+        AppRoute::UserProfile { host, handle } => (host, handle),
+        _ => return rsx! { div { "Invalid route" } },
+    };
+
+    // `handle@domain` links name the provider in the handle itself
+    let (handle, host) = match handle.split_once('@') {
+        Some((name, domain)) => (name.to_string(), domain.to_string()),
+        None => (handle, host),
+    };
+    let domain = if host.is_empty() { get_auth_store().domain() } else { host.clone() };
+    let user_id = format!("{}@{}", handle, domain);
+
+    let profile = Signal::new(get_profile_store().get_cached(&handle, &domain));
+    let error = Signal::new(None::<String>);
+
+    {
+        let client = get_auth_store().make_client_for(&host);
+        let handle = handle.clone();
+        crate::runtime::spawn(
+            async move { client.get_user_profile(&handle).await },
+            move |result: Result<UserProfile, _>| match result {
+                Ok(found) => {
+                    get_profile_store().cache_profile(found.clone());
+                    profile.set(Some(found));
+                }
+                Err(e) => error.set(Some(format!("Couldn't load this profile: {}", e))),
+            },
+        );
+    }
+
+    rsx! {
+        div {
+            style: "flex: 1; display: flex; flex-direction: column; overflow: hidden;",
+
+            // Header — matches channel header height
+            div {
+                style: "height: 52px; min-height: 52px; display: flex; align-items: center; padding: 0 20px; gap: 10px; border-bottom: 1px solid var(--rinch-color-dark-4, #373a40); flex-shrink: 0;",
+
+                {render_tabler_icon(__scope, TablerIcon::User, TablerIconStyle::Outline)}
+
+                Text {
+                    size: "md",
+                    weight: "600",
+                    {format!("@{}", user_id)}
+                }
+            }
+
+            div {
+                style: "flex: 1; overflow-y: auto; padding: 24px;",
+
+                if let Some(err) = error.get().clone() {
+                    Alert {
+                        color: "red",
+                        variant: "light",
+                        {err}
+                    }
+                }
+
+                if let Some(p) = profile.get().clone() {
+                    {crate::components::profile::profile_card::profile_card(
+                        __scope,
+                        user_id.clone(),
+                        p.display_name.clone().unwrap_or_else(|| p.handle.clone()),
+                        p.bio.clone().unwrap_or_default(),
+                        p.avatar.clone().unwrap_or_default(),
+                        String::new(),
+                    )}
+                } else if error.get().is_none() {
+                    Loader {}
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use chrono::Utc;
 use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
 use futures_util::{SinkExt, StreamExt};
-use rorumall_shared::{ClientCommand, MessageType, ServerEvent, WsEnvelope};
+use rorumall_shared::{Attachment, ClientCommand, MessageReference, MessageType, ServerEvent, WsEnvelope};
 use std::sync::{Arc, RwLock};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
@@ -127,15 +127,7 @@ impl WsHandle {
     }
 
     pub fn send_message(&self, channel_id: &str, body: &str, nonce: &str) -> Result<(), String> {
-        self.send(ClientCommand::MessageCreate {
-            channel_id: channel_id.to_string(),
-            body: body.to_string(),
-            nonce: nonce.to_string(),
-            title: None,
-            message_type: None,
-            parent_id: None,
-            attachments: vec![],
-        })
+        self.send_message_with_options(channel_id, body, nonce, MessageOptions::default())
     }
 
     pub fn send_message_with_options(
@@ -143,18 +135,18 @@ impl WsHandle {
         channel_id: &str,
         body: &str,
         nonce: &str,
-        title: Option<String>,
-        message_type: Option<MessageType>,
-        attachments: Vec<rorumall_shared::Attachment>,
+        options: MessageOptions,
     ) -> Result<(), String> {
         self.send(ClientCommand::MessageCreate {
             channel_id: channel_id.to_string(),
             body: body.to_string(),
             nonce: nonce.to_string(),
-            title,
-            message_type,
+            title: options.title,
+            message_type: options.message_type,
             parent_id: None,
-            attachments,
+            attachments: options.attachments,
+            tags: options.tags,
+            reference: options.reference,
         })
     }
 
@@ -164,21 +156,33 @@ impl WsHandle {
         body: &str,
         nonce: &str,
         parent_id: &str,
-        message_type: Option<MessageType>,
-        attachments: Vec<rorumall_shared::Attachment>,
+        options: MessageOptions,
     ) -> Result<(), String> {
         self.send(ClientCommand::MessageCreate {
             channel_id: channel_id.to_string(),
             body: body.to_string(),
             nonce: nonce.to_string(),
-            title: None,
-            message_type,
+            title: options.title,
+            message_type: options.message_type,
             parent_id: Some(parent_id.to_string()),
-            attachments,
+            attachments: options.attachments,
+            tags: options.tags,
+            reference: options.reference,
         })
     }
 }
 
+/// Optional parts of a new message or reply.
+#[derive(Debug, Clone, Default)]
+pub struct MessageOptions {
+    pub title: Option<String>,
+    /// `None` sends a plain message.
+    pub message_type: Option<MessageType>,
+    pub attachments: Vec<Attachment>,
+    pub tags: Vec<String>,
+    pub reference: Option<MessageReference>,
+}
+
 pub struct WsConnection {
     pub host: String,
     pub state: SharedState<ConnectionState>,
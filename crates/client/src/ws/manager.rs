@@ -5,7 +5,7 @@ use rorumall_shared::{BaseMessage, Presence, ServerEvent, UserRef, WsEnvelope};
 
 use super::connection::{ConnectionState, WsConnection, WsHandle};
 use crate::client_keys::sign_ws_request;
-use crate::stores::{get_messages_store, get_presence_store, StoredMessage};
+use crate::stores::{get_messages_store, get_pins_store, get_presence_store, get_scheduled_store, StoredMessage};
 
 pub fn normalize_host(host: &str) -> String {
     host.trim_start_matches("http://")
@@ -14,7 +14,25 @@ pub fn normalize_host(host: &str) -> String {
         .to_string()
 }
 
-fn extract_user_id(user_ref: &UserRef) -> String {
+fn to_stored_message(message: &BaseMessage) -> StoredMessage {
+    StoredMessage {
+        id: message.id.clone(),
+        user_id: extract_user_id(&message.author),
+        title: message.title.clone(),
+        content: message.content.text.clone(),
+        mime: Some(message.content.mime.clone()),
+        message_type: message.r#type.clone(),
+        created_at: message.created_at,
+        parent_id: message.parent_id.clone(),
+        parent_message_type: message.parent_message_type.clone(),
+        attachments: message.attachments.clone(),
+        tags: message.tags.clone(),
+        reference: message.reference.clone(),
+        reactions: Vec::new(),
+    }
+}
+
+pub(crate) fn extract_user_id(user_ref: &UserRef) -> String {
     match user_ref {
         UserRef::Handle(h) => h.to_string(),
         UserRef::Uri(u) => {
@@ -161,17 +179,7 @@ pub fn connect_to_host(
                 channel_id,
                 message,
             } => {
-                let stored = StoredMessage {
-                    id: message.id.clone(),
-                    user_id: extract_user_id(&message.author),
-                    title: message.title.clone(),
-                    content: message.content.text.clone(),
-                    message_type: message.r#type.clone(),
-                    created_at: message.created_at,
-                    parent_id: message.parent_id.clone(),
-                    parent_message_type: message.parent_message_type.clone(),
-                    attachments: message.attachments.clone(),
-                };
+                let stored = to_stored_message(&message);
 
                 let _is_own_message = stored.user_id == user_id_for_event;
 
@@ -179,6 +187,42 @@ pub fn connect_to_host(
                     get_messages_store().add_message(&channel_id, stored);
                 });
             }
+            ServerEvent::MessageUpdate {
+                channel_id,
+                message,
+            } => {
+                let stored = to_stored_message(&message);
+                rinch::run_on_main_thread(move || {
+                    get_messages_store().update_message(&channel_id, stored);
+                });
+            }
+            ServerEvent::MessagePin { channel_id, pin } => {
+                rinch::run_on_main_thread(move || {
+                    get_pins_store().add_pin(&channel_id, pin);
+                });
+            }
+            ServerEvent::MessageUnpin {
+                channel_id,
+                message_id,
+            } => {
+                rinch::run_on_main_thread(move || {
+                    get_pins_store().remove_pin(&channel_id, &message_id);
+                });
+            }
+            ServerEvent::ReactionAdd { channel_id, reaction } => {
+                rinch::run_on_main_thread(move || {
+                    get_messages_store().add_reaction(&channel_id, reaction);
+                });
+            }
+            ServerEvent::ReactionRemove {
+                channel_id,
+                message_id,
+                reaction_id,
+            } => {
+                rinch::run_on_main_thread(move || {
+                    get_messages_store().remove_reaction(&channel_id, &message_id, &reaction_id);
+                });
+            }
             ServerEvent::PresenceUpdate {
                 user_handle,
                 user_domain,
@@ -195,6 +239,9 @@ pub fn connect_to_host(
                     nonce,
                     message_id
                 );
+                rinch::run_on_main_thread(move || {
+                    get_scheduled_store().acknowledge(&nonce);
+                });
             }
             ServerEvent::Error {
                 code,
@@ -1,7 +1,9 @@
 pub mod error;
 pub mod models;
+pub mod permissions;
 pub mod protocol;
 
 pub use error::*;
 pub use models::*;
+pub use permissions::*;
 pub use protocol::*;
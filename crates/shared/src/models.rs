@@ -166,9 +166,50 @@ pub struct Upload {
 #[serde(rename_all = "camelCase")]
 pub struct Attachment {
     pub id: String,
+    /// Original file name. Older providers leave it out.
+    #[serde(default)]
+    pub filename: String,
     pub mime: String,
     pub url: String,
     pub size: u64,
+    /// Pixel size of images, so receivers can reserve space before loading.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// A small preview of an image, uploaded alongside it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+}
+
+/// Start a chunked upload of `size` bytes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUploadSessionRequest {
+    pub filename: String,
+    pub mime: String,
+    pub size: u64,
+}
+
+/// A chunked upload in progress. The next chunk starts at `offset`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadSession {
+    pub id: String,
+    pub offset: u64,
+    pub size: u64,
+    /// Largest chunk the provider accepts in one request.
+    pub chunk_size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+}
+
+/// Finish an upload session. Each chunk was signed on its own; the hex
+/// SHA-256 of the whole file lets the provider check the assembled result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompleteUploadSessionRequest {
+    pub sha256: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -186,12 +227,30 @@ pub struct Content {
     pub mime: String,
 }
 
+/// `MessageReference::type` for a message quoted in a new one.
+pub const REFERENCE_QUOTE: &str = "quote";
+/// `MessageReference::type` for a message forwarded from elsewhere.
+pub const REFERENCE_FORWARD: &str = "forward";
+
+/// Points at another message. Quotes and forwards also carry where the
+/// original lives and a snapshot of it, since readers may not be able to
+/// load a message from another channel or host.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MessageReference {
     #[serde(rename = "type")]
     pub r#type: String,
     pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excerpt: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -243,6 +302,18 @@ pub struct Reaction {
     pub metadata: Metadata,
 }
 
+/// React to a message. Built-in emoji use the emoji itself as `key` and
+/// `unicode`; custom group emoji use `GroupEmoji::reaction_key` and `image`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AddReactionRequest {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unicode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(untagged)]
 pub enum TimelineItem {
@@ -329,6 +400,13 @@ pub struct Capabilities {
     pub metadata_schemas: Vec<MetadataSchemaInfo>,
     #[serde(default)]
     pub limits: Option<Limits>,
+    /// The provider can hold messages and post them at a later time.
+    #[serde(default)]
+    pub scheduled_messages: bool,
+    /// The provider accepts uploads in chunks through upload sessions,
+    /// which can be resumed after a dropped connection.
+    #[serde(default)]
+    pub resumable_uploads: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -362,6 +440,7 @@ pub struct WsEnvelope<T> {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "data", rename_all = "camelCase")]
+#[allow(clippy::large_enum_variant)]
 pub enum ClientCommand {
     Subscribe {
         channel_id: String,
@@ -382,6 +461,10 @@ pub enum ClientCommand {
         parent_id: Option<String>,
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         attachments: Vec<Attachment>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reference: Option<MessageReference>,
     },
 }
 
@@ -394,6 +477,32 @@ pub enum ServerEvent {
         channel_id: String,
         message: BaseMessage,
     },
+    #[serde(rename = "message.update")]
+    MessageUpdate {
+        channel_id: String,
+        message: BaseMessage,
+    },
+    #[serde(rename = "message.pin")]
+    MessagePin {
+        channel_id: String,
+        pin: ChannelPin,
+    },
+    #[serde(rename = "message.unpin")]
+    MessageUnpin {
+        channel_id: String,
+        message_id: String,
+    },
+    #[serde(rename = "reaction.add")]
+    ReactionAdd {
+        channel_id: String,
+        reaction: Reaction,
+    },
+    #[serde(rename = "reaction.remove")]
+    ReactionRemove {
+        channel_id: String,
+        message_id: String,
+        reaction_id: String,
+    },
     #[serde(rename = "presence.update")]
     PresenceUpdate {
         user_handle: String,
@@ -443,6 +552,85 @@ fn default_join_policy() -> String {
     "open".to_string()
 }
 
+// --- Joining Groups ---
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinGroupRequest {
+    /// Note for the admins of a group that approves members.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Code of an invite link, which lets the user in whatever the join policy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invite_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JoinStatus {
+    /// The user is a member now.
+    Joined,
+    /// The request waits for an admin to approve it.
+    Pending,
+    /// An admin turned the request down.
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinGroupResponse {
+    pub status: JoinStatus,
+}
+
+// --- Invites ---
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupInvite {
+    pub code: String,
+    pub group_id: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// How many times the link can be used; unlimited when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_uses: Option<u32>,
+    #[serde(default)]
+    pub uses: u32,
+}
+
+impl GroupInvite {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+
+    pub fn is_used_up(&self) -> bool {
+        self.max_uses.is_some_and(|max| self.uses >= max)
+    }
+
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        !self.is_expired(now) && !self.is_used_up()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateInviteRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_uses: Option<u32>,
+}
+
+/// What an invite code leads to, shown before accepting it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InvitePreview {
+    pub invite: GroupInvite,
+    pub group: Group,
+}
+
 // --- Group Members ---
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -546,6 +734,18 @@ pub enum GroupInvitePermission {
     Member,
 }
 
+impl GroupInvitePermission {
+    /// Whether a member holding `roles` may invite people. Every role above
+    /// the required one may too.
+    pub fn allows(&self, roles: &[String]) -> bool {
+        match get_base_role(roles) {
+            "owner" => true,
+            "admin" => *self != Self::Owner,
+            _ => *self == Self::Member,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupPrivacySettings {
@@ -843,6 +1043,9 @@ pub struct ChannelMessage {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
     pub body: String,
+    /// Mime type of `body`, e.g. `text/markdown` or `text/plain`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub message_type: Option<MessageType>,
     pub created_at: String,
@@ -852,6 +1055,111 @@ pub struct ChannelMessage {
     pub parent_message_type: Option<MessageType>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub attachments: Vec<Attachment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<MessageReference>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reactions: Vec<Reaction>,
+}
+
+/// Edit a message the caller authored. Omitted fields are left unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMessageRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<Attachment>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+/// A message pinned to the top of a channel.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelPin {
+    pub message_id: String,
+    pub pinned_by: String,
+    pub pinned_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListPinsResponse {
+    pub pins: Vec<ChannelPin>,
+}
+
+/// A group's custom emoji, used in messages as `:shortcode:` and in reactions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupEmoji {
+    pub shortcode: String,
+    pub url: String,
+    #[serde(default)]
+    pub created_by: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+impl GroupEmoji {
+    /// `Reaction::key` for a reaction with this emoji; its `image` is `url`.
+    pub fn reaction_key(&self) -> String {
+        format!(":{}:", self.shortcode)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListGroupEmojiResponse {
+    pub emoji: Vec<GroupEmoji>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGroupEmojiRequest {
+    pub shortcode: String,
+    pub url: String,
+}
+
+/// A message for the provider to post at `send_at`. Only sent to providers
+/// advertising `Capabilities::scheduled_messages`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleMessageRequest {
+    pub body: String,
+    pub nonce: String,
+    pub send_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_type: Option<MessageType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<MessageReference>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledMessageResponse {
+    pub id: String,
+    pub send_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateScheduledMessageRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub send_at: Option<DateTime<Utc>>,
 }
 
 // --- Users ---
@@ -0,0 +1,93 @@
+//! Client-side evaluation of channel permissions.
+//!
+//! The server stays authoritative; this only decides what a client should
+//! offer, so a read-only channel doesn't present a composer that will fail.
+
+use crate::models::{ChannelSettings, GroupRole, MessageType};
+
+/// Permission target that matches every member of the group.
+pub const EVERYONE: &str = "@everyone";
+
+/// Base roles that can always view and post, so a misconfigured channel
+/// can't lock out the people who manage it.
+const MANAGER_ROLES: [&str; 2] = ["owner", "admin"];
+
+/// What the current member may do in a channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelAccess {
+    pub can_view: bool,
+    pub can_send: bool,
+    /// Types allowed for new top-level posts; empty when sending is denied.
+    pub root_types: Vec<MessageType>,
+    /// Types allowed for replies; empty when sending is denied.
+    pub reply_types: Vec<MessageType>,
+}
+
+impl ChannelAccess {
+    /// Evaluate `settings` for a member holding `roles` (base role names and
+    /// custom role ids). `group_roles` resolves targets written as role names.
+    pub fn evaluate(settings: &ChannelSettings, roles: &[String], group_roles: &[GroupRole]) -> Self {
+        let is_manager = roles.iter().any(|r| MANAGER_ROLES.contains(&r.as_str()));
+        let allowed = |targets: &[String]| {
+            is_manager || targets.iter().any(|t| matches_target(t, roles, group_roles))
+        };
+
+        let can_view = allowed(&settings.permissions.view);
+        let can_send = can_view && allowed(&settings.permissions.send);
+        let types = |types: &[MessageType]| if can_send { types.to_vec() } else { Vec::new() };
+
+        Self {
+            can_view,
+            can_send,
+            root_types: types(&settings.message_types.root_types),
+            reply_types: types(&settings.message_types.reply_types),
+        }
+    }
+
+    /// Types allowed for a new post, or for a reply when `is_reply`.
+    pub fn allowed_types(&self, is_reply: bool) -> &[MessageType] {
+        if is_reply {
+            &self.reply_types
+        } else {
+            &self.root_types
+        }
+    }
+
+    pub fn allows(&self, message_type: &MessageType, is_reply: bool) -> bool {
+        self.allowed_types(is_reply).contains(message_type)
+    }
+
+    /// Why nothing can be posted here, if that's the case.
+    pub fn read_only_reason(&self) -> Option<&'static str> {
+        if !self.can_view {
+            Some("You don't have permission to view this channel")
+        } else if !self.can_send {
+            Some("This channel is read-only for your roles")
+        } else if self.root_types.is_empty() && self.reply_types.is_empty() {
+            Some("This channel doesn't accept new posts")
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for ChannelAccess {
+    fn default() -> Self {
+        Self::evaluate(&ChannelSettings::default(), &[], &[])
+    }
+}
+
+/// Whether a permission target (`@everyone`, a base role such as `@admin`,
+/// or a custom role id or name) applies to a member holding `roles`.
+pub fn matches_target(target: &str, roles: &[String], group_roles: &[GroupRole]) -> bool {
+    if target == EVERYONE {
+        return true;
+    }
+    let name = target.strip_prefix('@').unwrap_or(target);
+    roles.iter().any(|held| {
+        held == name
+            || group_roles
+                .iter()
+                .any(|r| r.id == *held && r.name.eq_ignore_ascii_case(name))
+    })
+}